@@ -0,0 +1,485 @@
+//! `requestor`, which runs requests, `.http` files and saved collections headlessly so CI can
+//! check the same requests that are built in the app.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use requestor_core::assertions::{Assertion, AssertionKind};
+use requestor_core::bench::{self, BenchOptions, BenchReport};
+use requestor_core::body::RequestBody;
+use requestor_core::collection::{self, Collection, CollectionRequest};
+use requestor_core::data::DataRow;
+use requestor_core::environment::{self, Environment};
+use requestor_core::runner::{self, Run, RunOptions, RunResult};
+use requestor_core::{data, db, http_file, report, KeyValue, RequestArgs, ResponseData};
+
+/// Width of the longest bar of a benchmark's latency histogram, in characters.
+const HISTOGRAM_WIDTH: u64 = 40;
+
+#[derive(Parser)]
+#[command(name = "requestor", version, about)]
+struct Cli {
+    /// Database holding the app's environments and collections.
+    #[arg(long, global = true, default_value = "requests.db")]
+    db: PathBuf,
+    /// Saved environment to use instead of the active one.
+    #[arg(short, long, global = true)]
+    env: Option<String>,
+    /// JSON object of variables to use instead of a saved environment.
+    #[arg(long, global = true, conflicts_with = "env")]
+    env_file: Option<PathBuf>,
+    /// Sets a variable, overriding the environment.
+    #[arg(long = "var", value_name = "NAME=VALUE", global = true, value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    /// Writes a JUnit XML report to this file.
+    #[arg(long, global = true)]
+    junit: Option<PathBuf>,
+    /// Writes a JSON report to this file.
+    #[arg(long, global = true)]
+    json: Option<PathBuf>,
+    /// Writes the data file rows with a failing request to this file, as JSON that `--data`
+    /// reads to run just those rows again.
+    #[arg(long, global = true)]
+    failed_rows: Option<PathBuf>,
+    /// Prints the headers and body of every response.
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Sends a single request and prints its response.
+    Request {
+        #[command(flatten)]
+        request: RequestSpec,
+        /// Expected status, e.g. 200, 2xx or 200-299.
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Sends a request repeatedly and reports throughput and latency percentiles.
+    Bench {
+        #[command(flatten)]
+        request: RequestSpec,
+        /// Number of requests to send.
+        #[arg(short = 'n', long, required_unless_present = "duration")]
+        requests: Option<u64>,
+        /// How long to keep sending requests, in seconds.
+        #[arg(long)]
+        duration: Option<f64>,
+        /// Number of requests in flight at once.
+        #[arg(short, long, default_value_t = 1)]
+        concurrency: u32,
+        /// Requests started per second, as fast as possible when unset.
+        #[arg(long)]
+        rate: Option<f64>,
+    },
+    /// Runs the requests of a `.http` file in order.
+    File {
+        path: PathBuf,
+        /// Only runs the request with this name.
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        run: RunArgs,
+    },
+    /// Runs a saved collection, given its name or id.
+    Collection {
+        collection: String,
+        /// Only runs the requests in this folder and its subfolders.
+        #[arg(long, default_value = "")]
+        folder: String,
+        /// Only runs the request with this name.
+        #[arg(long, default_value = "")]
+        request: String,
+        #[command(flatten)]
+        run: RunArgs,
+    },
+}
+
+#[derive(Args)]
+struct RequestSpec {
+    method: String,
+    url: String,
+    /// Header to send, as `Name: value`.
+    #[arg(short = 'H', long = "header")]
+    headers: Vec<String>,
+    /// Body to send.
+    #[arg(short, long)]
+    data: Option<String>,
+}
+
+impl RequestSpec {
+    fn args(&self) -> Result<RequestArgs, String> {
+        let mut args = RequestArgs {
+            method: self.method.to_ascii_uppercase().parse()?,
+            url: self.url.clone(),
+            ..Default::default()
+        };
+        for header in &self.headers {
+            let (key, value) = header
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid header {header:?}, use Name: value"))?;
+            args.headers.push(KeyValue {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            });
+        }
+        if let Some(text) = &self.data {
+            args.body = RequestBody::Raw {
+                content_type: String::new(),
+                text: text.clone(),
+            };
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// How many times the requests are run.
+    #[arg(long, default_value_t = 1)]
+    iterations: u32,
+    /// Pause between requests, in milliseconds.
+    #[arg(long, default_value_t = 0)]
+    delay: u64,
+    /// Stops at the first request that fails.
+    #[arg(long)]
+    stop_on_failure: bool,
+    /// CSV or JSON file with a row per iteration, its columns are set as variables.
+    #[arg(long, conflicts_with = "iterations")]
+    data: Option<PathBuf>,
+    /// Saves the responses that differ from their snapshots, or have none, as the new ones.
+    #[arg(long)]
+    update_snapshots: bool,
+}
+
+impl RunArgs {
+    fn options(&self, folder: String, request: String) -> Result<RunOptions, String> {
+        Ok(RunOptions {
+            folder,
+            request,
+            delay_ms: self.delay,
+            stop_on_failure: self.stop_on_failure,
+            iterations: self.iterations,
+            data: match &self.data {
+                Some(path) => data::load(path)?,
+                None => Vec::new(),
+            },
+            update_snapshots: self.update_snapshots,
+        })
+    }
+}
+
+fn parse_variable(text: &str) -> Result<(String, String), String> {
+    let (name, value) = text
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid variable {text:?}, use NAME=VALUE"))?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// The environment requests are run against. It's kept in memory, so variables set by scripts
+/// and extractors aren't saved to the database.
+fn environment(cli: &Cli, file_variables: Vec<(String, String)>) -> Result<Environment, String> {
+    let mut env = match (&cli.env, &cli.env_file) {
+        (_, Some(path)) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            let values: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&text)
+                .map_err(|e| format!("{} isn't a JSON object: {e}", path.display()))?;
+            Environment {
+                name: path.display().to_string(),
+                variables: values
+                    .into_iter()
+                    .map(|(key, value)| KeyValue {
+                        key,
+                        value: match value {
+                            serde_json::Value::String(text) => text,
+                            other => other.to_string(),
+                        },
+                    })
+                    .collect(),
+            }
+        }
+        (name, None) => {
+            let saved = environment::get_environments()?;
+            let name = name.as_ref().or(saved.active.as_ref());
+            match name {
+                Some(name) => saved
+                    .environments
+                    .into_iter()
+                    .find(|env| &env.name == name)
+                    .ok_or_else(|| format!("No environment named {name}"))?,
+                None => Environment::default(),
+            }
+        }
+    };
+
+    // File variables are defaults the environment overrides, `--var` overrides both.
+    for (key, value) in file_variables {
+        if !env.variables.iter().any(|kv| kv.key == key) {
+            env.variables.push(KeyValue { key, value });
+        }
+    }
+    for (key, value) in &cli.variables {
+        env.variables.retain(|kv| &kv.key != key);
+        env.variables.push(KeyValue {
+            key: key.clone(),
+            value: value.clone(),
+        });
+    }
+    Ok(env)
+}
+
+fn single_request(request: &RequestSpec, status: &Option<String>) -> Result<Collection, String> {
+    let mut args = request.args()?;
+    if let Some(expected) = status {
+        args.assertions.push(Assertion {
+            kind: AssertionKind::Status,
+            target: String::new(),
+            expected: expected.clone(),
+            enabled: true,
+        });
+    }
+    Ok(Collection {
+        id: None,
+        name: "request".to_string(),
+        requests: vec![CollectionRequest {
+            name: format!("{} {}", args.method.as_str(), args.url),
+            folder: String::new(),
+            request: args,
+        }],
+    })
+}
+
+fn find_collection(name: &str) -> Result<Collection, String> {
+    collection::get_collections()?
+        .into_iter()
+        .find(|c| c.name == name || c.id.is_some_and(|id| id.to_string() == name))
+        .ok_or_else(|| format!("No collection named {name}"))
+}
+
+fn print_result(result: &RunResult, outcome: &Result<ResponseData, String>, verbose: bool) {
+    let mark = if result.passed() { "✓" } else { "✗" };
+    println!(
+        "{mark} {}  {} {} → {} ({:.2} ms)",
+        result.name, result.method, result.url, result.status, result.response_time
+    );
+    for test in result.tests.iter().filter(|t| !t.passed) {
+        match test.message.as_str() {
+            "" => println!("    ✗ {}", test.name),
+            message => println!("    ✗ {}: {message}", test.name),
+        }
+    }
+    if let Some(snapshot) = &result.snapshot {
+        if snapshot.updated {
+            println!("    snapshot updated");
+        }
+        let mark = if snapshot.updated { " " } else { "✗" };
+        for change in snapshot.describe() {
+            println!("    {mark} snapshot {change}");
+        }
+    }
+    match outcome {
+        Err(e) => println!("    {e}"),
+        Ok(response) if verbose => {
+            for header in &response.headers {
+                println!("    {}: {}", header.key, header.value);
+            }
+            println!();
+            println!("{}", response.body);
+            for line in &response.console {
+                println!("    > {line}");
+            }
+        }
+        Ok(_) => {}
+    }
+}
+
+/// Heading for the results of an iteration, with the data file row it used.
+fn iteration_heading(iteration: u32, data: &[DataRow]) -> String {
+    match data.get(iteration as usize - 1) {
+        Some(row) => {
+            let columns: Vec<String> = row.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            format!("\nIteration {iteration}: {}", columns.join(", "))
+        }
+        None => format!("\nIteration {iteration}"),
+    }
+}
+
+fn summary(run: &Run) -> String {
+    let summary = &run.summary;
+    let stopped = if summary.stopped {
+        ", stopped early"
+    } else {
+        ""
+    };
+    format!(
+        "{}/{} requests and {}/{} assertions passed, {:.2} ms total, {:.2} ms average{stopped}",
+        summary.requests - summary.failed_requests,
+        summary.requests,
+        summary.assertions - summary.failed_assertions,
+        summary.assertions,
+        summary.total_time,
+        summary.average_time
+    )
+}
+
+fn write_reports(cli: &Cli, run: &Run) -> Result<(), String> {
+    if let Some(path) = &cli.junit {
+        std::fs::write(path, report::junit(run))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    if let Some(path) = &cli.json {
+        std::fs::write(path, report::json(run)?)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    if let Some(path) = &cli.failed_rows {
+        let rows = serde_json::to_string_pretty(&run.failed_rows()).map_err(|e| e.to_string())?;
+        std::fs::write(path, rows)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn print_bench(report: &BenchReport) {
+    println!(
+        "{} requests in {:.2} s, {:.2} requests/s, {} failed{}",
+        report.requests,
+        report.duration / 1000.0,
+        report.throughput,
+        report.failed,
+        if report.cancelled { ", cancelled" } else { "" }
+    );
+    for status in &report.statuses {
+        println!("  {}: {}", status.status, status.count);
+    }
+    for error in &report.errors {
+        println!("  {}: {}", error.error, error.count);
+    }
+
+    let latency = &report.latency;
+    println!("\nLatency (ms)");
+    for (name, value) in [
+        ("min", latency.min),
+        ("mean", latency.mean),
+        ("p50", latency.p50),
+        ("p90", latency.p90),
+        ("p99", latency.p99),
+        ("max", latency.max),
+    ] {
+        println!("  {name:<5}{value:>10.2}");
+    }
+
+    let most = report.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    if most > 0 {
+        println!();
+    }
+    for bucket in &report.histogram {
+        let bar = "█".repeat((bucket.count * HISTOGRAM_WIDTH / most) as usize);
+        println!(
+            "  {:>10.2} - {:<10.2} {:>8} {bar}",
+            bucket.start, bucket.end, bucket.count
+        );
+    }
+}
+
+async fn bench(cli: &Cli, args: RequestArgs, options: BenchOptions) -> Result<bool, String> {
+    let report = bench::run(args, &options, |_| {}, interrupted()).await?;
+    print_bench(&report);
+    if let Some(path) = &cli.json {
+        let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+        std::fs::write(path, json)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(report.failed == 0 && !report.cancelled)
+}
+
+/// Resolves when the user presses Ctrl+C.
+async fn interrupted() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Runs the command, returning whether everything passed.
+async fn run(cli: &Cli) -> Result<bool, String> {
+    db::set_path(cli.db.clone());
+
+    let (collection, options, file_variables) = match &cli.command {
+        Command::Request { request, status } => (
+            single_request(request, status)?,
+            RunOptions::default(),
+            Vec::new(),
+        ),
+        Command::Bench {
+            request,
+            requests,
+            duration,
+            concurrency,
+            rate,
+        } => {
+            environment::use_environment(environment(cli, Vec::new())?);
+            let options = BenchOptions {
+                requests: *requests,
+                duration_ms: duration.map(|secs| (secs * 1000.0) as u64),
+                concurrency: *concurrency,
+                rate: *rate,
+            };
+            return bench(cli, request.args()?, options).await;
+        }
+        Command::File { path, name, run } => {
+            let file = http_file::load(path)?;
+            let name = name.clone().unwrap_or_default();
+            if !name.is_empty() && !file.collection.requests.iter().any(|r| r.name == name) {
+                return Err(format!("No request named {name} in {}", path.display()));
+            }
+            (
+                file.collection,
+                run.options(String::new(), name)?,
+                file.variables,
+            )
+        }
+        Command::Collection {
+            collection,
+            folder,
+            request,
+            run,
+        } => (
+            find_collection(collection)?,
+            run.options(folder.clone(), request.clone())?,
+            Vec::new(),
+        ),
+    };
+    environment::use_environment(environment(cli, file_variables)?);
+
+    // A single request always prints its response.
+    let verbose = cli.verbose || matches!(cli.command, Command::Request { .. });
+    let grouped = options.iterations > 1 || !options.data.is_empty();
+    let mut iteration = 0;
+    let on_result = |result: &RunResult, outcome: &Result<ResponseData, String>| {
+        if grouped && result.iteration != iteration {
+            iteration = result.iteration;
+            println!("{}", iteration_heading(iteration, &options.data));
+        }
+        print_result(result, outcome, verbose);
+    };
+    let run = runner::run(&collection, &options, on_result, interrupted()).await;
+
+    println!("\n{}", summary(&run));
+    write_reports(cli, &run)?;
+    Ok(run.summary.failed_requests == 0 && !run.summary.stopped)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+use crate::{KeyValue, ResponseData};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionKind {
+    /// `expected` is a code (`200`), a class (`2xx`) or a range (`200-299`).
+    Status,
+    HeaderPresent,
+    HeaderEquals,
+    HeaderMatches,
+    JsonPathEquals,
+    /// The value is a string or array containing `expected`, or an object with that key.
+    JsonPathContains,
+    /// `expected` is one of `null`, `boolean`, `number`, `string`, `array` or `object`.
+    JsonPathType,
+    BodyContains,
+    BodyMatches,
+    /// `expected` is the limit in milliseconds.
+    ResponseTimeBelow,
+    /// `expected` is the JSON Schema the body must validate against.
+    JsonSchema,
+}
+
+/// A check run against the response of a request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Assertion {
+    pub kind: AssertionKind,
+    /// The header name or JSONPath the assertion looks at, if any.
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub expected: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub name: String,
+    pub passed: bool,
+    /// What was found instead, or why the assertion couldn't be evaluated.
+    pub message: String,
+}
+
+impl Assertion {
+    fn name(&self) -> String {
+        let (target, expected) = (self.target.trim(), self.expected.trim());
+        match self.kind {
+            AssertionKind::Status => format!("Status is {expected}"),
+            AssertionKind::HeaderPresent => format!("Header {target} is present"),
+            AssertionKind::HeaderEquals => format!("Header {target} equals {expected}"),
+            AssertionKind::HeaderMatches => format!("Header {target} matches {expected}"),
+            AssertionKind::JsonPathEquals => format!("{target} equals {expected}"),
+            AssertionKind::JsonPathContains => format!("{target} contains {expected}"),
+            AssertionKind::JsonPathType => format!("{target} is {expected}"),
+            AssertionKind::BodyContains => format!("Body contains {expected}"),
+            AssertionKind::BodyMatches => format!("Body matches {expected}"),
+            AssertionKind::ResponseTimeBelow => format!("Response time below {expected} ms"),
+            AssertionKind::JsonSchema => "Body matches the JSON Schema".to_string(),
+        }
+    }
+
+    /// Checks the response, `Err` describes why the assertion failed.
+    fn check(&self, response: &ResponseData) -> Result<(), String> {
+        let (target, expected) = (self.target.trim(), self.expected.trim());
+        match self.kind {
+            AssertionKind::Status => check_status(response.status, expected),
+            AssertionKind::HeaderPresent => header(&response.headers, target).map(|_| ()),
+            AssertionKind::HeaderEquals => {
+                let value = header(&response.headers, target)?;
+                expect(value == expected, || format!("Was {value}"))
+            }
+            AssertionKind::HeaderMatches => {
+                let value = header(&response.headers, target)?;
+                let pattern = Regex::new(expected).map_err(|e| e.to_string())?;
+                expect(pattern.is_match(value), || format!("Was {value}"))
+            }
+            AssertionKind::JsonPathEquals => {
+                let actual = json_path(&response.body, target)?;
+                let wanted = expected_json(expected);
+                expect(actual == wanted, || format!("Was {actual}"))
+            }
+            AssertionKind::JsonPathContains => {
+                let actual = json_path(&response.body, target)?;
+                let wanted = expected_json(expected);
+                let contains = match (&actual, &wanted) {
+                    (Value::String(s), Value::String(part)) => s.contains(part.as_str()),
+                    (Value::Array(items), _) => items.contains(&wanted),
+                    (Value::Object(map), Value::String(key)) => map.contains_key(key),
+                    _ => false,
+                };
+                expect(contains, || format!("Was {actual}"))
+            }
+            AssertionKind::JsonPathType => {
+                let actual = json_type(&json_path(&response.body, target)?);
+                expect(actual == expected.to_ascii_lowercase(), || {
+                    format!("Was {actual}")
+                })
+            }
+            AssertionKind::BodyContains => {
+                expect(response.body.contains(expected), || "Not found".to_string())
+            }
+            AssertionKind::BodyMatches => {
+                let pattern = Regex::new(expected).map_err(|e| e.to_string())?;
+                expect(pattern.is_match(&response.body), || "No match".to_string())
+            }
+            AssertionKind::ResponseTimeBelow => {
+                let limit: f32 = expected
+                    .parse()
+                    .map_err(|_| format!("Invalid limit {expected:?}"))?;
+                expect(response.response_time < limit, || {
+                    format!("Took {:.2} ms", response.response_time)
+                })
+            }
+            AssertionKind::JsonSchema => check_schema(&response.body, expected),
+        }
+    }
+}
+
+fn expect(passed: bool, failure: impl FnOnce() -> String) -> Result<(), String> {
+    match passed {
+        true => Ok(()),
+        false => Err(failure()),
+    }
+}
+
+fn check_status(status: u16, expected: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid status {expected:?}, use e.g. 200, 2xx or 200-299");
+    let (min, max) = if let Some(class) = expected.to_ascii_lowercase().strip_suffix("xx") {
+        let class: u16 = class.parse().map_err(|_| invalid())?;
+        if !matches!(class, 1..=5) {
+            return Err(invalid());
+        }
+        (class * 100, class * 100 + 99)
+    } else if let Some((min, max)) = expected.split_once('-') {
+        let min = min.trim().parse().map_err(|_| invalid())?;
+        let max = max.trim().parse().map_err(|_| invalid())?;
+        if min > max {
+            return Err(invalid());
+        }
+        (min, max)
+    } else {
+        let code = expected.parse().map_err(|_| invalid())?;
+        (code, code)
+    };
+    expect((min..=max).contains(&status), || format!("Was {status}"))
+}
+
+pub fn header<'a>(headers: &'a [KeyValue], name: &str) -> Result<&'a str, String> {
+    headers
+        .iter()
+        .find(|h| h.key.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+        .ok_or_else(|| format!("No {name} header"))
+}
+
+fn parse_body(body: &str) -> Result<Value, String> {
+    serde_json::from_str(body).map_err(|e| format!("Body isn't JSON: {e}"))
+}
+
+/// The single value `path` selects in the body.
+pub fn json_path(body: &str, path: &str) -> Result<Value, String> {
+    let path = JsonPath::parse(path).map_err(|e| e.to_string())?;
+    let body = parse_body(body)?;
+    let found = path.query(&body).all();
+    match found.as_slice() {
+        [] => Err("Nothing found".to_string()),
+        [value] => Ok((*value).clone()),
+        values => Ok(Value::Array(values.iter().map(|v| (*v).clone()).collect())),
+    }
+}
+
+/// Expected values are JSON, falling back to a plain string so `abc` needn't be quoted.
+fn expected_json(expected: &str) -> Value {
+    serde_json::from_str(expected).unwrap_or_else(|_| Value::String(expected.to_string()))
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn check_schema(body: &str, schema: &str) -> Result<(), String> {
+    let schema: Value =
+        serde_json::from_str(schema).map_err(|e| format!("Schema isn't JSON: {e}"))?;
+    let validator = jsonschema::validator_for(&schema).map_err(|e| e.to_string())?;
+    let body = parse_body(body)?;
+    let errors: Vec<String> = validator
+        .iter_errors(&body)
+        .map(|e| match e.instance_path().to_string().as_str() {
+            "" => e.to_string(),
+            path => format!("{path}: {e}"),
+        })
+        .collect();
+    expect(errors.is_empty(), || errors.join("\n"))
+}
+
+/// Runs the enabled assertions against a response.
+pub fn evaluate(assertions: &[Assertion], response: &ResponseData) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .filter(|a| a.enabled)
+        .map(|assertion| {
+            let outcome = assertion.check(response);
+            AssertionResult {
+                name: assertion.name(),
+                passed: outcome.is_ok(),
+                message: outcome.err().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assertion(kind: AssertionKind, target: &str, expected: &str) -> Assertion {
+        Assertion {
+            kind,
+            target: target.to_string(),
+            expected: expected.to_string(),
+            enabled: true,
+        }
+    }
+
+    fn response() -> ResponseData {
+        ResponseData {
+            status: 201,
+            headers: vec![KeyValue {
+                key: "content-type".to_string(),
+                value: "application/json; charset=utf-8".to_string(),
+            }],
+            body: r#"{"id": 7, "tags": ["a", "b"], "user": {"name": "ada"}}"#.to_string(),
+            response_time: 12.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn evaluates_assertions() {
+        use AssertionKind::*;
+        let assertions = [
+            assertion(Status, "", "2xx"),
+            assertion(Status, "", "200-200"),
+            assertion(HeaderMatches, "Content-Type", "^application/json"),
+            assertion(HeaderPresent, "etag", ""),
+            assertion(JsonPathEquals, "$.user.name", "ada"),
+            assertion(JsonPathContains, "$.tags", "b"),
+            assertion(JsonPathType, "$.id", "number"),
+            assertion(BodyMatches, "", r#""id":\s*\d+"#),
+            assertion(ResponseTimeBelow, "", "10"),
+            assertion(
+                JsonSchema,
+                "",
+                r#"{"type": "object", "required": ["id"], "properties": {"id": {"type": "string"}}}"#,
+            ),
+        ];
+        let results = evaluate(&assertions, &response());
+        let passed: Vec<bool> = results.iter().map(|r| r.passed).collect();
+        assert_eq!(
+            passed,
+            [true, false, true, false, true, true, true, true, false, false]
+        );
+        assert_eq!(results[1].message, "Was 201");
+        assert!(results[9].message.starts_with("/id: "));
+    }
+
+    #[test]
+    fn rejects_invalid_statuses() {
+        assert!(check_status(404, "4xx").is_ok());
+        assert!(check_status(201, "200-299").is_ok());
+        for expected in ["0xx", "6xx", "700xx", "999xx", "299-200"] {
+            assert!(check_status(200, expected)
+                .unwrap_err()
+                .starts_with("Invalid status"));
+        }
+    }
+}
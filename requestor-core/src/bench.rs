@@ -0,0 +1,430 @@
+//! Benchmarks, which send a request many times concurrently and report its latency.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+
+use crate::data::DataRow;
+use crate::{jsonrpc, prepare_request, raw, script, RequestArgs};
+
+/// Number of buckets latencies are grouped into.
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// How often progress is reported while a benchmark runs.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BenchOptions {
+    /// Number of requests to send. When a duration is also given the benchmark ends at
+    /// whichever comes first.
+    #[serde(default)]
+    pub requests: Option<u64>,
+    /// How long to keep sending requests, in milliseconds.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Number of requests in flight at once.
+    #[serde(default = "one")]
+    pub concurrency: u32,
+    /// Requests started per second across all connections, as fast as possible when unset.
+    #[serde(default)]
+    pub rate: Option<f64>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Number of requests that took from `start` up to `end` milliseconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    pub start: f64,
+    pub end: f64,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusCount {
+    pub status: u16,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorCount {
+    pub error: String,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// Requests that completed, with a response or an error.
+    pub requests: u64,
+    /// Requests answered with a 4xx or 5xx status or that got no response.
+    pub failed: u64,
+    /// Wall-clock time the benchmark ran for, in milliseconds.
+    pub duration: f64,
+    /// Completed requests per second.
+    pub throughput: f64,
+    pub statuses: Vec<StatusCount>,
+    /// Why requests that got no response failed.
+    pub errors: Vec<ErrorCount>,
+    /// Latencies of the responses, in milliseconds.
+    pub latency: LatencyStats,
+    pub histogram: Vec<HistogramBucket>,
+    /// Whether the benchmark was cancelled before it finished.
+    pub cancelled: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchProgress {
+    pub requests: u64,
+    pub failed: u64,
+    /// Milliseconds since the benchmark started.
+    pub elapsed: f64,
+}
+
+/// The outcome of a single request: its status or why it failed, and its latency.
+struct Sample {
+    outcome: Result<u16, String>,
+    latency: f64,
+}
+
+/// The latency at percentile `p` of sorted latencies, by the nearest-rank method.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn latency_stats(sorted: &[f64]) -> LatencyStats {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return LatencyStats::default();
+    };
+    LatencyStats {
+        min,
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: percentile(sorted, 50.0),
+        p90: percentile(sorted, 90.0),
+        p99: percentile(sorted, 99.0),
+        max,
+    }
+}
+
+/// Groups sorted latencies into equally wide buckets from the fastest to the slowest.
+fn histogram(sorted: &[f64]) -> Vec<HistogramBucket> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    let buckets = if max > min { HISTOGRAM_BUCKETS } else { 1 };
+    let width = (max - min) / buckets as f64;
+    let mut histogram: Vec<HistogramBucket> = (0..buckets)
+        .map(|idx| HistogramBucket {
+            start: min + width * idx as f64,
+            end: if idx + 1 == buckets {
+                max
+            } else {
+                min + width * (idx + 1) as f64
+            },
+            count: 0,
+        })
+        .collect();
+    for &latency in sorted {
+        let idx = match width {
+            0.0 => 0,
+            _ => (((latency - min) / width) as usize).min(buckets - 1),
+        };
+        histogram[idx].count += 1;
+    }
+    histogram
+}
+
+fn report(samples: &[Sample], duration: f64, cancelled: bool) -> BenchReport {
+    let mut statuses = BTreeMap::<u16, u64>::new();
+    let mut errors = BTreeMap::<String, u64>::new();
+    for sample in samples {
+        match &sample.outcome {
+            Ok(status) => *statuses.entry(*status).or_default() += 1,
+            Err(e) => *errors.entry(e.clone()).or_default() += 1,
+        }
+    }
+    let mut latencies: Vec<f64> = samples
+        .iter()
+        .filter(|s| s.outcome.is_ok())
+        .map(|s| s.latency)
+        .collect();
+    latencies.sort_by(f64::total_cmp);
+
+    let failed = samples
+        .iter()
+        .filter(|s| !matches!(s.outcome, Ok(status) if status < 400))
+        .count() as u64;
+    BenchReport {
+        requests: samples.len() as u64,
+        failed,
+        duration,
+        throughput: match duration {
+            0.0 => 0.0,
+            _ => samples.len() as f64 / (duration / 1000.0),
+        },
+        statuses: statuses
+            .into_iter()
+            .map(|(status, count)| StatusCount { status, count })
+            .collect(),
+        errors: errors
+            .into_iter()
+            .map(|(error, count)| ErrorCount { error, count })
+            .collect(),
+        latency: latency_stats(&latencies),
+        histogram: histogram(&latencies),
+        cancelled,
+    }
+}
+
+async fn send_one(request: &reqwest::RequestBuilder) -> Sample {
+    let start = Instant::now();
+    let outcome = match request.try_clone() {
+        Some(request) => match request.send().await {
+            Ok(res) => {
+                let status = res.status().as_u16();
+                res.bytes().await.map(|_| status).map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        },
+        None => Err("The request's body can't be sent more than once".to_string()),
+    };
+    Sample {
+        outcome,
+        latency: start.elapsed().as_secs_f64() * 1000.0,
+    }
+}
+
+/// Sends a request repeatedly with the same client, so connections are reused as they are
+/// between requests sent from the app, and reports the latencies. The pre-request script runs
+/// once and JSON-RPC calls get their ids once, so every request repeats them. The responses
+/// aren't checked against the request's assertions.
+pub async fn run(
+    mut args: RequestArgs,
+    options: &BenchOptions,
+    mut on_progress: impl FnMut(&BenchProgress),
+    cancelled: impl Future<Output = ()>,
+) -> Result<BenchReport, String> {
+    if options.requests.is_none() && options.duration_ms.is_none() {
+        return Err("Set a number of requests or a duration".to_string());
+    }
+    if raw::is_raw(&args) {
+        return Err("Requests sent over a raw connection can't be benchmarked".to_string());
+    }
+    script::before_request(&mut args, &DataRow::new())?;
+    jsonrpc::assign_ids(&mut args.body);
+    let request = Arc::new(prepare_request(args).await?);
+    if request.try_clone().is_none() {
+        return Err("Requests with a streamed body can't be benchmarked".to_string());
+    }
+
+    let start = Instant::now();
+    let deadline = options
+        .duration_ms
+        .map(|ms| start + Duration::from_millis(ms));
+    let limit = options.requests.unwrap_or(u64::MAX);
+    let started = Arc::new(AtomicU64::new(0));
+    let pacer = options.rate.filter(|rate| *rate > 0.0).map(|rate| {
+        let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / rate));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        Arc::new(Mutex::new(interval))
+    });
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut workers = JoinSet::new();
+    for _ in 0..options.concurrency.max(1) {
+        let (request, started, pacer, tx) =
+            (request.clone(), started.clone(), pacer.clone(), tx.clone());
+        workers.spawn(async move {
+            loop {
+                if let Some(pacer) = &pacer {
+                    pacer.lock().await.tick().await;
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                    || started.fetch_add(1, Ordering::Relaxed) >= limit
+                {
+                    return;
+                }
+                if tx.send(send_one(&request).await).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    tokio::pin!(cancelled);
+    let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+    let mut samples = Vec::new();
+    let mut failed = 0;
+    let was_cancelled = loop {
+        tokio::select! {
+            sample = rx.recv() => match sample {
+                Some(sample) => {
+                    if !matches!(sample.outcome, Ok(status) if status < 400) {
+                        failed += 1;
+                    }
+                    samples.push(sample);
+                }
+                None => break false,
+            },
+            _ = progress.tick() => on_progress(&BenchProgress {
+                requests: samples.len() as u64,
+                failed,
+                elapsed: start.elapsed().as_secs_f64() * 1000.0,
+            }),
+            _ = &mut cancelled => {
+                workers.abort_all();
+                break true;
+            }
+        }
+    };
+
+    Ok(report(
+        &samples,
+        start.elapsed().as_secs_f64() * 1000.0,
+        was_cancelled,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::RequestBody;
+    use crate::environment::{self, Environment};
+    use crate::jsonrpc::JsonRpcCall;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    fn sample(outcome: Result<u16, &str>, latency: f64) -> Sample {
+        Sample {
+            outcome: outcome.map_err(str::to_string),
+            latency,
+        }
+    }
+
+    #[test]
+    fn reports_latencies() {
+        let mut samples: Vec<Sample> = (1..=100).map(|ms| sample(Ok(200), ms as f64)).collect();
+        samples.push(sample(Ok(503), 50.0));
+        samples.push(sample(Err("connection refused"), 0.0));
+        let report = report(&samples, 2000.0, false);
+
+        assert_eq!(report.requests, 102);
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.throughput, 51.0);
+        assert_eq!(
+            report.statuses,
+            [
+                StatusCount {
+                    status: 200,
+                    count: 100
+                },
+                StatusCount {
+                    status: 503,
+                    count: 1
+                }
+            ]
+        );
+        assert_eq!(report.errors[0].count, 1);
+
+        let latency = &report.latency;
+        assert_eq!((latency.min, latency.max), (1.0, 100.0));
+        assert_eq!((latency.p50, latency.p90, latency.p99), (50.0, 90.0, 99.0));
+        assert_eq!(report.histogram.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(report.histogram.iter().map(|b| b.count).sum::<u64>(), 101);
+        assert_eq!(report.histogram.last().unwrap().end, 100.0);
+    }
+
+    #[test]
+    fn reports_equal_latencies_in_one_bucket() {
+        let samples = [sample(Ok(200), 5.0), sample(Ok(200), 5.0)];
+        let histogram = report(&samples, 0.0, true).histogram;
+        assert_eq!(
+            histogram,
+            [HistogramBucket {
+                start: 5.0,
+                end: 5.0,
+                count: 2
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_json_rpc_calls_with_ids() {
+        crate::db::set_path(std::env::temp_dir().join("requestor-bench-test.db"));
+        environment::use_environment(Environment::default());
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await.unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        });
+
+        let args = RequestArgs {
+            method: "POST".parse().unwrap(),
+            url: format!("http://127.0.0.1:{port}/rpc"),
+            body: RequestBody::JsonRpc {
+                calls: vec![JsonRpcCall {
+                    method: "ping".to_string(),
+                    ..Default::default()
+                }],
+                batch: false,
+            },
+            ..Default::default()
+        };
+        let options = BenchOptions {
+            requests: Some(1),
+            duration_ms: None,
+            concurrency: 1,
+            rate: None,
+        };
+        let report = run(args, &options, |_| {}, std::future::pending())
+            .await
+            .unwrap();
+        assert_eq!(report.failed, 0);
+        let message = server.await.unwrap();
+        assert_eq!(message["method"], "ping");
+        assert!(message["id"].is_u64());
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::multipart::{Form, Part};
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::graphql;
+use crate::jsonrpc;
+use crate::KeyValue;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum RequestBody {
+    #[default]
+    None,
+    Raw {
+        content_type: String,
+        text: String,
+    },
+    UrlEncoded {
+        fields: Vec<KeyValue>,
+    },
+    Multipart {
+        parts: Vec<MultipartPart>,
+    },
+    Binary {
+        path: String,
+        content_type: String,
+    },
+    GraphQl {
+        query: String,
+        /// JSON object of variables, may be empty.
+        variables: String,
+        operation_name: String,
+    },
+    JsonRpc {
+        calls: Vec<jsonrpc::JsonRpcCall>,
+        /// Send every call in one batch array rather than just the first call.
+        batch: bool,
+    },
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartKind {
+    #[default]
+    Text,
+    File,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    pub kind: PartKind,
+    /// The text value, or the path of the file to upload for file parts.
+    pub value: String,
+    pub content_type: String,
+}
+
+impl RequestBody {
+    /// Parses a body saved in the `requests` table. Rows written before body modes existed
+    /// hold the raw body text.
+    pub fn from_stored(stored: &str) -> RequestBody {
+        match serde_json::from_str(stored) {
+            Ok(body) => body,
+            Err(_) if stored.is_empty() => RequestBody::None,
+            Err(_) => RequestBody::Raw {
+                content_type: String::new(),
+                text: stored.to_string(),
+            },
+        }
+    }
+
+    /// Whether the `Content-Type` header is generated for the body and must not be overridden,
+    /// as for multipart bodies whose header carries the boundary.
+    pub fn owns_content_type(&self) -> bool {
+        matches!(self, RequestBody::Multipart { .. })
+    }
+
+    /// The text fields of the body that may contain `{{variables}}`.
+    pub fn texts_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            RequestBody::None => Vec::new(),
+            RequestBody::Raw { content_type, text } => vec![content_type, text],
+            RequestBody::UrlEncoded { fields } => fields
+                .iter_mut()
+                .flat_map(|kv| [&mut kv.key, &mut kv.value])
+                .collect(),
+            RequestBody::Multipart { parts } => parts
+                .iter_mut()
+                .flat_map(|p| [&mut p.name, &mut p.value, &mut p.content_type])
+                .collect(),
+            RequestBody::Binary { path, content_type } => vec![path, content_type],
+            RequestBody::GraphQl {
+                query,
+                variables,
+                operation_name,
+            } => vec![query, variables, operation_name],
+            RequestBody::JsonRpc { calls, .. } => calls
+                .iter_mut()
+                .flat_map(|c| [&mut c.method, &mut c.params])
+                .collect(),
+        }
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+async fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    tokio::fs::read(path.trim())
+        .await
+        .map_err(|e| format!("{path}: {e}"))
+}
+
+async fn multipart_form(parts: Vec<MultipartPart>) -> Result<Form, String> {
+    let mut form = Form::new();
+    for part in parts.into_iter().filter(|p| !p.name.is_empty()) {
+        let mut body = match part.kind {
+            PartKind::Text => Part::text(part.value),
+            PartKind::File => {
+                Part::bytes(read_file(&part.value).await?).file_name(file_name(&part.value))
+            }
+        };
+        if !part.content_type.trim().is_empty() {
+            body = body
+                .mime_str(part.content_type.trim())
+                .map_err(|e| e.to_string())?;
+        }
+        form = form.part(part.name, body);
+    }
+    Ok(form)
+}
+
+/// Attaches the body to `builder` along with the `Content-Type` it implies.
+pub async fn apply(builder: RequestBuilder, body: RequestBody) -> Result<RequestBuilder, String> {
+    Ok(match body {
+        RequestBody::None => builder,
+        RequestBody::Raw { content_type, text } if content_type.trim().is_empty() => {
+            builder.body(text)
+        }
+        RequestBody::Raw { content_type, text } => {
+            builder.header(CONTENT_TYPE, content_type.trim()).body(text)
+        }
+        RequestBody::UrlEncoded { fields } => {
+            let pairs: Vec<(String, String)> = fields
+                .into_iter()
+                .filter(|kv| !kv.key.is_empty())
+                .map(|kv| (kv.key, kv.value))
+                .collect();
+            builder.form(&pairs)
+        }
+        RequestBody::Multipart { parts } => builder.multipart(multipart_form(parts).await?),
+        RequestBody::Binary { path, content_type } => {
+            let content_type = match content_type.trim() {
+                "" => "application/octet-stream",
+                ct => ct,
+            };
+            builder
+                .header(CONTENT_TYPE, content_type)
+                .body(read_file(&path).await?)
+        }
+        RequestBody::GraphQl {
+            query,
+            variables,
+            operation_name,
+        } => builder.json(&graphql::request_body(&query, &variables, &operation_name)?),
+        RequestBody::JsonRpc { calls, batch } => {
+            builder.json(&jsonrpc::request_body(&calls, batch)?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_legacy_raw_bodies() {
+        assert!(matches!(RequestBody::from_stored(""), RequestBody::None));
+        assert!(matches!(
+            RequestBody::from_stored("{\"name\": \"x\"}"),
+            RequestBody::Raw { text, .. } if text == "{\"name\": \"x\"}"
+        ));
+
+        let stored =
+            serde_json::to_string(&RequestBody::UrlEncoded { fields: Vec::new() }).unwrap();
+        assert!(matches!(
+            RequestBody::from_stored(&stored),
+            RequestBody::UrlEncoded { .. }
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tokio::sync::oneshot;
+
+pub const CANCELLED: &str = "Request cancelled";
+
+/// In-flight requests, keyed by the id the frontend gave them, with the channel used to cancel
+/// them.
+static IN_FLIGHT: Lazy<Mutex<HashMap<u64, oneshot::Sender<()>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Registration of an in-flight request, removed again when dropped.
+pub struct Cancellation {
+    id: u64,
+    receiver: oneshot::Receiver<()>,
+}
+
+impl Cancellation {
+    /// Resolves once `cancel_request` is called for the request. Must not be awaited again
+    /// after it resolved.
+    pub async fn cancelled(&mut self) {
+        let _ = (&mut self.receiver).await;
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        IN_FLIGHT.lock().unwrap().remove(&self.id);
+    }
+}
+
+pub fn register(id: u64) -> Cancellation {
+    let (sender, receiver) = oneshot::channel();
+    IN_FLIGHT.lock().unwrap().insert(id, sender);
+    Cancellation { id, receiver }
+}
+
+/// Runs `future` as request `id`, abandoning it if the request is cancelled.
+pub async fn cancellable<T>(
+    id: u64,
+    future: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let mut cancellation = register(id);
+    tokio::select! {
+        result = future => result,
+        _ = cancellation.cancelled() => Err(CANCELLED.to_string()),
+    }
+}
+
+pub fn cancel_request(id: u64) {
+    if let Some(sender) = IN_FLIGHT.lock().unwrap().remove(&id) {
+        let _ = sender.send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancels_in_flight_requests() {
+        let pending = cancellable(1, std::future::pending::<Result<(), String>>());
+        let cancel = async {
+            tokio::task::yield_now().await;
+            cancel_request(1);
+        };
+        let (result, ()) = tokio::join!(pending, cancel);
+        assert_eq!(result, Err(CANCELLED.to_string()));
+        assert!(IN_FLIGHT.lock().unwrap().is_empty());
+
+        assert_eq!(cancellable(2, async { Ok(5) }).await, Ok(5));
+    }
+}
@@ -0,0 +1,52 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, ClientBuilder, Url};
+
+use crate::proxy::{self, RequestProxy};
+use crate::tls;
+use crate::KeyValue;
+
+/// Configures a client for a request to `url`, applying any TLS settings saved for its host
+/// and routing it through the request's proxy.
+pub fn client_builder(url: &Url, request_proxy: &RequestProxy) -> Result<ClientBuilder, String> {
+    let tls_config = tls::config_for_host(url.host_str().unwrap_or_default())?;
+
+    // The TLS info carries the leaf certificate that finds the chain the server presented.
+    let builder = Client::builder()
+        .tls_backend_preconfigured(tls::client_config(tls_config.as_ref())?)
+        .tls_info(true);
+
+    proxy::apply(builder, request_proxy)
+}
+
+/// Builds the HTTP client for a request to `url`, see `client_builder`.
+pub fn build_client(url: &Url, request_proxy: &RequestProxy) -> Result<Client, String> {
+    client_builder(url, request_proxy)?
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Converts the key/value rows from the editor into headers, skipping rows without a name.
+pub fn header_map(headers: &[KeyValue]) -> Result<HeaderMap, String> {
+    let mut map = HeaderMap::new();
+    for kv in headers {
+        if kv.key.is_empty() {
+            continue;
+        }
+        map.insert(
+            HeaderName::from_bytes(kv.key.as_bytes()).map_err(|e| e.to_string())?,
+            HeaderValue::from_str(&kv.value).map_err(|e| e.to_string())?,
+        );
+    }
+    Ok(map)
+}
+
+/// Converts response headers into key/value rows, keeping repeated headers as separate rows.
+pub fn header_rows(headers: &HeaderMap) -> Vec<KeyValue> {
+    headers
+        .iter()
+        .map(|(name, value)| KeyValue {
+            key: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
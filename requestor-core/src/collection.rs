@@ -0,0 +1,117 @@
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::{db, RequestArgs};
+
+/// A request saved to a collection. Folders are `/` separated paths, empty for requests at
+/// the top of the collection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionRequest {
+    pub name: String,
+    #[serde(default)]
+    pub folder: String,
+    pub request: RequestArgs,
+}
+
+/// Saved requests that are run together, in the order they're listed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Collection {
+    /// Unset for collections that haven't been saved yet.
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<CollectionRequest>,
+}
+
+/// Whether `folder` is `parent` or one of its subfolders. Every folder is in the empty one.
+pub fn in_folder(folder: &str, parent: &str) -> bool {
+    let parent = parent.trim_matches('/');
+    let folder = folder.trim_matches('/');
+    parent.is_empty()
+        || folder == parent
+        || folder
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+pub fn load(id: i64) -> Result<Collection, String> {
+    let conn = db::open()?;
+    conn.query_row(
+        "SELECT name, requests FROM collections WHERE id = ?1",
+        [id],
+        |row| {
+            Ok(Collection {
+                id: Some(id),
+                name: row.get(0)?,
+                requests: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or_default(),
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn get_collections() -> Result<Vec<Collection>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare("SELECT id, name, requests FROM collections ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Collection {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                requests: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// Saves a collection, returning its id.
+pub fn save_collection(collection: Collection) -> Result<i64, String> {
+    let conn = db::open()?;
+    let requests = serde_json::to_string(&collection.requests).map_err(|e| e.to_string())?;
+    match collection.id {
+        Some(id) => {
+            conn.execute(
+                "UPDATE collections SET name = ?1, requests = ?2 WHERE id = ?3",
+                params![collection.name, requests, id],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO collections (name, requests) VALUES (?1, ?2)",
+                params![collection.name, requests],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+pub fn delete_collection(id: i64) -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute("DELETE FROM collections WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM snapshots WHERE collection_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_folders_and_subfolders() {
+        assert!(in_folder("auth", ""));
+        assert!(in_folder("auth", "auth/"));
+        assert!(in_folder("auth/tokens", "auth"));
+        assert!(!in_folder("authz", "auth"));
+        assert!(!in_folder("", "auth"));
+    }
+}
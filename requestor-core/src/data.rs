@@ -0,0 +1,136 @@
+//! Data files for data-driven runs, where the requests are run once per row with the row's
+//! columns as variables.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// One row of a data file, by column name.
+pub type DataRow = BTreeMap<String, String>;
+
+/// Splits CSV text into records, following RFC 4180 quoting.
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    // Blank lines don't make rows.
+    records.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// Parses CSV with a header row naming the columns.
+pub fn parse_csv(text: &str) -> Result<Vec<DataRow>, String> {
+    let mut records = csv_records(text.trim_start_matches('\u{feff}'))?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_string()).collect();
+    records
+        .enumerate()
+        .map(|(idx, record)| {
+            if record.len() > header.len() {
+                return Err(format!(
+                    "Row {} has {} columns, the header has {}",
+                    idx + 1,
+                    record.len(),
+                    header.len()
+                ));
+            }
+            Ok(header.iter().cloned().zip(record).collect())
+        })
+        .collect()
+}
+
+/// Parses a JSON array of objects. Values that aren't strings are used as JSON.
+pub fn parse_json(text: &str) -> Result<Vec<DataRow>, String> {
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_str(text).map_err(|e| format!("Expected an array of objects: {e}"))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(text) => (key, text),
+                    other => (key, other.to_string()),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Reads a data file, as JSON when it has a `.json` extension and as CSV otherwise.
+pub fn load(path: &Path) -> Result<Vec<DataRow>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let rows = if is_json {
+        parse_json(&text)
+    } else {
+        parse_csv(&text)
+    };
+    rows.map_err(|e| format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> DataRow {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_csv() {
+        let text =
+            "user, password\r\nada,\"se,cret\"\n\n\"grace \"\"g\"\"\",\"multi\nline\"\nlinus\n";
+        assert_eq!(
+            parse_csv(text).unwrap(),
+            [
+                row(&[("user", "ada"), ("password", "se,cret")]),
+                row(&[("user", "grace \"g\""), ("password", "multi\nline")]),
+                row(&[("user", "linus")]),
+            ]
+        );
+        assert!(parse_csv("a\n1,2\n").is_err());
+        assert!(parse_csv("a\n\"1\n").is_err());
+    }
+
+    #[test]
+    fn parses_json() {
+        assert_eq!(
+            parse_json(r#"[{"user": "ada", "id": 1, "admin": true}]"#).unwrap(),
+            [row(&[("user", "ada"), ("id", "1"), ("admin", "true")])]
+        );
+        assert!(parse_json(r#"{"user": "ada"}"#).is_err());
+    }
+}
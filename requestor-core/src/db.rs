@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use once_cell::sync::OnceCell;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const DB_PATH: &str = "requests.db";
+
+static PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Uses the database at `path` instead of `requests.db` in the working directory. Only the
+/// first call has an effect.
+pub fn set_path(path: PathBuf) {
+    let _ = PATH.set(path);
+}
+
+/// Opens the application database, creating any tables that don't exist yet.
+pub fn open() -> Result<Connection, String> {
+    let path = PATH.get_or_init(|| PathBuf::from(DB_PATH));
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS requests (
+            id INTEGER PRIMARY KEY,
+            method TEXT,
+            url TEXT,
+            query_params TEXT,
+            headers TEXT,
+            body TEXT,
+            status INTEGER,
+            response_body TEXT,
+            response_time REAL,
+            created_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS tls_configs (
+            host TEXT PRIMARY KEY,
+            client_cert_path TEXT,
+            client_key_path TEXT,
+            pkcs12_path TEXT,
+            pkcs12_password TEXT,
+            ca_cert_paths TEXT
+        );
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        );
+        CREATE TABLE IF NOT EXISTS ws_sessions (
+            id INTEGER PRIMARY KEY,
+            url TEXT,
+            headers TEXT,
+            protocols TEXT,
+            messages TEXT,
+            created_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY,
+            name TEXT,
+            requests TEXT
+        );
+        CREATE TABLE IF NOT EXISTS runs (
+            id INTEGER PRIMARY KEY,
+            collection TEXT,
+            folder TEXT,
+            iterations INTEGER,
+            started_at TEXT,
+            finished_at TEXT,
+            summary TEXT,
+            results TEXT
+        );
+        CREATE TABLE IF NOT EXISTS recordings (
+            id INTEGER PRIMARY KEY,
+            method TEXT,
+            url TEXT,
+            request_headers TEXT,
+            request_body TEXT,
+            status INTEGER,
+            response_headers TEXT,
+            response_body BLOB,
+            response_time REAL,
+            created_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY,
+            collection_id INTEGER,
+            folder TEXT,
+            name TEXT,
+            status INTEGER,
+            headers TEXT,
+            body TEXT,
+            ignore TEXT,
+            approved_at TEXT,
+            UNIQUE (collection_id, folder, name)
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    add_column(&conn, "requests", "proxy TEXT")?;
+    add_column(&conn, "requests", "stream INTEGER")?;
+    add_column(&conn, "requests", "events TEXT")?;
+    add_column(&conn, "requests", "wire INTEGER")?;
+    add_column(&conn, "requests", "assertions TEXT")?;
+    add_column(&conn, "requests", "response_headers TEXT")?;
+    add_column(&conn, "requests", "tests TEXT")?;
+    add_column(&conn, "requests", "pre_request_script TEXT")?;
+    add_column(&conn, "requests", "post_response_script TEXT")?;
+    add_column(&conn, "requests", "extractors TEXT")?;
+    add_column(&conn, "runs", "data TEXT")?;
+    Ok(conn)
+}
+
+/// Adds a column to a table created by an older version, ignoring it if it already exists.
+fn add_column(conn: &Connection, table: &str, column: &str) -> Result<(), String> {
+    match conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column}"), []) {
+        Err(e) if !e.to_string().contains("duplicate column") => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Reads a JSON encoded setting, falling back to the default when it hasn't been saved.
+pub fn get_setting<T: DeserializeOwned + Default>(key: &str) -> Result<T, String> {
+    let conn = open()?;
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+pub fn put_setting<T: Serialize>(key: &str, value: &T) -> Result<(), String> {
+    let conn = open()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![
+            key,
+            serde_json::to_string(value).map_err(|e| e.to_string())?
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
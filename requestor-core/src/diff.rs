@@ -0,0 +1,447 @@
+//! Comparing two responses: their status, headers and bodies, structurally when both bodies
+//! are JSON and line by line otherwise.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::KeyValue;
+
+/// Past this many pairs of lines, the differing middle of two bodies is shown as removed and
+/// added whole, as finding the shortest diff takes time and memory proportional to the pairs.
+const MAX_LINE_PRODUCT: usize = 4_000_000;
+
+/// The parts of a response that are compared.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DiffSide {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DiffOptions {
+    /// JSON paths left out of the body diff, such as `$.meta.generated_at`, `$.items[*].id`
+    /// or `updated_at` for that key at any depth. Patterns naming a header leave it out of the
+    /// header diff.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A value that differs between the two JSON bodies.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineTag {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub tag: LineTag,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BodyDiff {
+    Json { changes: Vec<JsonChange> },
+    Text { lines: Vec<DiffLine> },
+}
+
+/// A header whose values differ, missing on the side it's `None`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeaderChange {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResponseDiff {
+    pub left_status: u16,
+    pub right_status: u16,
+    pub headers: Vec<HeaderChange>,
+    pub body: BodyDiff,
+}
+
+impl ResponseDiff {
+    /// Whether the two responses are the same, apart from what was ignored.
+    pub fn is_empty(&self) -> bool {
+        self.left_status == self.right_status
+            && self.headers.is_empty()
+            && match &self.body {
+                BodyDiff::Json { changes } => changes.is_empty(),
+                BodyDiff::Text { lines } => lines.iter().all(|l| l.tag == LineTag::Same),
+            }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// Any key or index, only in ignore patterns.
+    Any,
+}
+
+/// A parsed ignore pattern.
+struct Pattern {
+    /// Whether the pattern starts at the root, rather than matching the end of a path.
+    anchored: bool,
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    fn parse(pattern: &str) -> Pattern {
+        let pattern = pattern.trim();
+        let (anchored, rest) = match pattern.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let mut segments = Vec::new();
+        for part in rest.split('.').filter(|part| !part.is_empty()) {
+            let (key, indexes) = part.split_once('[').unwrap_or((part, ""));
+            match key {
+                "" => {}
+                "*" => segments.push(Segment::Any),
+                key => segments.push(Segment::Key(key.to_string())),
+            }
+            for index in indexes.split('[').map(|index| index.trim_end_matches(']')) {
+                let index = index.trim_matches(|c| c == '\'' || c == '"');
+                match index.parse() {
+                    _ if index == "*" => segments.push(Segment::Any),
+                    Ok(index) => segments.push(Segment::Index(index)),
+                    Err(_) if !index.is_empty() => segments.push(Segment::Key(index.to_string())),
+                    Err(_) => {}
+                }
+            }
+        }
+        Pattern { anchored, segments }
+    }
+
+    fn matches(&self, path: &[Segment]) -> bool {
+        if self.segments.is_empty() || self.segments.len() > path.len() {
+            return false;
+        }
+        if self.anchored && self.segments.len() != path.len() {
+            return false;
+        }
+        let tail = &path[path.len() - self.segments.len()..];
+        self.segments
+            .iter()
+            .zip(tail)
+            .all(|(pattern, segment)| *pattern == Segment::Any || pattern == segment)
+    }
+}
+
+fn render_path(path: &[Segment]) -> String {
+    let mut rendered = "$".to_string();
+    for segment in path {
+        match segment {
+            Segment::Key(key)
+                if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                rendered.push('.');
+                rendered.push_str(key);
+            }
+            Segment::Key(key) => rendered.push_str(&format!("['{key}']")),
+            Segment::Index(index) => rendered.push_str(&format!("[{index}]")),
+            Segment::Any => rendered.push_str("[*]"),
+        }
+    }
+    rendered
+}
+
+fn diff_values(
+    left: Option<&Value>,
+    right: Option<&Value>,
+    path: &mut Vec<Segment>,
+    ignore: &[Pattern],
+    changes: &mut Vec<JsonChange>,
+) {
+    if ignore.iter().any(|pattern| pattern.matches(path)) {
+        return;
+    }
+    let kind = match (left, right) {
+        (Some(Value::Object(l)), Some(Value::Object(r))) => {
+            let keys: BTreeSet<&String> = l.keys().chain(r.keys()).collect();
+            for key in keys {
+                path.push(Segment::Key(key.clone()));
+                diff_values(l.get(key), r.get(key), path, ignore, changes);
+                path.pop();
+            }
+            return;
+        }
+        (Some(Value::Array(l)), Some(Value::Array(r))) => {
+            for idx in 0..l.len().max(r.len()) {
+                path.push(Segment::Index(idx));
+                diff_values(l.get(idx), r.get(idx), path, ignore, changes);
+                path.pop();
+            }
+            return;
+        }
+        (Some(l), Some(r)) if l == r => return,
+        (Some(_), Some(_)) => ChangeKind::Changed,
+        (Some(_), None) => ChangeKind::Removed,
+        (None, Some(_)) => ChangeKind::Added,
+        (None, None) => return,
+    };
+    changes.push(JsonChange {
+        path: render_path(path),
+        kind,
+        left: left.cloned(),
+        right: right.cloned(),
+    });
+}
+
+/// The values that differ between two JSON documents, ignoring the order of object keys.
+/// Arrays are compared item by item.
+pub fn diff_json(left: &Value, right: &Value, ignore: &[String]) -> Vec<JsonChange> {
+    let ignore: Vec<Pattern> = ignore
+        .iter()
+        .filter(|pattern| !pattern.trim().is_empty())
+        .map(|pattern| Pattern::parse(pattern))
+        .collect();
+    let mut changes = Vec::new();
+    diff_values(
+        Some(left),
+        Some(right),
+        &mut Vec::new(),
+        &ignore,
+        &mut changes,
+    );
+    changes
+}
+
+/// The lines of `left` and `right` in order, tagged as kept, removed from `left` or added in
+/// `right`, following their longest common subsequence.
+pub fn diff_lines(left: &str, right: &str) -> Vec<DiffLine> {
+    let left: Vec<&str> = left.lines().collect();
+    let right: Vec<&str> = right.lines().collect();
+    let line = |tag, text: &str| DiffLine {
+        tag,
+        text: text.to_string(),
+    };
+
+    let prefix = left.iter().zip(&right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let (l, r) = (
+        &left[prefix..left.len() - suffix],
+        &right[prefix..right.len() - suffix],
+    );
+
+    let mut lines: Vec<DiffLine> = left[..prefix]
+        .iter()
+        .map(|text| line(LineTag::Same, text))
+        .collect();
+    if l.len() * r.len() > MAX_LINE_PRODUCT {
+        lines.extend(l.iter().map(|text| line(LineTag::Removed, text)));
+        lines.extend(r.iter().map(|text| line(LineTag::Added, text)));
+    } else {
+        // lengths[i][j] is the length of the longest common subsequence of l[i..] and r[j..].
+        let mut lengths = vec![vec![0usize; r.len() + 1]; l.len() + 1];
+        for i in (0..l.len()).rev() {
+            for j in (0..r.len()).rev() {
+                lengths[i][j] = match l[i] == r[j] {
+                    true => lengths[i + 1][j + 1] + 1,
+                    false => lengths[i + 1][j].max(lengths[i][j + 1]),
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < l.len() || j < r.len() {
+            if i < l.len() && j < r.len() && l[i] == r[j] {
+                lines.push(line(LineTag::Same, l[i]));
+                (i, j) = (i + 1, j + 1);
+            } else if j < r.len() && (i == l.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+                lines.push(line(LineTag::Added, r[j]));
+                j += 1;
+            } else {
+                lines.push(line(LineTag::Removed, l[i]));
+                i += 1;
+            }
+        }
+    }
+    lines.extend(
+        left[left.len() - suffix..]
+            .iter()
+            .map(|text| line(LineTag::Same, text)),
+    );
+    lines
+}
+
+/// Headers by lowercase name, with repeated headers' values joined.
+fn header_map(headers: &[KeyValue]) -> BTreeMap<String, String> {
+    let mut map = BTreeMap::<String, String>::new();
+    for kv in headers {
+        map.entry(kv.key.to_lowercase())
+            .and_modify(|value| {
+                value.push_str(", ");
+                value.push_str(&kv.value);
+            })
+            .or_insert_with(|| kv.value.clone());
+    }
+    map
+}
+
+fn diff_headers(left: &[KeyValue], right: &[KeyValue], ignore: &[String]) -> Vec<HeaderChange> {
+    let (left, right) = (header_map(left), header_map(right));
+    let names: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| !ignore.iter().any(|i| i.trim().eq_ignore_ascii_case(name)))
+        .filter(|name| left.get(*name) != right.get(*name))
+        .map(|name| HeaderChange {
+            name: name.clone(),
+            left: left.get(name).cloned(),
+            right: right.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Compares two responses, structurally when both bodies parse as JSON.
+pub fn diff_responses(left: &DiffSide, right: &DiffSide, options: &DiffOptions) -> ResponseDiff {
+    let body = match (
+        serde_json::from_str::<Value>(&left.body),
+        serde_json::from_str::<Value>(&right.body),
+    ) {
+        (Ok(l), Ok(r)) => BodyDiff::Json {
+            changes: diff_json(&l, &r, &options.ignore),
+        },
+        _ => BodyDiff::Text {
+            lines: diff_lines(&left.body, &right.body),
+        },
+    };
+    ResponseDiff {
+        left_status: left.status,
+        right_status: right.status,
+        headers: diff_headers(&left.headers, &right.headers, &options.ignore),
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(changes: &[JsonChange]) -> Vec<(&str, ChangeKind)> {
+        changes.iter().map(|c| (c.path.as_str(), c.kind)).collect()
+    }
+
+    #[test]
+    fn diffs_json_ignoring_key_order() {
+        let left = json!({"id": 1, "name": "Ada", "tags": ["a", "b"], "meta": {"x-trace": "1"}});
+        let right = json!({"tags": ["a", "c", "d"], "name": "Ada", "id": 2, "email": "ada@example.com", "meta": {"x-trace": "2"}});
+        let changes = diff_json(&left, &right, &[]);
+        assert_eq!(
+            paths(&changes),
+            [
+                ("$.email", ChangeKind::Added),
+                ("$.id", ChangeKind::Changed),
+                ("$.meta['x-trace']", ChangeKind::Changed),
+                ("$.tags[1]", ChangeKind::Changed),
+                ("$.tags[2]", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(changes[1].left, Some(json!(1)));
+        assert_eq!(changes[1].right, Some(json!(2)));
+        assert!(diff_json(&left, &left.clone(), &[]).is_empty());
+    }
+
+    #[test]
+    fn ignores_paths() {
+        let left =
+            json!({"updated_at": "1", "items": [{"id": 1, "n": 1}], "meta": {"updated_at": "1"}});
+        let right =
+            json!({"updated_at": "2", "items": [{"id": 2, "n": 1}], "meta": {"updated_at": "2"}});
+
+        let ignore = ["updated_at".to_string(), "$.items[*].id".to_string()];
+        assert!(diff_json(&left, &right, &ignore).is_empty());
+
+        let ignore = ["$.updated_at".to_string(), "items.*.id".to_string()];
+        assert_eq!(
+            paths(&diff_json(&left, &right, &ignore)),
+            [("$.meta.updated_at", ChangeKind::Changed)]
+        );
+    }
+
+    #[test]
+    fn diffs_lines() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nc\nd\ne");
+        let tagged: Vec<(LineTag, &str)> = lines.iter().map(|l| (l.tag, l.text.as_str())).collect();
+        assert_eq!(
+            tagged,
+            [
+                (LineTag::Same, "a"),
+                (LineTag::Removed, "b"),
+                (LineTag::Same, "c"),
+                (LineTag::Same, "d"),
+                (LineTag::Added, "e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_responses() {
+        let kv = |key: &str, value: &str| KeyValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let left = DiffSide {
+            status: 200,
+            headers: vec![kv("Content-Type", "text/plain"), kv("Date", "Mon")],
+            body: "hello".to_string(),
+        };
+        let right = DiffSide {
+            status: 404,
+            headers: vec![
+                kv("content-type", "text/plain"),
+                kv("Date", "Tue"),
+                kv("X-Cache", "miss"),
+            ],
+            body: "not found".to_string(),
+        };
+        let options = DiffOptions {
+            ignore: vec!["date".to_string()],
+        };
+        let diff = diff_responses(&left, &right, &options);
+
+        assert_eq!((diff.left_status, diff.right_status), (200, 404));
+        assert_eq!(
+            diff.headers,
+            [HeaderChange {
+                name: "x-cache".to_string(),
+                left: None,
+                right: Some("miss".to_string()),
+            }]
+        );
+        assert!(matches!(diff.body, BodyDiff::Text { lines } if lines.len() == 2));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::{db, KeyValue, RequestArgs};
+
+const SETTINGS_KEY: &str = "environments";
+
+/// Environment created for variables set by scripts while no environment is active.
+const DEFAULT_ENVIRONMENT: &str = "Default";
+
+/// `{{name}}` references, with optional spaces inside the braces.
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([\w.-]+)\s*\}\}").unwrap());
+
+/// Environment used in place of the saved ones, see [`use_environment`].
+static DETACHED: Lazy<Mutex<Option<Environment>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub variables: Vec<KeyValue>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Environments {
+    pub environments: Vec<Environment>,
+    /// Name of the environment whose variables requests use.
+    pub active: Option<String>,
+}
+
+/// Uses `environment` instead of the saved environments for the rest of the process. Variables
+/// set by scripts and extractors are then kept in memory rather than saved.
+pub fn use_environment(environment: Environment) {
+    *DETACHED.lock().unwrap() = Some(environment);
+}
+
+/// The variables of the active environment.
+pub fn active_variables() -> Result<HashMap<String, String>, String> {
+    let detached = DETACHED.lock().unwrap().clone();
+    let active = match detached {
+        Some(env) => Some(env),
+        None => {
+            let settings = get_environments()?;
+            settings
+                .environments
+                .into_iter()
+                .find(|env| Some(&env.name) == settings.active.as_ref())
+        }
+    };
+    Ok(active
+        .map(|env| {
+            env.variables
+                .into_iter()
+                .filter(|kv| !kv.key.is_empty())
+                .map(|kv| (kv.key, kv.value))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Writes variables to the active environment, activating a default one if there is none.
+pub fn set_variables(updates: &[(String, String)]) -> Result<(), String> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+    if let Some(env) = DETACHED.lock().unwrap().as_mut() {
+        update(&mut env.variables, updates);
+        return Ok(());
+    }
+    let mut settings = get_environments()?;
+    let active = settings
+        .active
+        .get_or_insert_with(|| DEFAULT_ENVIRONMENT.to_string())
+        .clone();
+    let idx = match settings.environments.iter().position(|e| e.name == active) {
+        Some(idx) => idx,
+        None => {
+            settings.environments.push(Environment {
+                name: active,
+                variables: Vec::new(),
+            });
+            settings.environments.len() - 1
+        }
+    };
+
+    update(&mut settings.environments[idx].variables, updates);
+    save_environments(settings)
+}
+
+fn update(variables: &mut Vec<KeyValue>, updates: &[(String, String)]) {
+    for (key, value) in updates {
+        match variables.iter_mut().find(|kv| &kv.key == key) {
+            Some(kv) => kv.value = value.clone(),
+            None => variables.push(KeyValue {
+                key: key.clone(),
+                value: value.clone(),
+            }),
+        }
+    }
+}
+
+/// Replaces `{{name}}` references with their values, leaving unknown ones as they are.
+pub fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    VARIABLE
+        .replace_all(text, |caps: &Captures| match variables.get(&caps[1]) {
+            Some(value) => value.clone(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Fills in the variables referenced by the URL, query parameters, headers and body.
+pub fn substitute_request(args: &mut RequestArgs, variables: &HashMap<String, String>) {
+    let mut texts = vec![&mut args.url];
+    for kv in args.query_params.iter_mut().chain(args.headers.iter_mut()) {
+        texts.push(&mut kv.key);
+        texts.push(&mut kv.value);
+    }
+    texts.extend(args.body.texts_mut());
+    for text in texts {
+        if text.contains("{{") {
+            *text = substitute(text, variables);
+        }
+    }
+}
+
+pub fn get_environments() -> Result<Environments, String> {
+    db::get_setting(SETTINGS_KEY)
+}
+
+pub fn save_environments(environments: Environments) -> Result<(), String> {
+    db::put_setting(SETTINGS_KEY, &environments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_known_variables() {
+        let variables = HashMap::from([
+            ("host".to_string(), "api.example.com".to_string()),
+            ("auth.token".to_string(), "abc".to_string()),
+        ]);
+        assert_eq!(
+            substitute(
+                "https://{{host}}/me?t={{ auth.token }}&x={{missing}}",
+                &variables
+            ),
+            "https://api.example.com/me?t=abc&x={{missing}}"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::assertions::{header, json_path};
+use crate::ResponseData;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractorSource {
+    JsonPath,
+    /// The first capture group of the regex in the body, or the whole match without groups.
+    Regex,
+    Header,
+    /// A cookie the response sets with `Set-Cookie`.
+    Cookie,
+}
+
+/// Copies a value from a successful response into an environment variable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Extractor {
+    pub source: ExtractorSource,
+    /// JSONPath, regex, header name or cookie name depending on the source.
+    pub expression: String,
+    pub variable: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Extraction {
+    pub variable: String,
+    pub value: Option<String>,
+    /// Why nothing was extracted.
+    pub message: String,
+}
+
+fn json_text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+fn cookie(response: &ResponseData, name: &str) -> Result<String, String> {
+    response
+        .headers
+        .iter()
+        .filter(|h| h.key.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|h| h.value.split(';').next()?.split_once('='))
+        .find(|(cookie, _)| cookie.trim() == name)
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .ok_or_else(|| format!("No {name} cookie set"))
+}
+
+impl Extractor {
+    fn value(&self, response: &ResponseData) -> Result<String, String> {
+        let expression = self.expression.trim();
+        match self.source {
+            ExtractorSource::JsonPath => json_path(&response.body, expression).map(json_text),
+            ExtractorSource::Regex => {
+                let pattern = Regex::new(expression).map_err(|e| e.to_string())?;
+                let caps = pattern
+                    .captures(&response.body)
+                    .ok_or_else(|| "No match".to_string())?;
+                let found = caps.get(1).or_else(|| caps.get(0)).unwrap();
+                Ok(found.as_str().to_string())
+            }
+            ExtractorSource::Header => header(&response.headers, expression).map(str::to_string),
+            ExtractorSource::Cookie => cookie(response, expression),
+        }
+    }
+}
+
+/// Runs the enabled extractors against a response. Nothing is extracted from responses that
+/// aren't successful, so a failed login doesn't clear the token of an earlier one.
+pub fn run(extractors: &[Extractor], response: &ResponseData) -> Vec<Extraction> {
+    let successful = (200..300).contains(&response.status);
+    extractors
+        .iter()
+        .filter(|e| e.enabled && !e.variable.trim().is_empty())
+        .map(|extractor| {
+            let value = match successful {
+                true => extractor.value(response),
+                false => Err(format!("Status {} isn't successful", response.status)),
+            };
+            Extraction {
+                variable: extractor.variable.trim().to_string(),
+                message: value.as_ref().err().cloned().unwrap_or_default(),
+                value: value.ok(),
+            }
+        })
+        .collect()
+}
+
+/// The variables to write to the environment for the values that were extracted.
+pub fn variables(extractions: &[Extraction]) -> Vec<(String, String)> {
+    extractions
+        .iter()
+        .filter_map(|e| Some((e.variable.clone(), e.value.clone()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyValue;
+
+    fn extractor(source: ExtractorSource, expression: &str, variable: &str) -> Extractor {
+        Extractor {
+            source,
+            expression: expression.to_string(),
+            variable: variable.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn extracts_values_from_successful_responses() {
+        let mut response = ResponseData {
+            status: 200,
+            headers: vec![
+                KeyValue {
+                    key: "Set-Cookie".to_string(),
+                    value: "session=abc123; Path=/; HttpOnly".to_string(),
+                },
+                KeyValue {
+                    key: "Location".to_string(),
+                    value: "/users/7".to_string(),
+                },
+            ],
+            body: r#"{"token": "t0k3n", "user": {"id": 7}}"#.to_string(),
+            response_time: 1.0,
+            ..Default::default()
+        };
+        let extractors = [
+            extractor(ExtractorSource::JsonPath, "$.token", "token"),
+            extractor(ExtractorSource::JsonPath, "$.user.id", "user_id"),
+            extractor(ExtractorSource::Regex, r#""id":\s*(\d+)"#, "id"),
+            extractor(ExtractorSource::Header, "location", "location"),
+            extractor(ExtractorSource::Cookie, "session", "session"),
+            extractor(ExtractorSource::Cookie, "missing", "missing"),
+        ];
+        assert_eq!(
+            variables(&run(&extractors, &response)),
+            [
+                ("token", "t0k3n"),
+                ("user_id", "7"),
+                ("id", "7"),
+                ("location", "/users/7"),
+                ("session", "abc123"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+
+        response.status = 401;
+        assert!(variables(&run(&extractors, &response)).is_empty());
+    }
+}
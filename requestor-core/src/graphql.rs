@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use graphql_parser::query::{
+    parse_query, Definition, FragmentDefinition, OperationDefinition, Selection, SelectionSet,
+    TypeCondition,
+};
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::client;
+use crate::proxy::RequestProxy;
+use crate::KeyValue;
+
+/// Introspected schemas keyed by endpoint URL.
+static SCHEMAS: Lazy<Mutex<HashMap<String, GraphQlSchema>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      enumValues(includeDeprecated: true) { name }
+      possibleTypes { name }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } }
+}
+"#;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GraphQlSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: Vec<GraphQlType>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GraphQlType {
+    pub name: String,
+    pub kind: String,
+    pub description: Option<String>,
+    /// Output fields for objects and interfaces, input fields for input objects.
+    pub fields: Vec<GraphQlField>,
+    pub enum_values: Vec<String>,
+    pub possible_types: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GraphQlField {
+    pub name: String,
+    pub description: Option<String>,
+    /// Type as written in SDL, e.g. `[User!]!`.
+    pub type_name: String,
+    /// The named type with list and non-null wrappers removed.
+    pub named_type: String,
+    /// Non-null arguments and input fields without a default value.
+    pub required: bool,
+    pub args: Vec<GraphQlField>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IntrospectArgs {
+    pub url: String,
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub proxy: RequestProxy,
+    /// Ignores any cached schema for the endpoint.
+    pub refresh: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidateGraphQlArgs {
+    pub url: String,
+    pub query: String,
+}
+
+#[derive(Deserialize)]
+struct TypeRef {
+    kind: String,
+    name: Option<String>,
+    #[serde(rename = "ofType")]
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    fn render(&self) -> String {
+        match (self.kind.as_str(), &self.of_type) {
+            ("NON_NULL", Some(inner)) => format!("{}!", inner.render()),
+            ("LIST", Some(inner)) => format!("[{}]", inner.render()),
+            _ => self.name.clone().unwrap_or_default(),
+        }
+    }
+
+    fn named(&self) -> String {
+        match &self.of_type {
+            Some(inner) => inner.named(),
+            None => self.name.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IntrospectedInputValue {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+    #[serde(rename = "defaultValue")]
+    default_value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectedField {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<IntrospectedInputValue>,
+    #[serde(rename = "type")]
+    type_ref: TypeRef,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct IntrospectedType {
+    kind: String,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<IntrospectedField>>,
+    #[serde(rename = "inputFields")]
+    input_fields: Option<Vec<IntrospectedInputValue>>,
+    #[serde(rename = "enumValues")]
+    enum_values: Option<Vec<Named>>,
+    #[serde(rename = "possibleTypes")]
+    possible_types: Option<Vec<Named>>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectedSchema {
+    #[serde(rename = "queryType")]
+    query_type: Option<Named>,
+    #[serde(rename = "mutationType")]
+    mutation_type: Option<Named>,
+    #[serde(rename = "subscriptionType")]
+    subscription_type: Option<Named>,
+    types: Vec<IntrospectedType>,
+}
+
+impl From<IntrospectedInputValue> for GraphQlField {
+    fn from(value: IntrospectedInputValue) -> Self {
+        GraphQlField {
+            required: value.type_ref.kind == "NON_NULL" && value.default_value.is_none(),
+            type_name: value.type_ref.render(),
+            named_type: value.type_ref.named(),
+            name: value.name,
+            description: value.description,
+            args: Vec::new(),
+        }
+    }
+}
+
+impl From<IntrospectedSchema> for GraphQlSchema {
+    fn from(schema: IntrospectedSchema) -> Self {
+        let types = schema
+            .types
+            .into_iter()
+            .map(|t| {
+                let mut fields: Vec<GraphQlField> = t
+                    .fields
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|f| GraphQlField {
+                        type_name: f.type_ref.render(),
+                        named_type: f.type_ref.named(),
+                        name: f.name,
+                        description: f.description,
+                        required: false,
+                        args: f.args.into_iter().map(GraphQlField::from).collect(),
+                    })
+                    .collect();
+                fields.extend(
+                    t.input_fields
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(GraphQlField::from),
+                );
+
+                GraphQlType {
+                    name: t.name,
+                    kind: t.kind,
+                    description: t.description,
+                    fields,
+                    enum_values: t
+                        .enum_values
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| v.name)
+                        .collect(),
+                    possible_types: t
+                        .possible_types
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| v.name)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        GraphQlSchema {
+            query_type: schema.query_type.map(|t| t.name),
+            mutation_type: schema.mutation_type.map(|t| t.name),
+            subscription_type: schema.subscription_type.map(|t| t.name),
+            types,
+        }
+    }
+}
+
+/// Builds the JSON envelope sent for a GraphQL body.
+pub fn request_body(query: &str, variables: &str, operation_name: &str) -> Result<Value, String> {
+    let mut body = json!({ "query": query });
+    if !variables.trim().is_empty() {
+        body["variables"] = serde_json::from_str(variables)
+            .map_err(|e| format!("Invalid GraphQL variables: {e}"))?;
+    }
+    if !operation_name.trim().is_empty() {
+        body["operationName"] = operation_name.trim().into();
+    }
+    Ok(body)
+}
+
+struct Validator<'s, 'q> {
+    types: HashMap<&'s str, &'s GraphQlType>,
+    fragments: HashMap<&'q str, &'q FragmentDefinition<'q, &'q str>>,
+    errors: Vec<String>,
+}
+
+impl<'s, 'q> Validator<'s, 'q> {
+    fn selection_set(&mut self, type_name: &str, set: &SelectionSet<'q, &'q str>) {
+        let Some(parent) = self.types.get(type_name).copied() else {
+            self.errors.push(format!("Unknown type `{type_name}`"));
+            return;
+        };
+
+        for selection in &set.items {
+            match selection {
+                Selection::Field(field) => {
+                    if field.name == "__typename" {
+                        continue;
+                    }
+                    let Some(definition) = parent.fields.iter().find(|f| f.name == field.name)
+                    else {
+                        self.errors.push(format!(
+                            "{}: Cannot query field `{}` on type `{type_name}`",
+                            field.position, field.name
+                        ));
+                        continue;
+                    };
+
+                    for (arg, _) in &field.arguments {
+                        if !definition.args.iter().any(|a| a.name == *arg) {
+                            self.errors.push(format!(
+                                "{}: Unknown argument `{arg}` on field `{type_name}.{}`",
+                                field.position, field.name
+                            ));
+                        }
+                    }
+                    for required in definition.args.iter().filter(|a| a.required) {
+                        if !field.arguments.iter().any(|(arg, _)| *arg == required.name) {
+                            self.errors.push(format!(
+                                "{}: Field `{type_name}.{}` requires argument `{}: {}`",
+                                field.position, field.name, required.name, required.type_name
+                            ));
+                        }
+                    }
+
+                    let composite =
+                        self.types
+                            .get(definition.named_type.as_str())
+                            .is_some_and(|t| {
+                                matches!(t.kind.as_str(), "OBJECT" | "INTERFACE" | "UNION")
+                            });
+                    match (composite, field.selection_set.items.is_empty()) {
+                        (true, true) => self.errors.push(format!(
+                            "{}: Field `{}` of type `{}` must have a selection of subfields",
+                            field.position, field.name, definition.type_name
+                        )),
+                        (false, false) => self.errors.push(format!(
+                            "{}: Field `{}` must not have a selection since type `{}` has no subfields",
+                            field.position, field.name, definition.type_name
+                        )),
+                        (true, false) => {
+                            self.selection_set(&definition.named_type, &field.selection_set)
+                        }
+                        (false, true) => {}
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    if !self.fragments.contains_key(spread.fragment_name) {
+                        self.errors.push(format!(
+                            "{}: Unknown fragment `{}`",
+                            spread.position, spread.fragment_name
+                        ));
+                    }
+                }
+                Selection::InlineFragment(fragment) => match &fragment.type_condition {
+                    Some(TypeCondition::On(on)) => self.selection_set(on, &fragment.selection_set),
+                    None => self.selection_set(type_name, &fragment.selection_set),
+                },
+            }
+        }
+    }
+}
+
+/// Checks a query document against a schema, returning a message per problem found.
+pub fn validate(schema: &GraphQlSchema, query: &str) -> Vec<String> {
+    let document = match parse_query::<&str>(query) {
+        Ok(document) => document,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut validator = Validator {
+        types: schema.types.iter().map(|t| (t.name.as_str(), t)).collect(),
+        fragments: document
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::Fragment(f) => Some((f.name, f)),
+                _ => None,
+            })
+            .collect(),
+        errors: Vec::new(),
+    };
+
+    for definition in &document.definitions {
+        let (root, kind, set) = match definition {
+            Definition::Operation(OperationDefinition::SelectionSet(set)) => {
+                (&schema.query_type, "query", set)
+            }
+            Definition::Operation(OperationDefinition::Query(q)) => {
+                (&schema.query_type, "query", &q.selection_set)
+            }
+            Definition::Operation(OperationDefinition::Mutation(m)) => {
+                (&schema.mutation_type, "mutation", &m.selection_set)
+            }
+            Definition::Operation(OperationDefinition::Subscription(s)) => {
+                (&schema.subscription_type, "subscription", &s.selection_set)
+            }
+            Definition::Fragment(f) => {
+                let TypeCondition::On(on) = f.type_condition;
+                validator.selection_set(on, &f.selection_set);
+                continue;
+            }
+        };
+        match root {
+            Some(root) => validator.selection_set(root, set),
+            None => validator
+                .errors
+                .push(format!("The schema does not support {kind} operations")),
+        }
+    }
+    validator.errors
+}
+
+/// Validates `query` against the cached schema for `url`, if one has been introspected.
+pub fn validate_cached(url: &str, query: &str) -> Vec<String> {
+    match SCHEMAS.lock().unwrap().get(url) {
+        Some(schema) => validate(schema, query),
+        None => Vec::new(),
+    }
+}
+
+pub async fn graphql_introspect(args: IntrospectArgs) -> Result<GraphQlSchema, String> {
+    if !args.refresh {
+        if let Some(schema) = SCHEMAS.lock().unwrap().get(&args.url) {
+            return Ok(schema.clone());
+        }
+    }
+
+    let url = Url::parse(&args.url).map_err(|e| e.to_string())?;
+    let client = client::build_client(&url, &args.proxy)?;
+    let res: Value = client
+        .post(url)
+        .headers(client::header_map(&args.headers)?)
+        .json(&json!({ "query": INTROSPECTION_QUERY, "operationName": "IntrospectionQuery" }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| format!("Introspection response was not JSON: {e}"))?;
+
+    let introspected =
+        res.pointer("/data/__schema")
+            .cloned()
+            .ok_or_else(|| match res.get("errors") {
+                Some(errors) => format!("Introspection failed: {errors}"),
+                None => "Introspection response had no schema".to_string(),
+            })?;
+    let schema: GraphQlSchema = serde_json::from_value::<IntrospectedSchema>(introspected)
+        .map_err(|e| e.to_string())?
+        .into();
+
+    SCHEMAS
+        .lock()
+        .unwrap()
+        .insert(args.url.clone(), schema.clone());
+    Ok(schema)
+}
+
+pub fn graphql_operations(query: String) -> Vec<String> {
+    let Ok(document) = parse_query::<&str>(&query) else {
+        return Vec::new();
+    };
+    document
+        .definitions
+        .iter()
+        .filter_map(|d| match d {
+            Definition::Operation(OperationDefinition::Query(q)) => q.name,
+            Definition::Operation(OperationDefinition::Mutation(m)) => m.name,
+            Definition::Operation(OperationDefinition::Subscription(s)) => s.name,
+            _ => None,
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn validate_graphql(args: ValidateGraphQlArgs) -> Vec<String> {
+    validate_cached(&args.url, &args.query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, type_name: &str, named_type: &str) -> GraphQlField {
+        GraphQlField {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            named_type: named_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn schema() -> GraphQlSchema {
+        let mut user_arg = field("id", "ID!", "ID");
+        user_arg.required = true;
+        let mut user = field("user", "User", "User");
+        user.args.push(user_arg);
+
+        GraphQlSchema {
+            query_type: Some("Query".to_string()),
+            types: vec![
+                GraphQlType {
+                    name: "Query".to_string(),
+                    kind: "OBJECT".to_string(),
+                    fields: vec![user],
+                    ..Default::default()
+                },
+                GraphQlType {
+                    name: "User".to_string(),
+                    kind: "OBJECT".to_string(),
+                    fields: vec![field("name", "String", "String")],
+                    ..Default::default()
+                },
+                GraphQlType {
+                    name: "String".to_string(),
+                    kind: "SCALAR".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accepts_valid_query() {
+        let errors = validate(&schema(), "query Q { user(id: 1) { name __typename } }");
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_missing_arguments() {
+        let errors = validate(&schema(), "{ user { email } }");
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors[0].contains("requires argument `id: ID!`"));
+        assert!(errors[1].contains("Cannot query field `email` on type `User`"));
+    }
+
+    #[test]
+    fn lists_named_operations() {
+        assert_eq!(
+            graphql_operations("query A { a } mutation B { b } { c }".to_string()),
+            vec!["A", "B"]
+        );
+    }
+}
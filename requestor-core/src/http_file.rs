@@ -0,0 +1,276 @@
+//! Requests written in the `.http` format of the VS Code REST Client and the JetBrains HTTP
+//! Client. Scripts in `< {% %}` and `> {% %}` blocks are Rhai pre-request and post-response
+//! scripts rather than JavaScript.
+
+use std::path::Path;
+
+use crate::body::RequestBody;
+use crate::collection::{Collection, CollectionRequest};
+use crate::{KeyValue, RequestArgs};
+
+/// The requests of a `.http` file and the variables it declares with `@name = value`.
+#[derive(Clone, Debug)]
+pub struct HttpFile {
+    pub variables: Vec<(String, String)>,
+    pub collection: Collection,
+}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with("//")
+}
+
+/// Collects a `{% ... %}` script starting on `first`, which may end on the same line.
+fn script<'a>(first: &str, lines: &mut impl Iterator<Item = &'a str>) -> String {
+    let mut script = first.trim_start().trim_start_matches("{%").to_string();
+    loop {
+        if let Some(end) = script.rfind("%}") {
+            script.truncate(end);
+            return script.trim().to_string();
+        }
+        match lines.next() {
+            Some(line) => {
+                script.push('\n');
+                script.push_str(line);
+            }
+            None => return script.trim().to_string(),
+        }
+    }
+}
+
+/// The part of a request line after the method, without the HTTP version.
+fn target(line: &str) -> &str {
+    match line.rsplit_once(' ') {
+        Some((target, version)) if version.starts_with("HTTP/") => target.trim(),
+        _ => line,
+    }
+}
+
+/// Parses one request, the text between `###` separators.
+fn parse_request(
+    block: &str,
+    mut name: String,
+    dir: &Path,
+    variables: &mut Vec<(String, String)>,
+) -> Result<Option<CollectionRequest>, String> {
+    let mut lines = block.lines();
+    let mut args = RequestArgs::default();
+
+    // Comments, file variables and the pre-request script come before the request line.
+    let request_line = loop {
+        let Some(line) = lines.next() else {
+            return Ok(None);
+        };
+        let line = line.trim();
+        if let Some(named) = line
+            .strip_prefix('#')
+            .or_else(|| line.strip_prefix("//"))
+            .and_then(|comment| comment.trim().strip_prefix("@name"))
+        {
+            name = named.trim().trim_start_matches('=').trim().to_string();
+        } else if let Some(variable) = line.strip_prefix('@') {
+            let (key, value) = variable
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid variable {line:?}, use @name = value"))?;
+            variables.push((key.trim().to_string(), value.trim().to_string()));
+        } else if let Some(rest) = line
+            .strip_prefix('<')
+            .filter(|r| r.trim().starts_with("{%"))
+        {
+            args.pre_request_script = script(rest, &mut lines);
+        } else if !line.is_empty() && !is_comment(line) {
+            break line;
+        }
+    };
+
+    let (method, url) = match request_line.split_once(' ') {
+        Some((method, rest)) if method.chars().all(|c| c.is_ascii_uppercase()) => {
+            (method, target(rest.trim()))
+        }
+        _ => ("GET", target(request_line)),
+    };
+    args.method = method.parse()?;
+    args.url = url.to_string();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if is_comment(line) {
+            continue;
+        }
+        if let Some(rest) = line
+            .strip_prefix('>')
+            .filter(|r| r.trim().starts_with("{%"))
+        {
+            args.post_response_script = script(rest, &mut lines);
+            continue;
+        }
+        // Long query strings may continue on the following lines.
+        if line.starts_with('?') || line.starts_with('&') {
+            args.url.push_str(line);
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid header {line:?} in {name}"))?;
+        args.headers.push(KeyValue {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        });
+    }
+
+    let mut body = Vec::new();
+    while let Some(line) = lines.next() {
+        match line.strip_prefix('>') {
+            Some(rest) if rest.trim().starts_with("{%") => {
+                args.post_response_script = script(rest, &mut lines);
+            }
+            _ => body.push(line),
+        }
+    }
+    let body = body.join("\n");
+    let body = body.trim_end().trim_start_matches('\n');
+    args.body = match body.trim_start().strip_prefix("< ") {
+        Some(path) if !path.contains('\n') => RequestBody::Binary {
+            path: dir.join(path.trim()).to_string_lossy().into_owned(),
+            content_type: String::new(),
+        },
+        _ if body.trim().is_empty() => RequestBody::None,
+        _ => RequestBody::Raw {
+            content_type: String::new(),
+            text: body.to_string(),
+        },
+    };
+
+    if name.is_empty() {
+        name = format!("{} {}", args.method.as_str(), args.url);
+    }
+    Ok(Some(CollectionRequest {
+        name,
+        folder: String::new(),
+        request: args,
+    }))
+}
+
+/// Parses the text of a `.http` file, `dir` is where files it references are relative to.
+pub fn parse(text: &str, name: &str, dir: &Path) -> Result<HttpFile, String> {
+    let mut file = HttpFile {
+        variables: Vec::new(),
+        collection: Collection {
+            id: None,
+            name: name.to_string(),
+            requests: Vec::new(),
+        },
+    };
+    let mut block = String::new();
+    let mut block_name = String::new();
+    for line in text.lines().chain(["###"]) {
+        match line.trim_start().strip_prefix("###") {
+            Some(separator) => {
+                let request = parse_request(&block, block_name, dir, &mut file.variables)?;
+                file.collection.requests.extend(request);
+                block.clear();
+                block_name = separator.trim().to_string();
+            }
+            None => {
+                block.push_str(line);
+                block.push('\n');
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Reads and parses a `.http` file.
+pub fn load(path: &Path) -> Result<HttpFile, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    parse(&text, &name, path.parent().unwrap_or(Path::new(".")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"@host = https://api.example.com
+@user = ada
+
+### Log in
+POST {{host}}/login HTTP/1.1
+Content-Type: application/json
+# A comment between headers
+
+{
+  "user": "{{user}}"
+}
+
+> {%
+  test("logged in", response.status == 200);
+  set_env("token", response.json.token);
+%}
+
+###
+# @name profile
+< {% set_var("trace", "on"); %}
+GET {{host}}/users
+    ?page=2
+    &size=10
+Authorization: Bearer {{token}}
+
+###
+PUT {{host}}/avatar
+Content-Type: image/png
+
+< ./avatar.png
+
+### Nothing to send, just a comment
+# GET https://example.com
+"#;
+
+    #[test]
+    fn parses_requests() {
+        let file = parse(FILE, "api", Path::new("/requests")).unwrap();
+        assert_eq!(
+            file.variables,
+            [("host", "https://api.example.com"), ("user", "ada")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+
+        let requests = &file.collection.requests;
+        assert_eq!(requests.len(), 3);
+
+        let login = &requests[0];
+        assert_eq!(login.name, "Log in");
+        assert_eq!(login.request.method.as_str(), "POST");
+        assert_eq!(login.request.url, "{{host}}/login");
+        assert_eq!(login.request.headers.len(), 1);
+        assert!(matches!(
+            &login.request.body,
+            RequestBody::Raw { text, .. } if text == "{\n  \"user\": \"{{user}}\"\n}"
+        ));
+        assert_eq!(
+            login.request.post_response_script,
+            "test(\"logged in\", response.status == 200);\n  set_env(\"token\", response.json.token);"
+        );
+
+        let profile = &requests[1];
+        assert_eq!(profile.name, "profile");
+        assert_eq!(profile.request.url, "{{host}}/users?page=2&size=10");
+        assert_eq!(
+            profile.request.pre_request_script,
+            "set_var(\"trace\", \"on\");"
+        );
+        assert!(matches!(profile.request.body, RequestBody::None));
+
+        assert!(matches!(
+            &requests[2].request.body,
+            RequestBody::Binary { path, .. } if path == "/requests/./avatar.png"
+        ));
+        assert_eq!(requests[2].name, "PUT {{host}}/avatar");
+    }
+}
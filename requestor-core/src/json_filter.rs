@@ -0,0 +1,75 @@
+//! Filters narrowing a JSON response down to the parts of interest, written as a JSONPath
+//! (`$.items[*].id`) or a jq-style path (`.items[].id`).
+
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// The JSONPath a filter stands for. jq-style paths start with a dot, `[]` iterates like
+/// `[*]`.
+pub fn to_json_path(filter: &str) -> String {
+    let filter = filter.trim();
+    if filter.starts_with('$') {
+        return filter.to_string();
+    }
+    let path = match filter.strip_prefix('.') {
+        Some("") => return "$".to_string(),
+        Some(_) => format!("${filter}"),
+        None => format!("$.{filter}"),
+    };
+    path.replace("[]", "[*]").replace(".[", "[")
+}
+
+/// Whether a valid path selects at most one value, so its match isn't wrapped in an array.
+/// The parser doesn't expose the query it builds, but only accepts singular queries as the
+/// argument of `length` compared to a value, which leaves quoted keys to the parser.
+fn singular(path: &str) -> bool {
+    let relative = path.strip_prefix('$').unwrap_or(path);
+    JsonPath::parse(&format!("$[?length(@{relative}) == 0]")).is_ok()
+}
+
+/// The values `filter` selects in `body`: the value itself for paths naming a single one, an
+/// array of the matches for paths with wildcards, slices or filter expressions.
+pub fn filter(body: &str, filter: &str) -> Result<Value, String> {
+    let body: Value = serde_json::from_str(body).map_err(|e| format!("Body isn't JSON: {e}"))?;
+    let path = to_json_path(filter);
+    let query = JsonPath::parse(&path).map_err(|e| e.to_string())?;
+    let found = query.query(&body).all();
+    match found.as_slice() {
+        [] if singular(&path) => Err("Nothing found".to_string()),
+        [value] if singular(&path) => Ok((*value).clone()),
+        values => Ok(Value::Array(values.iter().map(|v| (*v).clone()).collect())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn translates_jq_paths() {
+        assert_eq!(to_json_path("."), "$");
+        assert_eq!(to_json_path(".items[].id"), "$.items[*].id");
+        assert_eq!(to_json_path(".[0].name"), "$[0].name");
+        assert_eq!(to_json_path("items[1]"), "$.items[1]");
+        assert_eq!(to_json_path(" $.items[?@.id > 1] "), "$.items[?@.id > 1]");
+    }
+
+    #[test]
+    fn filters_bodies() {
+        let body = r#"{"items": [{"id": 1, "tags": ["a"]}, {"id": 2, "tags": []}]}"#;
+        assert_eq!(filter(body, ".items[1].id").unwrap(), json!(2));
+        assert_eq!(filter(body, "$.items[*].id").unwrap(), json!([1, 2]));
+        assert_eq!(filter(body, ".items[].tags[]").unwrap(), json!(["a"]));
+        assert_eq!(filter(body, ".items[].missing").unwrap(), json!([]));
+        assert_eq!(filter(body, ".missing").unwrap_err(), "Nothing found");
+        assert!(filter(body, "$.items[").is_err());
+        assert!(filter("not json", ".").is_err());
+
+        let body = r#"{"a:b": 1, "x,y": [true], "it's": {"*": null}}"#;
+        assert_eq!(filter(body, "$['a:b']").unwrap(), json!(1));
+        assert_eq!(filter(body, "$['x,y'][0]").unwrap(), json!(true));
+        assert_eq!(filter(body, r"$['it\'s']['*']").unwrap(), json!(null));
+        assert_eq!(filter(body, "$['a:b','x,y'][0]").unwrap(), json!([true]));
+    }
+}
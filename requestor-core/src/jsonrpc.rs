@@ -0,0 +1,232 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::body::RequestBody;
+
+/// Ids given to JSON-RPC calls, incrementing across all requests sent.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JsonRpcCall {
+    pub method: String,
+    /// JSON array or object of parameters, may be empty.
+    pub params: String,
+    /// Notifications are sent without an id and get no response.
+    #[serde(default)]
+    pub notification: bool,
+    /// Assigned when the request is sent.
+    #[serde(default)]
+    pub id: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+/// The response to one call, both `result` and `error` are unset when the server didn't
+/// answer it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcOutcome {
+    pub id: Option<u64>,
+    pub method: String,
+    pub result: Option<String>,
+    pub error: Option<JsonRpcError>,
+}
+
+fn is_sent(call: &JsonRpcCall) -> bool {
+    !call.method.trim().is_empty()
+}
+
+/// Gives the calls of a JSON-RPC body fresh ids, returning the calls that expect a response.
+pub fn assign_ids(body: &mut RequestBody) -> Vec<JsonRpcCall> {
+    let RequestBody::JsonRpc { calls, batch } = body else {
+        return Vec::new();
+    };
+    // Only the first call is sent unless batching.
+    let count = if *batch { calls.len() } else { 1 };
+    let mut sent = Vec::new();
+    for call in calls.iter_mut().filter(|c| is_sent(c)).take(count) {
+        call.id = match call.notification {
+            true => None,
+            false => Some(NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+        };
+        if call.id.is_some() {
+            sent.push(call.clone());
+        }
+    }
+    sent
+}
+
+fn message(call: &JsonRpcCall) -> Result<Value, String> {
+    let mut message = json!({ "jsonrpc": "2.0", "method": call.method.trim() });
+    if !call.params.trim().is_empty() {
+        let params: Value = serde_json::from_str(&call.params)
+            .map_err(|e| format!("Params of {}: {e}", call.method.trim()))?;
+        if !params.is_array() && !params.is_object() {
+            return Err(format!(
+                "Params of {} must be an array or object",
+                call.method.trim()
+            ));
+        }
+        message["params"] = params;
+    }
+    if let Some(id) = call.id {
+        message["id"] = json!(id);
+    }
+    Ok(message)
+}
+
+pub fn request_body(calls: &[JsonRpcCall], batch: bool) -> Result<Value, String> {
+    let mut sent = calls.iter().filter(|c| is_sent(c));
+    if batch {
+        return sent
+            .map(message)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    // Only the first call is sent, the others needn't be valid.
+    match sent.next() {
+        Some(call) => message(call),
+        None => Err("No JSON-RPC method given".to_string()),
+    }
+}
+
+fn response_id(response: &Value) -> Option<u64> {
+    match response.get("id")? {
+        Value::Number(id) => id.as_u64(),
+        Value::String(id) => id.parse().ok(),
+        _ => None,
+    }
+}
+
+fn outcome(response: &Value, id: Option<u64>, method: &str) -> JsonRpcOutcome {
+    let pretty = |value: &Value| serde_json::to_string_pretty(value).unwrap_or_default();
+    let error = response.get("error").map(|error| JsonRpcError {
+        code: error
+            .get("code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default(),
+        message: error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        data: error.get("data").map(pretty),
+    });
+    JsonRpcOutcome {
+        id,
+        method: method.to_string(),
+        result: response.get("result").map(pretty),
+        error,
+    }
+}
+
+/// Pairs the responses in `body` with the calls that were sent by id. Responses the calls
+/// can't account for, such as errors for unparseable requests, are listed after them.
+pub fn match_responses(body: &str, calls: &[JsonRpcCall]) -> Vec<JsonRpcOutcome> {
+    if calls.is_empty() {
+        return Vec::new();
+    }
+    let Ok(parsed) = serde_json::from_str::<Value>(body) else {
+        return Vec::new();
+    };
+    let mut responses = match parsed {
+        Value::Array(responses) => responses,
+        response => vec![response],
+    };
+
+    let mut outcomes = Vec::new();
+    for call in calls {
+        let matching = responses
+            .iter()
+            .position(|r| call.id.is_some() && response_id(r) == call.id);
+        outcomes.push(match matching {
+            Some(idx) => outcome(&responses.remove(idx), call.id, &call.method),
+            None => JsonRpcOutcome {
+                id: call.id,
+                method: call.method.clone(),
+                result: None,
+                error: None,
+            },
+        });
+    }
+    outcomes.extend(responses.iter().map(|r| outcome(r, response_id(r), "")));
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(method: &str, params: &str, notification: bool) -> JsonRpcCall {
+        JsonRpcCall {
+            method: method.to_string(),
+            params: params.to_string(),
+            notification,
+            id: None,
+        }
+    }
+
+    #[test]
+    fn builds_batches_with_incrementing_ids() {
+        let mut body = RequestBody::JsonRpc {
+            calls: vec![
+                call("eth_blockNumber", "", false),
+                call("log", r#"{"level": "info"}"#, true),
+                call("eth_getBalance", r#"["0x1", "latest"]"#, false),
+            ],
+            batch: true,
+        };
+        let sent = assign_ids(&mut body);
+        assert_eq!(sent.len(), 2);
+        assert!(sent[1].id > sent[0].id);
+
+        let RequestBody::JsonRpc { calls, .. } = &body else {
+            unreachable!()
+        };
+        let value = request_body(calls, true).unwrap();
+        assert_eq!(value[0]["id"], json!(sent[0].id));
+        assert!(value[0].get("params").is_none());
+        assert!(value[1].get("id").is_none());
+        assert_eq!(value[2]["params"], json!(["0x1", "latest"]));
+
+        assert!(request_body(&[call("x", "1", false)], false).is_err());
+        // Calls after the first aren't sent unless batching.
+        let single = [call("ping", "", false), call("x", "1", false)];
+        assert_eq!(request_body(&single, false).unwrap()["method"], "ping");
+        assert!(request_body(&single, true).is_err());
+    }
+
+    #[test]
+    fn matches_responses_by_id() {
+        let calls = [
+            JsonRpcCall {
+                id: Some(1),
+                ..call("a", "", false)
+            },
+            JsonRpcCall {
+                id: Some(2),
+                ..call("b", "", false)
+            },
+            JsonRpcCall {
+                id: Some(3),
+                ..call("c", "", false)
+            },
+        ];
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "Method not found"}},
+            {"jsonrpc": "2.0", "id": 1, "result": 7},
+            {"jsonrpc": "2.0", "id": null, "error": {"code": -32700, "message": "Parse error"}}
+        ]"#;
+        let outcomes = match_responses(body, &calls);
+        assert_eq!(outcomes[0].result.as_deref(), Some("7"));
+        assert_eq!(outcomes[1].error.as_ref().unwrap().code, -32601);
+        assert_eq!((&outcomes[2].result, &outcomes[2].error), (&None, &None));
+        assert_eq!(outcomes[3].error.as_ref().unwrap().message, "Parse error");
+    }
+}
@@ -0,0 +1,293 @@
+//! Building, sending and checking requests, shared by the desktop app and the command-line
+//! runner.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use rusqlite::types::{FromSql, FromSqlError, ValueRef};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+pub mod assertions;
+pub mod bench;
+pub mod body;
+pub mod cancel;
+pub mod client;
+pub mod collection;
+pub mod data;
+pub mod db;
+pub mod diff;
+pub mod environment;
+pub mod extract;
+pub mod graphql;
+pub mod http_file;
+pub mod json_filter;
+pub mod jsonrpc;
+pub mod mock;
+pub mod proxy;
+pub mod raw;
+pub mod recorder;
+pub mod report;
+pub mod runner;
+pub mod script;
+pub mod snapshot;
+pub mod stream;
+pub mod tls;
+pub mod webhook;
+
+pub fn rfc3339_now() -> String {
+    let now = SystemTime::now();
+    let now: DateTime<Utc> = now.into();
+    now.to_rfc3339()
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum HttpMethod {
+    #[default]
+    GET,
+    POST,
+    PUT,
+    DELETE,
+    PATCH,
+    HEAD,
+    OPTIONS,
+    TRACE,
+    CONNECT,
+    /// Extension methods such as `PROPFIND` or `PURGE`.
+    Custom(String),
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &str {
+        match self {
+            HttpMethod::GET => "GET",
+            HttpMethod::POST => "POST",
+            HttpMethod::PUT => "PUT",
+            HttpMethod::DELETE => "DELETE",
+            HttpMethod::PATCH => "PATCH",
+            HttpMethod::HEAD => "HEAD",
+            HttpMethod::OPTIONS => "OPTIONS",
+            HttpMethod::TRACE => "TRACE",
+            HttpMethod::CONNECT => "CONNECT",
+            HttpMethod::Custom(method) => method,
+        }
+    }
+
+    pub fn to_reqwest(&self) -> Result<reqwest::Method, String> {
+        reqwest::Method::from_bytes(self.as_str().as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "GET" => HttpMethod::GET,
+            "POST" => HttpMethod::POST,
+            "PUT" => HttpMethod::PUT,
+            "DELETE" => HttpMethod::DELETE,
+            "PATCH" => HttpMethod::PATCH,
+            "HEAD" => HttpMethod::HEAD,
+            "OPTIONS" => HttpMethod::OPTIONS,
+            "TRACE" => HttpMethod::TRACE,
+            "CONNECT" => HttpMethod::CONNECT,
+            other => {
+                reqwest::Method::from_bytes(other.as_bytes())
+                    .map_err(|_| format!("Invalid HTTP method {other:?}"))?;
+                HttpMethod::Custom(other.to_string())
+            }
+        })
+    }
+}
+
+impl Serialize for HttpMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromSql for HttpMethod {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RequestArgs {
+    pub method: HttpMethod,
+    pub url: String,
+    pub query_params: Vec<KeyValue>,
+    pub headers: Vec<KeyValue>,
+    pub body: body::RequestBody,
+    #[serde(default)]
+    pub proxy: proxy::RequestProxy,
+    /// Whether the response is read with `stream_request`.
+    #[serde(default)]
+    pub stream: bool,
+    /// Send over a plain HTTP/1.1 connection that records the bytes exchanged.
+    #[serde(default)]
+    pub wire: bool,
+    #[serde(default)]
+    pub assertions: Vec<assertions::Assertion>,
+    /// Rhai script run before `{{variables}}` are filled in, with the request in scope.
+    #[serde(default)]
+    pub pre_request_script: String,
+    /// Rhai script run after the assertions, with the request and response in scope.
+    #[serde(default)]
+    pub post_response_script: String,
+    /// Values copied from successful responses into environment variables.
+    #[serde(default)]
+    pub extractors: Vec<extract::Extractor>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResponseData {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    pub body: String,
+    pub response_time: f32,
+    #[serde(default)]
+    pub certificates: Vec<tls::CertificateInfo>,
+    /// Transcript of a streamed response.
+    #[serde(default)]
+    pub events: Vec<stream::StreamEvent>,
+    /// Responses to the calls of a JSON-RPC body, matched by id.
+    #[serde(default)]
+    pub json_rpc: Vec<jsonrpc::JsonRpcOutcome>,
+    /// Bytes exchanged for requests sent over a raw connection.
+    #[serde(default)]
+    pub wire: Option<raw::WireExchange>,
+    /// Results of the request's assertions.
+    #[serde(default)]
+    pub tests: Vec<assertions::AssertionResult>,
+    /// Output the request's scripts printed.
+    #[serde(default)]
+    pub console: Vec<String>,
+    /// Variables the request's extractors set.
+    #[serde(default)]
+    pub extracted: Vec<extract::Extraction>,
+}
+
+/// Builds the request described by `args`, ready to be sent.
+pub async fn prepare_request(args: RequestArgs) -> Result<reqwest::RequestBuilder, String> {
+    if let body::RequestBody::GraphQl { query, .. } = &args.body {
+        let errors = graphql::validate_cached(&args.url, query);
+        if !errors.is_empty() {
+            return Err(format!("GraphQL validation failed:\n{}", errors.join("\n")));
+        }
+    }
+
+    let mut url = reqwest::Url::parse(&args.url).map_err(|e| e.to_string())?;
+    let client = client::build_client(&url, &args.proxy)?;
+
+    let query_params: Vec<&KeyValue> = args
+        .query_params
+        .iter()
+        .filter(|kv| !kv.key.is_empty())
+        .collect();
+    if !query_params.is_empty() {
+        let mut pairs = url.query_pairs_mut();
+        for kv in query_params {
+            pairs.append_pair(&kv.key, &kv.value);
+        }
+    }
+
+    let mut headers = client::header_map(&args.headers)?;
+    if args.body.owns_content_type() {
+        headers.remove(CONTENT_TYPE);
+    }
+
+    let request = body::apply(client.request(args.method.to_reqwest()?, url), args.body).await?;
+
+    // User supplied headers are applied last so they override the body's content type.
+    Ok(request.headers(headers))
+}
+
+/// The certificate chain the server presented, for responses received over TLS.
+pub fn response_certificates(res: &reqwest::Response) -> Vec<tls::CertificateInfo> {
+    res.extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .map(tls::peer_certificates)
+        .unwrap_or_default()
+}
+
+/// Runs a request's pre-request script, sends it and checks the response.
+pub async fn execute(args: RequestArgs) -> Result<ResponseData, String> {
+    execute_with_data(args, &data::DataRow::new()).await
+}
+
+/// Like `execute`, with the columns of a data file's row as variables.
+pub async fn execute_with_data(
+    mut args: RequestArgs,
+    row: &data::DataRow,
+) -> Result<ResponseData, String> {
+    let run = script::before_request(&mut args, row)?;
+    let mut response = send(args.clone()).await?;
+    complete_response(&args, run, &mut response)?;
+    Ok(response)
+}
+
+/// Checks a response against the request's assertions, extracts variables from it and runs
+/// the post-response script.
+pub fn complete_response(
+    args: &RequestArgs,
+    mut run: script::ScriptRun,
+    response: &mut ResponseData,
+) -> Result<(), String> {
+    response.tests = assertions::evaluate(&args.assertions, response);
+    response.extracted = extract::run(&args.extractors, response);
+    let extracted = extract::variables(&response.extracted);
+    environment::set_variables(&extracted)?;
+    run.variables.extend(extracted);
+    script::after_response(args, run, response)
+}
+
+pub async fn send(mut args: RequestArgs) -> Result<ResponseData, String> {
+    let json_rpc_calls = jsonrpc::assign_ids(&mut args.body);
+    if raw::is_raw(&args) {
+        let mut response = raw::send(args).await?;
+        response.json_rpc = jsonrpc::match_responses(&response.body, &json_rpc_calls);
+        return Ok(response);
+    }
+    let request = prepare_request(args).await?;
+
+    let start = std::time::Instant::now();
+    let res = request.send().await;
+
+    let res = res.map_err(|e| e.to_string())?;
+    let status = res.status().as_u16();
+    let headers = client::header_rows(res.headers());
+    let certificates = response_certificates(&res);
+    let body = res.text().await.map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed().as_secs_f32() * 1000.0;
+
+    Ok(ResponseData {
+        status,
+        headers,
+        response_time: elapsed,
+        certificates,
+        json_rpc: jsonrpc::match_responses(&body, &json_rpc_calls),
+        body,
+        ..Default::default()
+    })
+}
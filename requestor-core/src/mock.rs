@@ -0,0 +1,467 @@
+//! A local mock server, serving canned responses from routes matched on the method, a path
+//! pattern and optionally headers, query parameters and the body.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::{db, environment, rfc3339_now, KeyValue};
+
+const SETTINGS_KEY: &str = "mock_server";
+
+/// Port the mock server listens on unless another one is saved.
+const DEFAULT_PORT: u16 = 8787;
+
+static SERVER: Lazy<Mutex<Option<Running>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MockRoute {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Method the request must use, any when empty or `*`.
+    #[serde(default)]
+    pub method: String,
+    /// Path the request must have. `:name` or `{name}` segments match any segment and are
+    /// available to templates as `{{params.name}}`, a `*` segment matches the rest of the path.
+    pub path: String,
+    /// Headers the request must have, with any value when the value is empty.
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    /// Query parameters the request must have, with any value when the value is empty.
+    #[serde(default)]
+    pub query: Vec<KeyValue>,
+    /// Text the request body must contain.
+    #[serde(default)]
+    pub body: String,
+    #[serde(default = "ok")]
+    pub status: u16,
+    #[serde(default)]
+    pub response_headers: Vec<KeyValue>,
+    /// Body of the response. `{{params.x}}`, `{{query.x}}`, `{{headers.x}}`, `{{body}}`,
+    /// `{{body.x}}` for fields of a JSON body, `{{method}}` and `{{path}}` are filled in from
+    /// the request.
+    #[serde(default)]
+    pub response_body: String,
+    /// How long to wait before responding, in milliseconds.
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn ok() -> u16 {
+    200
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MockSettings {
+    pub port: u16,
+    pub routes: Vec<MockRoute>,
+}
+
+impl Default for MockSettings {
+    fn default() -> Self {
+        MockSettings {
+            port: DEFAULT_PORT,
+            routes: Vec::new(),
+        }
+    }
+}
+
+/// A request the mock server received and how it answered it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MockHit {
+    pub time: String,
+    pub method: String,
+    /// Path and query of the request.
+    pub path: String,
+    /// Name of the route that answered, none when no route matched.
+    pub route: Option<String>,
+    pub status: u16,
+}
+
+struct Running {
+    port: u16,
+    routes: Arc<RwLock<Vec<MockRoute>>>,
+    task: JoinHandle<()>,
+}
+
+/// The parts of a request routes are matched against.
+struct MockRequest {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// Matches a path against a route's pattern, returning the path parameters.
+fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    // Patterns copied from a request may be whole URLs.
+    let pattern = match pattern.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |idx| &rest[idx..]),
+        None => pattern,
+    };
+    let pattern = pattern.split(['?', '#']).next().unwrap_or_default();
+
+    let mut params = HashMap::new();
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    for expected in pattern.split('/').filter(|s| !s.is_empty()) {
+        if expected == "*" {
+            return Some(params);
+        }
+        let segment = segments.next()?;
+        let name = expected
+            .strip_prefix(':')
+            .or_else(|| expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')));
+        match name {
+            Some(name) => {
+                params.insert(name.to_string(), segment.to_string());
+            }
+            None if expected != segment => return None,
+            None => {}
+        }
+    }
+    segments.next().is_none().then_some(params)
+}
+
+/// Whether every expected pair is among `actual`, an empty expected value matching any value.
+fn has_all(expected: &[KeyValue], actual: &[(String, String)], ignore_case: bool) -> bool {
+    expected.iter().filter(|kv| !kv.key.is_empty()).all(|kv| {
+        actual.iter().any(|(key, value)| {
+            let same_key = if ignore_case {
+                key.eq_ignore_ascii_case(&kv.key)
+            } else {
+                *key == kv.key
+            };
+            same_key && (kv.value.is_empty() || *value == kv.value)
+        })
+    })
+}
+
+/// The first enabled route matching the request, with its path parameters.
+fn find_route<'a>(
+    routes: &'a [MockRoute],
+    request: &MockRequest,
+) -> Option<(&'a MockRoute, HashMap<String, String>)> {
+    routes.iter().filter(|r| r.enabled).find_map(|route| {
+        let method = route.method.trim();
+        if !(method.is_empty() || method == "*" || method.eq_ignore_ascii_case(&request.method)) {
+            return None;
+        }
+        let params = match_path(&route.path, &request.path)?;
+        let matches = has_all(&route.headers, &request.headers, true)
+            && has_all(&route.query, &request.query, false)
+            && request.body.contains(&route.body);
+        matches.then_some((route, params))
+    })
+}
+
+/// The values response templates can reference.
+fn template_variables(
+    request: &MockRequest,
+    params: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut variables: HashMap<String, String> = params
+        .into_iter()
+        .map(|(name, value)| (format!("params.{name}"), value))
+        .collect();
+    for (name, value) in &request.query {
+        variables.insert(format!("query.{name}"), value.clone());
+    }
+    for (name, value) in &request.headers {
+        variables.insert(format!("headers.{}", name.to_lowercase()), value.clone());
+    }
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::from_str(&request.body) {
+        for (name, value) in fields {
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            };
+            variables.insert(format!("body.{name}"), value);
+        }
+    }
+    variables.insert("body".to_string(), request.body.clone());
+    variables.insert("method".to_string(), request.method.clone());
+    variables.insert("path".to_string(), request.path.clone());
+    variables
+}
+
+pub(crate) fn response(status: u16, headers: &[KeyValue], body: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+    for kv in headers.iter().filter(|kv| !kv.key.is_empty()) {
+        let Ok(name) = hyper::header::HeaderName::from_bytes(kv.key.as_bytes()) else {
+            continue;
+        };
+        if let Ok(value) = hyper::header::HeaderValue::from_str(&kv.value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
+
+async fn handle(
+    req: Request<Incoming>,
+    routes: Arc<RwLock<Vec<MockRoute>>>,
+    on_hit: Arc<dyn Fn(&MockHit) + Send + Sync>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|collected| String::from_utf8_lossy(&collected.to_bytes()).into_owned())
+        .unwrap_or_default();
+    let query = parts
+        .uri
+        .query()
+        .map(|query| {
+            reqwest::Url::parse(&format!("http://localhost/?{query}"))
+                .map(|url| url.query_pairs().into_owned().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let request = MockRequest {
+        method: parts.method.as_str().to_string(),
+        path: parts.uri.path().to_string(),
+        query,
+        headers: parts
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.to_string(), value)
+            })
+            .collect(),
+        body,
+    };
+
+    let route = {
+        let routes = routes.read().unwrap();
+        find_route(&routes, &request).map(|(route, params)| (route.clone(), params))
+    };
+    let (name, response) = match route {
+        Some((route, params)) => {
+            let variables = template_variables(&request, params);
+            let headers: Vec<KeyValue> = route
+                .response_headers
+                .iter()
+                .map(|kv| KeyValue {
+                    key: kv.key.clone(),
+                    value: environment::substitute(&kv.value, &variables),
+                })
+                .collect();
+            let body = environment::substitute(&route.response_body, &variables);
+            if route.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(route.delay_ms)).await;
+            }
+            (Some(route.name), response(route.status, &headers, body))
+        }
+        None => {
+            let body = format!("No mock route matches {} {}", request.method, request.path);
+            (None, response(404, &[], body))
+        }
+    };
+
+    on_hit(&MockHit {
+        time: rfc3339_now(),
+        method: request.method,
+        path: parts
+            .uri
+            .path_and_query()
+            .map(|p| p.to_string())
+            .unwrap_or(request.path),
+        route: name,
+        status: response.status().as_u16(),
+    });
+    Ok(response)
+}
+
+/// Starts serving `routes` on `port` of localhost, replacing the running server, and returns
+/// the port it listens on, which is picked by the system when `port` is 0. `on_hit` is called
+/// for every request it answers.
+pub async fn start(
+    port: u16,
+    routes: Vec<MockRoute>,
+    on_hit: impl Fn(&MockHit) + Send + Sync + 'static,
+) -> Result<u16, String> {
+    stop();
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {port}: {e}"))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let routes = Arc::new(RwLock::new(routes));
+    let on_hit: Arc<dyn Fn(&MockHit) + Send + Sync> = Arc::new(on_hit);
+    let served = routes.clone();
+    let task = tokio::spawn(async move {
+        // Connections are aborted along with the server when it stops.
+        let mut connections = JoinSet::new();
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::warn!("Mock server failed to accept a connection: {e}");
+                    continue;
+                }
+            };
+            while connections.try_join_next().is_some() {}
+            let (routes, on_hit) = (served.clone(), on_hit.clone());
+            connections.spawn(async move {
+                let service = service_fn(move |req| handle(req, routes.clone(), on_hit.clone()));
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    log::debug!("Mock server connection failed: {e}");
+                }
+            });
+        }
+    });
+
+    *SERVER.lock().unwrap() = Some(Running { port, routes, task });
+    Ok(port)
+}
+
+pub fn stop() {
+    if let Some(running) = SERVER.lock().unwrap().take() {
+        running.task.abort();
+    }
+}
+
+/// The port the mock server listens on, if it's running.
+pub fn running_port() -> Option<u16> {
+    SERVER.lock().unwrap().as_ref().map(|running| running.port)
+}
+
+pub fn get_mock_settings() -> Result<MockSettings, String> {
+    db::get_setting(SETTINGS_KEY)
+}
+
+/// Saves the mock server's settings, serving the new routes right away if it's running.
+pub fn save_mock_settings(settings: MockSettings) -> Result<(), String> {
+    db::put_setting(SETTINGS_KEY, &settings)?;
+    if let Some(running) = SERVER.lock().unwrap().as_ref() {
+        *running.routes.write().unwrap() = settings.routes;
+    }
+    Ok(())
+}
+
+/// Adds a route after the saved ones.
+pub fn add_mock_route(route: MockRoute) -> Result<MockSettings, String> {
+    let mut settings = get_mock_settings()?;
+    settings.routes.push(route);
+    save_mock_settings(settings.clone())?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn route(name: &str, method: &str, path: &str) -> MockRoute {
+        MockRoute {
+            name: name.to_string(),
+            enabled: true,
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: Vec::new(),
+            query: Vec::new(),
+            body: String::new(),
+            status: 200,
+            response_headers: Vec::new(),
+            response_body: String::new(),
+            delay_ms: 0,
+        }
+    }
+
+    fn request(method: &str, path: &str) -> MockRequest {
+        MockRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn matches_paths() {
+        let params = match_path("/users/:id/posts/{post}", "/users/7/posts/12/").unwrap();
+        assert_eq!(params["id"], "7");
+        assert_eq!(params["post"], "12");
+        assert!(match_path("/users/:id", "/users").is_none());
+        assert!(match_path("/users/:id", "/users/7/posts").is_none());
+        assert!(match_path("/static/*", "/static/css/app.css").is_some());
+        assert!(match_path("https://api.example.com/users?page=2", "/users").is_some());
+        assert!(match_path("https://api.example.com", "/").is_some());
+    }
+
+    #[test]
+    fn finds_the_first_matching_route() {
+        let mut admin = route("admin", "GET", "/users/:id");
+        admin.headers = vec![kv("Authorization", "")];
+        admin.query = vec![kv("role", "admin")];
+        let mut create = route("create", "POST", "/users");
+        create.body = "\"name\"".to_string();
+        let routes = [
+            admin,
+            route("disabled", "GET", "/users/:id"),
+            route("user", "*", "/users/:id"),
+            create,
+        ];
+        let mut routes = routes.to_vec();
+        routes[1].enabled = false;
+
+        let mut get = request("GET", "/users/1");
+        assert_eq!(find_route(&routes, &get).unwrap().0.name, "user");
+        get.headers = vec![("authorization".to_string(), "Bearer x".to_string())];
+        get.query = vec![("role".to_string(), "admin".to_string())];
+        assert_eq!(find_route(&routes, &get).unwrap().0.name, "admin");
+
+        let mut post = request("POST", "/users");
+        assert!(find_route(&routes, &post).is_none());
+        post.body = r#"{"name": "Ada"}"#.to_string();
+        assert_eq!(find_route(&routes, &post).unwrap().0.name, "create");
+    }
+
+    #[test]
+    fn fills_in_templates() {
+        let mut req = request("POST", "/users/7");
+        req.query = vec![("page".to_string(), "2".to_string())];
+        req.headers = vec![("X-Trace".to_string(), "abc".to_string())];
+        req.body = r#"{"name": "Ada", "age": 36}"#.to_string();
+        let params = HashMap::from([("id".to_string(), "7".to_string())]);
+        let variables = template_variables(&req, params);
+        assert_eq!(
+            environment::substitute(
+                "{{method}} {{params.id}} {{query.page}} {{headers.x-trace}} {{body.name}} {{body.age}} {{missing}}",
+                &variables
+            ),
+            "POST 7 2 abc Ada 36 {{missing}}"
+        );
+    }
+}
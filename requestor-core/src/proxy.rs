@@ -0,0 +1,84 @@
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+const SETTINGS_KEY: &str = "proxy";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// `http://`, `https://` or `socks5://` proxy URL.
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    /// Comma separated hosts, domains and CIDR ranges that bypass the proxy.
+    pub no_proxy: String,
+}
+
+/// Proxy applied to every request that doesn't override it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProxySettings {
+    pub proxy: ProxyConfig,
+    /// Falls back to the `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` environment and system
+    /// settings when no proxy URL is configured.
+    pub use_system: bool,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        ProxySettings {
+            proxy: ProxyConfig::default(),
+            use_system: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum RequestProxy {
+    /// Use the global proxy settings.
+    #[default]
+    Inherit,
+    /// Connect directly, ignoring global and system proxies.
+    Direct,
+    Custom(ProxyConfig),
+}
+
+impl ProxyConfig {
+    fn to_proxy(&self) -> Result<Proxy, String> {
+        let mut proxy = Proxy::all(self.url.trim()).map_err(|e| e.to_string())?;
+        if !self.username.is_empty() {
+            proxy = proxy.basic_auth(&self.username, &self.password);
+        }
+        Ok(proxy.no_proxy(NoProxy::from_string(&self.no_proxy)))
+    }
+}
+
+/// Configures `builder` with the proxy a request should go through.
+pub fn apply(builder: ClientBuilder, request: &RequestProxy) -> Result<ClientBuilder, String> {
+    match request {
+        RequestProxy::Direct => Ok(builder.no_proxy()),
+        RequestProxy::Custom(config) if !config.url.trim().is_empty() => {
+            Ok(builder.proxy(config.to_proxy()?))
+        }
+        RequestProxy::Custom(_) => Ok(builder.no_proxy()),
+        RequestProxy::Inherit => {
+            let settings = get_proxy_settings()?;
+            if !settings.proxy.url.trim().is_empty() {
+                Ok(builder.proxy(settings.proxy.to_proxy()?))
+            } else if settings.use_system {
+                Ok(builder)
+            } else {
+                Ok(builder.no_proxy())
+            }
+        }
+    }
+}
+
+pub fn get_proxy_settings() -> Result<ProxySettings, String> {
+    db::get_setting(SETTINGS_KEY)
+}
+
+pub fn save_proxy_settings(settings: ProxySettings) -> Result<(), String> {
+    db::put_setting(SETTINGS_KEY, &settings)
+}
@@ -0,0 +1,626 @@
+use std::sync::Arc;
+
+use http_body_util::BodyExt;
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+use crate::{cancel, jsonrpc, prepare_request, tls, KeyValue, RequestArgs, ResponseData};
+
+/// What a part of the response was on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    /// Status line and headers, including informational responses.
+    Head,
+    /// The size line starting a chunk of a chunked body.
+    ChunkSize,
+    Data,
+    Trailers,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WireSegment {
+    pub kind: SegmentKind,
+    /// The bytes received, with `\r` and other unprintable bytes escaped.
+    pub text: String,
+}
+
+/// The exact bytes of a request and its response.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WireExchange {
+    pub request: String,
+    pub response: Vec<WireSegment>,
+}
+
+/// A hand written HTTP/1.1 request and where to send it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RawRequest {
+    /// `http://host:port`, `https://host:port` or `unix:<socket path>`.
+    pub target: String,
+    pub text: String,
+    /// Set `Content-Length` to the size of the body as written.
+    #[serde(default)]
+    pub fix_content_length: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Target {
+    Tcp { host: String, port: u16, tls: bool },
+    Unix(String),
+}
+
+impl Target {
+    fn parse(target: &str) -> Result<Target, String> {
+        let target = target.trim();
+        if let Some((socket, _)) = unix_url(target) {
+            return Ok(Target::Unix(socket.to_string()));
+        }
+        let url = reqwest::Url::parse(target).map_err(|e| format!("{target}: {e}"))?;
+        let tls = match url.scheme() {
+            "http" => false,
+            "https" => true,
+            other => {
+                return Err(format!(
+                    "Unsupported scheme {other}, use http, https or unix"
+                ))
+            }
+        };
+        // IPv6 literals are connected to without the brackets the URL writes them in.
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("{target}: no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        Ok(Target::Tcp {
+            host,
+            port: url.port_or_known_default().unwrap_or(80),
+            tls,
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Target::Tcp { host, port, tls } => {
+                let scheme = if *tls { "https" } else { "http" };
+                match host.contains(':') {
+                    true => format!("{scheme}://[{host}]:{port}"),
+                    false => format!("{scheme}://{host}:{port}"),
+                }
+            }
+            Target::Unix(socket) => format!("unix:{socket}"),
+        }
+    }
+}
+
+/// Splits a `unix:<socket path>:<request path>` URL into the socket and the request path,
+/// which defaults to `/`.
+pub fn unix_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("unix:")?;
+    Some(match rest.find(":/") {
+        Some(idx) => (&rest[..idx], &rest[idx + 1..]),
+        None => (rest, "/"),
+    })
+}
+
+/// Whether a request is sent over the raw HTTP/1.1 connection rather than the HTTP client.
+pub fn is_raw(args: &RequestArgs) -> bool {
+    args.wire || unix_url(&args.url).is_some()
+}
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// Connects to `target`, returning the certificate chain the server presented over TLS.
+async fn connect(
+    target: &Target,
+) -> Result<(Box<dyn Connection>, Vec<tls::CertificateInfo>), String> {
+    match target {
+        Target::Tcp { host, port, tls } => {
+            let stream = TcpStream::connect((host.as_str(), *port))
+                .await
+                .map_err(|e| format!("{}: {e}", target.describe()))?;
+            if !tls {
+                return Ok((Box::new(stream), Vec::new()));
+            }
+            let mut config = tls::client_config(tls::config_for_host(host)?.as_ref())?;
+            // Only HTTP/1.1 can be written out by hand.
+            config.alpn_protocols = vec![b"http/1.1".to_vec()];
+            let name = ServerName::try_from(host.clone()).map_err(|e| e.to_string())?;
+            let stream = TlsConnector::from(Arc::new(config))
+                .connect(name, stream)
+                .await
+                .map_err(|e| e.to_string())?;
+            let certificates = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .map(tls::describe_chain)
+                .unwrap_or_default();
+            Ok((Box::new(stream), certificates))
+        }
+        #[cfg(unix)]
+        Target::Unix(socket) => {
+            let stream = tokio::net::UnixStream::connect(socket)
+                .await
+                .map_err(|e| format!("{socket}: {e}"))?;
+            Ok((Box::new(stream), Vec::new()))
+        }
+        #[cfg(not(unix))]
+        Target::Unix(_) => Err("Unix sockets aren't supported on this platform".to_string()),
+    }
+}
+
+/// Shows bytes as text, escaping `\r` and bytes that aren't printable UTF-8 so the exact
+/// bytes can be read back.
+pub fn escape(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\r' => text.push_str("\\r"),
+                '\n' | '\t' => text.push(c),
+                c if c.is_control() => text.push_str(&format!("\\x{:02x}", c as u32)),
+                c => text.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            text.push_str(&format!("\\x{byte:02x}"));
+        }
+    }
+    text
+}
+
+/// The header name of a Content-Length line as it's spelled there.
+fn content_length_name(line: &str) -> Option<&str> {
+    line.split_once(':')
+        .map(|(name, _)| name.trim())
+        .filter(|name| name.eq_ignore_ascii_case("content-length"))
+}
+
+/// Turns text typed into the editor into request bytes: the head gets CRLF line endings
+/// while the body is sent as written.
+fn request_bytes(text: &str, fix_content_length: bool) -> Vec<u8> {
+    let blank_line = ["\r\n\r\n", "\n\n"]
+        .iter()
+        .filter_map(|separator| text.find(separator).map(|idx| (idx, separator.len())))
+        .min();
+    let (head, body) = match blank_line {
+        Some((idx, len)) => (&text[..idx], &text[idx + len..]),
+        None => (text.trim_end_matches(['\r', '\n']), ""),
+    };
+    let mut lines: Vec<&str> = head.lines().map(|l| l.trim_end_matches('\r')).collect();
+
+    let chunked = lines.iter().skip(1).any(|l| {
+        l.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.to_ascii_lowercase().contains("chunked")
+        })
+    });
+    let content_length;
+    if fix_content_length && !chunked {
+        // The first Content-Length keeps its place and spelling, so unedited requests are sent
+        // as they were rendered.
+        let at = lines.iter().position(|l| content_length_name(l).is_some());
+        let name = at
+            .and_then(|at| content_length_name(lines[at]))
+            .unwrap_or("Content-Length");
+        content_length = format!("{name}: {}", body.len());
+        lines.retain(|l| content_length_name(l).is_none());
+        match at {
+            _ if body.is_empty() => {}
+            Some(at) => lines.insert(at, &content_length),
+            None => lines.push(&content_length),
+        }
+    }
+
+    let mut bytes = lines.join("\r\n").into_bytes();
+    bytes.extend_from_slice(b"\r\n\r\n");
+    bytes.extend_from_slice(body.as_bytes());
+    bytes
+}
+
+/// Renders the request the HTTP client would send for `args` as HTTP/1.1 bytes.
+async fn render(mut args: RequestArgs) -> Result<(Target, Vec<u8>), String> {
+    let unix = unix_url(&args.url).map(|(socket, path)| (socket.to_string(), path.to_string()));
+    if let Some((_, path)) = &unix {
+        args.url = format!("http://localhost{path}");
+    }
+    let mut request = prepare_request(args)
+        .await?
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let url = request.url().clone();
+    let target = match unix {
+        Some((socket, _)) => Target::Unix(socket),
+        None => Target::parse(url.as_str())?,
+    };
+    let body = match request.body_mut().take() {
+        Some(body) => body.collect().await.map_err(|e| e.to_string())?.to_bytes(),
+        None => Default::default(),
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let mut bytes = format!("{} {path} HTTP/1.1\r\n", request.method()).into_bytes();
+    let headers = request.headers();
+    let mut add_header = |name: &str, value: &[u8]| {
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(b": ");
+        bytes.extend_from_slice(value);
+        bytes.extend_from_slice(b"\r\n");
+    };
+    if !headers.contains_key("host") {
+        add_header("Host", host.as_bytes());
+    }
+    for (name, value) in headers {
+        add_header(name.as_str(), value.as_bytes());
+    }
+    if !body.is_empty() && !headers.contains_key("content-length") {
+        add_header("Content-Length", body.len().to_string().as_bytes());
+    }
+    // The response is read to the end when it doesn't say how long it is.
+    if !headers.contains_key("connection") {
+        add_header("Connection", b"close");
+    }
+    bytes.extend_from_slice(b"\r\n");
+    bytes.extend_from_slice(&body);
+    Ok((target, bytes))
+}
+
+struct RawResponse {
+    status: u16,
+    headers: Vec<KeyValue>,
+    body: Vec<u8>,
+    segments: Vec<WireSegment>,
+}
+
+fn segment(kind: SegmentKind, bytes: &[u8]) -> WireSegment {
+    WireSegment {
+        kind,
+        text: escape(bytes),
+    }
+}
+
+async fn read_line(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<Vec<u8>, String> {
+    let mut line = Vec::new();
+    reader
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(line)
+}
+
+/// Reads the head of a response up to and including the blank line ending it.
+async fn read_head(
+    reader: &mut (impl AsyncBufRead + Unpin),
+) -> Result<(Vec<u8>, u16, Vec<KeyValue>), String> {
+    let mut head = Vec::new();
+    let status_line = read_line(reader).await?;
+    if status_line.is_empty() {
+        return Err("Connection closed before a response was received".to_string());
+    }
+    let status = String::from_utf8_lossy(&status_line)
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("Invalid status line {:?}", escape(&status_line)))?;
+    head.extend_from_slice(&status_line);
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader).await?;
+        head.extend_from_slice(&line);
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches(['\r', '\n']);
+        if text.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = text.split_once(':') {
+            headers.push(KeyValue {
+                key: name.trim().to_string(),
+                value: value.trim().to_string(),
+            });
+        }
+    }
+    Ok((head, status, headers))
+}
+
+/// Reads exactly `length` bytes. The buffer grows with the bytes received rather than being
+/// sized upfront from a length the server sent, which may be far larger than what it sends.
+async fn read_length(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    length: u64,
+) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    (&mut *reader)
+        .take(length)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+    if (bytes.len() as u64) < length {
+        return Err(format!(
+            "Connection closed after {} of {length} bytes",
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Reads a response, recording its head and how the body was framed.
+async fn read_response(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    head_request: bool,
+) -> Result<RawResponse, String> {
+    let mut segments = Vec::new();
+    let (status, headers) = loop {
+        let (head, status, headers) = read_head(reader).await?;
+        segments.push(segment(SegmentKind::Head, &head));
+        // Informational responses precede the actual one, except for protocol switches.
+        if !(100..200).contains(&status) || status == 101 {
+            break (status, headers);
+        }
+    };
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str())
+    };
+
+    let mut body = Vec::new();
+    if head_request || status == 204 || status == 304 || (100..200).contains(&status) {
+        return Ok(RawResponse {
+            status,
+            headers,
+            body,
+            segments,
+        });
+    }
+
+    if header("transfer-encoding").is_some_and(|te| te.to_ascii_lowercase().contains("chunked")) {
+        loop {
+            let size_line = read_line(reader).await?;
+            if size_line.is_empty() {
+                return Err("Connection closed in the middle of a chunked body".to_string());
+            }
+            segments.push(segment(SegmentKind::ChunkSize, &size_line));
+            let size_text = String::from_utf8_lossy(&size_line);
+            let size_text = size_text.split(';').next().unwrap_or_default().trim();
+            let size = u64::from_str_radix(size_text, 16)
+                .map_err(|_| format!("Invalid chunk size {size_text:?}"))?;
+            if size == 0 {
+                let mut trailers = Vec::new();
+                loop {
+                    let line = read_line(reader).await?;
+                    trailers.extend_from_slice(&line);
+                    if line.is_empty() || line == b"\r\n" || line == b"\n" {
+                        break;
+                    }
+                }
+                segments.push(segment(SegmentKind::Trailers, &trailers));
+                break;
+            }
+            let mut chunk = read_length(reader, size).await?;
+            body.extend_from_slice(&chunk);
+            chunk.extend(read_line(reader).await?);
+            segments.push(segment(SegmentKind::Data, &chunk));
+        }
+    } else if let Some(length) = header("content-length") {
+        let length: u64 = length
+            .parse()
+            .map_err(|_| format!("Invalid Content-Length {length:?}"))?;
+        body = read_length(reader, length).await?;
+        segments.push(segment(SegmentKind::Data, &body));
+    } else {
+        reader
+            .read_to_end(&mut body)
+            .await
+            .map_err(|e| e.to_string())?;
+        segments.push(segment(SegmentKind::Data, &body));
+    }
+
+    Ok(RawResponse {
+        status,
+        headers,
+        body,
+        segments,
+    })
+}
+
+/// Writes `request` to `target` exactly as given and reads back the response.
+async fn exchange(target: &Target, request: Vec<u8>) -> Result<ResponseData, String> {
+    let start = std::time::Instant::now();
+    let (mut connection, certificates) = connect(target).await?;
+    connection
+        .write_all(&request)
+        .await
+        .map_err(|e| e.to_string())?;
+    connection.flush().await.map_err(|e| e.to_string())?;
+
+    let mut reader = BufReader::new(connection);
+    let response = read_response(&mut reader, request.starts_with(b"HEAD ")).await?;
+
+    Ok(ResponseData {
+        status: response.status,
+        headers: response.headers,
+        body: String::from_utf8_lossy(&response.body).into_owned(),
+        response_time: start.elapsed().as_secs_f32() * 1000.0,
+        certificates,
+        wire: Some(WireExchange {
+            request: escape(&request),
+            response: response.segments,
+        }),
+        ..Default::default()
+    })
+}
+
+/// Sends a request over a plain HTTP/1.1 connection, keeping the bytes exchanged. The
+/// connection is made directly, the request's proxy isn't used.
+pub async fn send(args: RequestArgs) -> Result<ResponseData, String> {
+    let (target, request) = render(args).await?;
+    exchange(&target, request).await
+}
+
+/// The editor's text for a rendered request. Only the head's line endings are changed,
+/// [`request_bytes`] puts them back while sending the body as written.
+fn editor_text(request: Vec<u8>) -> Result<String, String> {
+    let text = String::from_utf8(request)
+        .map_err(|_| "The request's body isn't text and can't be edited".to_string())?;
+    Ok(match text.split_once("\r\n\r\n") {
+        Some((head, body)) => format!("{}\n\n{body}", head.replace("\r\n", "\n")),
+        None => text.replace("\r\n", "\n"),
+    })
+}
+
+/// Renders a request as the raw HTTP/1.1 text the editor sends.
+pub async fn render_raw_request(mut args: RequestArgs) -> Result<RawRequest, String> {
+    jsonrpc::assign_ids(&mut args.body);
+    let (target, request) = render(args).await?;
+    Ok(RawRequest {
+        target: target.describe(),
+        text: editor_text(request)?,
+        fix_content_length: true,
+    })
+}
+
+/// Sends a hand written request, `id` identifies it to `cancel_request` while it is in flight.
+pub async fn send_raw_request(id: u64, args: RawRequest) -> Result<ResponseData, String> {
+    let target = Target::parse(&args.target)?;
+    let request = request_bytes(&args.text, args.fix_content_length);
+    cancel::cancellable(id, exchange(&target, request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::{MultipartPart, RequestBody};
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!(
+            unix_url("unix:/var/run/docker.sock:/v1.43/info"),
+            Some(("/var/run/docker.sock", "/v1.43/info"))
+        );
+        assert_eq!(
+            Target::parse("unix:/tmp/app.sock").unwrap(),
+            Target::Unix("/tmp/app.sock".to_string())
+        );
+        assert_eq!(
+            Target::parse("https://example.com/ignored").unwrap(),
+            Target::Tcp {
+                host: "example.com".to_string(),
+                port: 443,
+                tls: true
+            }
+        );
+        let ipv6 = Target::parse("http://[::1]:8080/").unwrap();
+        assert_eq!(
+            ipv6,
+            Target::Tcp {
+                host: "::1".to_string(),
+                port: 8080,
+                tls: false
+            }
+        );
+        assert_eq!(ipv6.describe(), "http://[::1]:8080");
+        assert!(Target::parse("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn normalizes_edited_requests() {
+        let bytes = request_bytes(
+            "POST /items HTTP/1.1\nHost: x\nContent-Length: 1\n\n{\"a\": 1}\n",
+            true,
+        );
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "POST /items HTTP/1.1\r\nHost: x\r\nContent-Length: 9\r\n\r\n{\"a\": 1}\n"
+        );
+        let bytes = request_bytes("GET / HTTP/1.1\nHost: x\n", false);
+        assert_eq!(bytes, b"GET / HTTP/1.1\r\nHost: x\r\n\r\n");
+    }
+
+    #[tokio::test]
+    async fn reads_chunked_responses_with_boundaries() {
+        let wire: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\n\
+            HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+            5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\nX-Checksum: 1\r\n\r\n";
+        let response = read_response(&mut BufReader::new(wire), false)
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"hello world");
+        let kinds: Vec<_> = response.segments.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                SegmentKind::Head,
+                SegmentKind::Head,
+                SegmentKind::ChunkSize,
+                SegmentKind::Data,
+                SegmentKind::ChunkSize,
+                SegmentKind::Data,
+                SegmentKind::ChunkSize,
+                SegmentKind::Trailers,
+            ]
+        );
+        assert_eq!(response.segments[3].text, "hello\\r\n");
+        assert_eq!(escape(&[0xff, b'\r', 0x01]), "\\xff\\r\\x01");
+    }
+
+    #[tokio::test]
+    async fn rejects_bodies_shorter_than_announced() {
+        let wire: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 99999999999\r\n\r\nshort";
+        let error = read_response(&mut BufReader::new(wire), false)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error, "Connection closed after 5 of 99999999999 bytes");
+
+        let wire: &[u8] =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffff\r\nshort";
+        assert!(read_response(&mut BufReader::new(wire), false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn edits_rendered_requests_without_changing_them() {
+        crate::db::set_path(std::env::temp_dir().join("requestor-raw-test.db"));
+        let part = |name: &str, value: &str| MultipartPart {
+            name: name.to_string(),
+            value: value.to_string(),
+            ..Default::default()
+        };
+        let args = RequestArgs {
+            method: "POST".parse().unwrap(),
+            url: "http://example.com/upload".to_string(),
+            body: RequestBody::Multipart {
+                parts: vec![part("title", "notes"), part("text", "one\r\ntwo\n")],
+            },
+            ..Default::default()
+        };
+        let (_, request) = render(args).await.unwrap();
+        let text = editor_text(request.clone()).unwrap();
+        assert!(text.starts_with("POST /upload HTTP/1.1\nHost: example.com\n"));
+        assert_eq!(request_bytes(&text, true), request);
+
+        let mut binary = b"POST / HTTP/1.1\r\n\r\n".to_vec();
+        binary.push(0xff);
+        assert!(editor_text(binary).is_err());
+    }
+}
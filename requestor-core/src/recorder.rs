@@ -0,0 +1,730 @@
+//! A recording proxy. Clients pointed at it have their HTTP and HTTPS traffic forwarded and
+//! stored, HTTPS being intercepted with certificates issued by a local certificate authority,
+//! and the stored exchanges can be served back instead of reaching the network.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::{Datelike, Duration, Utc};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::http::request::Parts;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use once_cell::sync::Lazy;
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose};
+use reqwest::redirect::Policy;
+use rusqlite::params;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::ServerConfig;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_rustls::TlsAcceptor;
+
+use crate::body::RequestBody;
+use crate::proxy::RequestProxy;
+use crate::{client, db, rfc3339_now, KeyValue, RequestArgs, ResponseData};
+
+const CA_SETTINGS_KEY: &str = "recorder_ca";
+
+const CA_NAME: &str = "Requestor recording proxy CA";
+
+/// Headers that only concern a single connection and aren't forwarded.
+const HOP_BY_HOP: [&str; 9] = [
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+static SERVER: Lazy<Mutex<Option<Running>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecorderMode {
+    /// Forward requests and store the exchanges.
+    #[default]
+    Record,
+    /// Answer requests with stored exchanges without reaching the network.
+    Replay,
+}
+
+/// A request that went through the recorder and the response it got.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub id: i64,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<KeyValue>,
+    pub request_body: String,
+    pub status: u16,
+    pub response_headers: Vec<KeyValue>,
+    /// The response body, with bytes that aren't valid UTF-8 replaced.
+    pub response_body: String,
+    pub response_time: f32,
+    pub created_at: String,
+    /// Whether the response was served from a stored exchange.
+    #[serde(default)]
+    pub replayed: bool,
+}
+
+impl Recording {
+    /// The recorded request, to be opened in the editor or saved to the history.
+    pub fn request(&self) -> RequestArgs {
+        request_from_parts(
+            &self.method,
+            &self.url,
+            &self.request_headers,
+            &self.request_body,
+        )
+    }
+
+    pub fn response(&self) -> ResponseData {
+        ResponseData {
+            status: self.status,
+            headers: self.response_headers.clone(),
+            body: self.response_body.clone(),
+            response_time: self.response_time,
+            ..Default::default()
+        }
+    }
+}
+
+/// A request received by one of the app's servers, as a request that can be sent again. The
+/// headers describing the received message, rather than the request, are left out.
+pub(crate) fn request_from_parts(
+    method: &str,
+    url: &str,
+    headers: &[KeyValue],
+    body: &str,
+) -> RequestArgs {
+    let content_type = headers
+        .iter()
+        .find(|kv| kv.key.eq_ignore_ascii_case("content-type"))
+        .map(|kv| kv.value.clone())
+        .unwrap_or_default();
+    RequestArgs {
+        method: method.parse().unwrap_or_default(),
+        url: url.to_string(),
+        headers: headers
+            .iter()
+            .filter(|kv| {
+                !["host", "content-length"]
+                    .iter()
+                    .any(|name| kv.key.eq_ignore_ascii_case(name))
+            })
+            .cloned()
+            .collect(),
+        body: match body.is_empty() {
+            true => RequestBody::None,
+            false => RequestBody::Raw {
+                content_type,
+                text: body.to_string(),
+            },
+        },
+        ..Default::default()
+    }
+}
+
+/// A stored response, served back in replay mode.
+struct Replay {
+    recording: Recording,
+    body: Bytes,
+}
+
+/// Stored responses by method and URL, served in the order they were recorded.
+#[derive(Default)]
+struct Replays {
+    responses: HashMap<(String, String), Vec<Replay>>,
+    served: HashMap<(String, String), usize>,
+}
+
+impl Replays {
+    /// The next response for a request, repeating the last one once they've all been served.
+    fn next(&mut self, method: &str, url: &str) -> Option<&Replay> {
+        let key = (method.to_string(), url.to_string());
+        let responses = self.responses.get(&key)?;
+        let served = self.served.entry(key).or_default();
+        let replay = responses.get(*served).or(responses.last());
+        *served += 1;
+        replay
+    }
+}
+
+/// The certificate authority that signs the certificates presented to intercepted clients.
+struct Authority {
+    issuer: Issuer<'static, KeyPair>,
+    certificate: CertificateDer<'static>,
+    /// TLS configurations already issued, by host.
+    configs: Mutex<HashMap<String, Arc<ServerConfig>>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoredAuthority {
+    cert_pem: String,
+    key_pem: String,
+}
+
+struct Proxy {
+    mode: RecorderMode,
+    authority: Authority,
+    replays: Mutex<Replays>,
+    on_exchange: Box<dyn Fn(&Recording) + Send + Sync>,
+    /// Hands intercepted CONNECT tunnels to the accept loop, which runs them along with the
+    /// connections.
+    tunnels: mpsc::UnboundedSender<Tunnel>,
+}
+
+type Tunnel = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct Running {
+    port: u16,
+    mode: RecorderMode,
+    task: JoinHandle<()>,
+}
+
+fn validity(params: &mut CertificateParams, days: i64) {
+    let date = |date: chrono::DateTime<Utc>| {
+        rcgen::date_time_ymd(date.year(), date.month() as u8, date.day() as u8)
+    };
+    let now = Utc::now();
+    params.not_before = date(now - Duration::days(1));
+    params.not_after = date(now + Duration::days(days));
+}
+
+/// The saved certificate authority, created on first use.
+fn stored_authority() -> Result<StoredAuthority, String> {
+    let stored: StoredAuthority = db::get_setting(CA_SETTINGS_KEY)?;
+    if !stored.cert_pem.is_empty() {
+        return Ok(stored);
+    }
+
+    let key = KeyPair::generate().map_err(|e| e.to_string())?;
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(DnType::CommonName, CA_NAME);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    validity(&mut params, 3650);
+    let cert = params.self_signed(&key).map_err(|e| e.to_string())?;
+
+    let stored = StoredAuthority {
+        cert_pem: cert.pem(),
+        key_pem: key.serialize_pem(),
+    };
+    db::put_setting(CA_SETTINGS_KEY, &stored)?;
+    Ok(stored)
+}
+
+/// The certificate of the recorder's certificate authority in PEM format, which clients must
+/// trust for their HTTPS traffic to be recorded.
+pub fn ca_certificate() -> Result<String, String> {
+    stored_authority().map(|stored| stored.cert_pem)
+}
+
+impl Authority {
+    fn load() -> Result<Authority, String> {
+        let stored = stored_authority()?;
+        let key = KeyPair::from_pem(&stored.key_pem).map_err(|e| e.to_string())?;
+        Ok(Authority {
+            issuer: Issuer::from_ca_cert_pem(&stored.cert_pem, key).map_err(|e| e.to_string())?,
+            certificate: CertificateDer::from_pem_slice(stored.cert_pem.as_bytes())
+                .map_err(|e| e.to_string())?,
+            configs: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The TLS configuration presenting a certificate for `host` signed by the authority.
+    fn server_config(&self, host: &str) -> Result<Arc<ServerConfig>, String> {
+        if let Some(config) = self.configs.lock().unwrap().get(host) {
+            return Ok(config.clone());
+        }
+
+        let key = KeyPair::generate().map_err(|e| e.to_string())?;
+        let mut params =
+            CertificateParams::new(vec![host.to_string()]).map_err(|e| e.to_string())?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        validity(&mut params, 365);
+        let cert = params
+            .signed_by(&key, &self.issuer)
+            .map_err(|e| e.to_string())?;
+
+        let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![cert.der().clone(), self.certificate.clone()],
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der())),
+            )
+            .map_err(|e| e.to_string())?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        let config = Arc::new(config);
+        self.configs
+            .lock()
+            .unwrap()
+            .insert(host.to_string(), config.clone());
+        Ok(config)
+    }
+}
+
+fn key_values(headers: &hyper::HeaderMap, skip: &[&str]) -> Vec<KeyValue> {
+    headers
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP.contains(&name.as_str()) && !skip.contains(&name.as_str()))
+        .map(|(name, value)| KeyValue {
+            key: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
+
+fn response(status: u16, headers: &[KeyValue], body: Bytes) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(body));
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+    for kv in headers {
+        // The length is set for the body served, which is never chunked.
+        if kv.key.eq_ignore_ascii_case("content-length") {
+            continue;
+        }
+        let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(kv.key.as_bytes()),
+            HeaderValue::from_str(&kv.value),
+        ) else {
+            continue;
+        };
+        response.headers_mut().append(name, value);
+    }
+    response
+}
+
+fn error_response(status: StatusCode, message: String) -> Response<Full<Bytes>> {
+    response(status.as_u16(), &[], Bytes::from(message))
+}
+
+/// Sends a request to its destination, without following redirects so the client sees them.
+async fn forward(
+    parts: &Parts,
+    body: Bytes,
+    url: &str,
+) -> Result<(u16, hyper::HeaderMap, Bytes), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    // Connect directly in case the app's own proxy settings point at the recorder.
+    let client = client::client_builder(&parsed, &RequestProxy::Direct)?
+        .redirect(Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut request = client.request(parts.method.clone(), parsed).body(body);
+    for (name, value) in parts.headers.iter() {
+        // Responses are stored as they arrive, so ask for them uncompressed to keep them
+        // readable.
+        if HOP_BY_HOP.contains(&name.as_str()) || name == "host" || name == "accept-encoding" {
+            continue;
+        }
+        request = request.header(name, value);
+    }
+    let res = request.send().await.map_err(|e| e.to_string())?;
+    let status = res.status().as_u16();
+    let headers = res.headers().clone();
+    let body = res.bytes().await.map_err(|e| e.to_string())?;
+    Ok((status, headers, body))
+}
+
+impl Proxy {
+    /// Answers a request for `url`, forwarding or replaying it depending on the mode.
+    async fn exchange(&self, req: Request<Incoming>, url: String) -> Response<Full<Bytes>> {
+        let start = Instant::now();
+        let (parts, body) = req.into_parts();
+        let body = match body.collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, e.to_string()),
+        };
+        let method = parts.method.to_string();
+
+        if self.mode == RecorderMode::Replay {
+            let mut replays = self.replays.lock().unwrap();
+            let Some(replay) = replays.next(&method, &url) else {
+                return error_response(
+                    StatusCode::GATEWAY_TIMEOUT,
+                    format!("Nothing was recorded for {method} {url}"),
+                );
+            };
+            let mut recording = replay.recording.clone();
+            recording.replayed = true;
+            recording.created_at = rfc3339_now();
+            let served = response(
+                recording.status,
+                &recording.response_headers,
+                replay.body.clone(),
+            );
+            drop(replays);
+            (self.on_exchange)(&recording);
+            return served;
+        }
+
+        let (status, headers, response_body) = match forward(&parts, body.clone(), &url).await {
+            Ok(forwarded) => forwarded,
+            Err(e) => return error_response(StatusCode::BAD_GATEWAY, e),
+        };
+        let mut recording = Recording {
+            id: 0,
+            method,
+            url,
+            request_headers: key_values(&parts.headers, &["accept-encoding"]),
+            request_body: String::from_utf8_lossy(&body).into_owned(),
+            status,
+            response_headers: key_values(&headers, &[]),
+            response_body: String::from_utf8_lossy(&response_body).into_owned(),
+            response_time: start.elapsed().as_secs_f32() * 1000.0,
+            created_at: rfc3339_now(),
+            replayed: false,
+        };
+        if let Err(e) = save_recording(&mut recording, &response_body) {
+            log::warn!("Failed to save a recorded exchange: {e}");
+        }
+        (self.on_exchange)(&recording);
+        response(status, &recording.response_headers, response_body)
+    }
+
+    async fn handle(
+        self: Arc<Self>,
+        req: Request<Incoming>,
+    ) -> Result<Response<Full<Bytes>>, Infallible> {
+        if req.method() == Method::CONNECT {
+            let Some(authority) = req.uri().authority().cloned() else {
+                return Ok(error_response(
+                    StatusCode::BAD_REQUEST,
+                    "CONNECT needs a host and port".to_string(),
+                ));
+            };
+            let tunnels = self.tunnels.clone();
+            let _ = tunnels.send(Box::pin(async move {
+                match hyper::upgrade::on(req).await {
+                    Ok(upgraded) => {
+                        let port = authority.port_u16().unwrap_or(443);
+                        self.intercept(TokioIo::new(upgraded), authority.host(), port)
+                            .await
+                    }
+                    Err(e) => log::debug!("Recorder failed to upgrade a CONNECT request: {e}"),
+                }
+            }));
+            return Ok(Response::new(Full::new(Bytes::new())));
+        }
+
+        // Requests sent through a proxy carry the absolute URL.
+        let url = req.uri().to_string();
+        if !url.starts_with("http://") {
+            return Ok(error_response(
+                StatusCode::BAD_REQUEST,
+                "The recorder is a proxy, send requests through it rather than to it".to_string(),
+            ));
+        }
+        Ok(self.exchange(req, url).await)
+    }
+
+    /// Serves the HTTPS connection tunnelled to `host`, decrypting it with a certificate issued
+    /// by the recorder's authority.
+    async fn intercept(
+        self: Arc<Self>,
+        stream: impl AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        host: &str,
+        port: u16,
+    ) {
+        let config = match self.authority.server_config(host) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("Recorder failed to issue a certificate for {host}: {e}");
+                return;
+            }
+        };
+        let stream = match TlsAcceptor::from(config).accept(stream).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::debug!("TLS handshake with a client for {host} failed: {e}");
+                return;
+            }
+        };
+        let origin = match port {
+            443 => format!("https://{host}"),
+            port => format!("https://{host}:{port}"),
+        };
+        let service = service_fn(move |req: Request<Incoming>| {
+            let proxy = self.clone();
+            let url = format!(
+                "{origin}{}",
+                req.uri().path_and_query().map_or("/", |p| p.as_str())
+            );
+            async move { Ok::<_, Infallible>(proxy.exchange(req, url).await) }
+        });
+        if let Err(e) = http1::Builder::new()
+            .serve_connection(TokioIo::new(stream), service)
+            .await
+        {
+            log::debug!("Recorder connection for {host} failed: {e}");
+        }
+    }
+}
+
+fn save_recording(recording: &mut Recording, body: &[u8]) -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute(
+        "INSERT INTO recordings (method, url, request_headers, request_body, status, response_headers, response_body, response_time, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            recording.method,
+            recording.url,
+            serde_json::to_string(&recording.request_headers).map_err(|e| e.to_string())?,
+            recording.request_body,
+            recording.status,
+            serde_json::to_string(&recording.response_headers).map_err(|e| e.to_string())?,
+            body,
+            recording.response_time,
+            recording.created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    recording.id = conn.last_insert_rowid();
+    Ok(())
+}
+
+/// The stored exchanges in the order they were recorded, with their response bodies.
+fn load_recordings() -> Result<Vec<(Recording, Vec<u8>)>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare("SELECT id, method, url, request_headers, request_body, status, response_headers, response_body, response_time, created_at FROM recordings ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let body: Vec<u8> = row.get(7)?;
+            Ok((
+                Recording {
+                    id: row.get(0)?,
+                    method: row.get(1)?,
+                    url: row.get(2)?,
+                    request_headers: serde_json::from_str(&row.get::<_, String>(3)?)
+                        .unwrap_or_default(),
+                    request_body: row.get(4)?,
+                    status: row.get(5)?,
+                    response_headers: serde_json::from_str(&row.get::<_, String>(6)?)
+                        .unwrap_or_default(),
+                    response_body: String::from_utf8_lossy(&body).into_owned(),
+                    response_time: row.get(8)?,
+                    created_at: row.get(9)?,
+                    replayed: false,
+                },
+                body,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+pub fn get_recordings() -> Result<Vec<Recording>, String> {
+    Ok(load_recordings()?
+        .into_iter()
+        .map(|(recording, _)| recording)
+        .collect())
+}
+
+pub fn clear_recordings() -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute("DELETE FROM recordings", [])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn replays(recordings: Vec<(Recording, Vec<u8>)>) -> Replays {
+    let mut replays = Replays::default();
+    for (recording, body) in recordings {
+        replays
+            .responses
+            .entry((recording.method.clone(), recording.url.clone()))
+            .or_default()
+            .push(Replay {
+                recording,
+                body: Bytes::from(body),
+            });
+    }
+    replays
+}
+
+/// Starts the recorder on `port` of localhost, replacing the running one, and returns the port
+/// it listens on, which is picked by the system when `port` is 0. `on_exchange` is called for
+/// every request answered with a response from the destination or a recording.
+pub async fn start(
+    port: u16,
+    mode: RecorderMode,
+    on_exchange: impl Fn(&Recording) + Send + Sync + 'static,
+) -> Result<u16, String> {
+    stop();
+    let (tunnels, mut tunnel_rx) = mpsc::unbounded_channel();
+    let proxy = Arc::new(Proxy {
+        mode,
+        authority: Authority::load()?,
+        replays: Mutex::new(match mode {
+            RecorderMode::Record => Replays::default(),
+            RecorderMode::Replay => replays(load_recordings()?),
+        }),
+        on_exchange: Box::new(on_exchange),
+        tunnels,
+    });
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {port}: {e}"))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let task = tokio::spawn(async move {
+        // Connections and the tunnels opened through them are aborted along with the
+        // recorder when it stops.
+        let mut connections = JoinSet::new();
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        log::warn!("Recorder failed to accept a connection: {e}");
+                        continue;
+                    }
+                },
+                Some(tunnel) = tunnel_rx.recv() => {
+                    connections.spawn(tunnel);
+                    continue;
+                }
+            };
+            while connections.try_join_next().is_some() {}
+            let proxy = proxy.clone();
+            connections.spawn(async move {
+                let service = service_fn(move |req| proxy.clone().handle(req));
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .with_upgrades()
+                    .await
+                {
+                    log::debug!("Recorder connection failed: {e}");
+                }
+            });
+        }
+    });
+
+    *SERVER.lock().unwrap() = Some(Running { port, mode, task });
+    Ok(port)
+}
+
+pub fn stop() {
+    if let Some(running) = SERVER.lock().unwrap().take() {
+        running.task.abort();
+    }
+}
+
+/// The port and mode of the recorder, if it's running.
+pub fn running() -> Option<(u16, RecorderMode)> {
+    SERVER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|running| (running.port, running.mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording(method: &str, url: &str, status: u16) -> (Recording, Vec<u8>) {
+        let recording = Recording {
+            id: 0,
+            method: method.to_string(),
+            url: url.to_string(),
+            request_headers: Vec::new(),
+            request_body: String::new(),
+            status,
+            response_headers: Vec::new(),
+            response_body: String::new(),
+            response_time: 0.0,
+            created_at: String::new(),
+            replayed: false,
+        };
+        (recording, Vec::new())
+    }
+
+    #[test]
+    fn replays_in_recorded_order() {
+        let mut replays = replays(vec![
+            recording("GET", "https://api.example.com/jobs/1", 202),
+            recording("POST", "https://api.example.com/jobs/1", 201),
+            recording("GET", "https://api.example.com/jobs/1", 200),
+        ]);
+        let mut next = |method, url| replays.next(method, url).map(|r| r.recording.status);
+
+        assert_eq!(next("GET", "https://api.example.com/jobs/1"), Some(202));
+        assert_eq!(next("GET", "https://api.example.com/jobs/1"), Some(200));
+        assert_eq!(next("GET", "https://api.example.com/jobs/1"), Some(200));
+        assert_eq!(next("POST", "https://api.example.com/jobs/1"), Some(201));
+        assert_eq!(next("GET", "https://api.example.com/jobs/2"), None);
+    }
+
+    #[test]
+    fn turns_recordings_into_requests() {
+        let (mut recorded, _) = recording("POST", "https://api.example.com/users", 201);
+        recorded.request_headers = vec![
+            KeyValue {
+                key: "host".to_string(),
+                value: "api.example.com".to_string(),
+            },
+            KeyValue {
+                key: "content-type".to_string(),
+                value: "application/json".to_string(),
+            },
+        ];
+        recorded.request_body = "{\"name\":\"Ada\"}".to_string();
+
+        let request = recorded.request();
+        assert_eq!(request.method.as_str(), "POST");
+        assert_eq!(request.headers.len(), 1);
+        assert!(matches!(
+            request.body,
+            RequestBody::Raw { content_type, .. } if content_type == "application/json"
+        ));
+    }
+
+    #[test]
+    fn issues_certificates_for_hosts() {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, CA_NAME);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+        let cert = params.self_signed(&key).unwrap();
+        let authority = Authority {
+            issuer: Issuer::from_ca_cert_der(cert.der(), key).unwrap(),
+            certificate: cert.der().clone(),
+            configs: Mutex::new(HashMap::new()),
+        };
+
+        let config = authority.server_config("api.example.com").unwrap();
+        let cached = authority.server_config("api.example.com").unwrap();
+        assert!(Arc::ptr_eq(&config, &cached));
+        assert_eq!(config.alpn_protocols, [b"http/1.1".to_vec()]);
+    }
+}
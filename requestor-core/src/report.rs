@@ -0,0 +1,168 @@
+//! Reports of runs in the formats CI systems read.
+
+use std::fmt::Write;
+
+use crate::runner::{Run, RunResult};
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace aren't allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn test_case(report: &mut String, run: &Run, result: &RunResult) {
+    let name = match run.iterations {
+        1 => result.name.clone(),
+        _ => format!("{} (iteration {})", result.name, result.iteration),
+    };
+    let class = match result.folder.as_str() {
+        "" => run.collection.clone(),
+        folder => format!("{}/{folder}", run.collection),
+    };
+    let _ = write!(
+        report,
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+        escape(&name),
+        escape(&class),
+        result.response_time / 1000.0
+    );
+
+    let failed: Vec<_> = result.tests.iter().filter(|t| !t.passed).collect();
+    let snapshot = result.snapshot.as_ref().filter(|s| !s.passed());
+    if result.error.is_none() && failed.is_empty() && snapshot.is_none() {
+        report.push_str("/>\n");
+        return;
+    }
+    report.push_str(">\n");
+    if let Some(error) = &result.error {
+        let _ = writeln!(report, "      <error message=\"{}\"/>", escape(error));
+    }
+    if !failed.is_empty() {
+        let details: Vec<String> = failed
+            .iter()
+            .map(|t| match t.message.as_str() {
+                "" => t.name.clone(),
+                message => format!("{}: {message}", t.name),
+            })
+            .collect();
+        let _ = writeln!(
+            report,
+            "      <failure message=\"{} of {} assertions failed\">{}</failure>",
+            failed.len(),
+            result.tests.len(),
+            escape(&details.join("\n"))
+        );
+    }
+    if let Some(snapshot) = snapshot {
+        let _ = writeln!(
+            report,
+            "      <failure message=\"Response differs from the snapshot\">{}</failure>",
+            escape(&snapshot.describe().join("\n"))
+        );
+    }
+    report.push_str("    </testcase>\n");
+}
+
+/// A JUnit XML report with a test case for every request sent.
+pub fn junit(run: &Run) -> String {
+    let errors = run.results.iter().filter(|r| r.error.is_some()).count();
+    let failures = run.summary.failed_requests - errors;
+    let time = run.summary.total_time / 1000.0;
+    let attributes = format!(
+        "name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\"",
+        escape(&run.collection),
+        run.results.len(),
+    );
+
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(report, "<testsuites {attributes}>");
+    let _ = writeln!(
+        report,
+        "  <testsuite {attributes} timestamp=\"{}\">",
+        escape(&run.started_at)
+    );
+    for result in &run.results {
+        test_case(&mut report, run, result);
+    }
+    report.push_str("  </testsuite>\n</testsuites>\n");
+    report
+}
+
+/// The run with every result, as JSON.
+pub fn json(run: &Run) -> Result<String, String> {
+    serde_json::to_string_pretty(run).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assertions::AssertionResult;
+    use crate::runner::RunSummary;
+
+    fn result(name: &str, tests: &[(&str, bool)], error: Option<&str>) -> RunResult {
+        RunResult {
+            iteration: 1,
+            name: name.to_string(),
+            folder: "auth".to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com".to_string(),
+            status: 200,
+            response_time: 1500.0,
+            tests: tests
+                .iter()
+                .map(|&(name, passed)| AssertionResult {
+                    name: name.to_string(),
+                    passed,
+                    message: if passed { "" } else { "Was <404>" }.to_string(),
+                })
+                .collect(),
+            error: error.map(str::to_string),
+            snapshot: None,
+        }
+    }
+
+    #[test]
+    fn writes_junit_reports() {
+        let run = Run {
+            id: 1,
+            collection: "API & co".to_string(),
+            folder: String::new(),
+            iterations: 1,
+            started_at: "2026-01-01T00:00:00+00:00".to_string(),
+            finished_at: "2026-01-01T00:00:03+00:00".to_string(),
+            summary: RunSummary {
+                requests: 3,
+                failed_requests: 2,
+                total_time: 4500.0,
+                ..Default::default()
+            },
+            results: vec![
+                result("login", &[("status", true)], None),
+                result("profile", &[("status", false), ("name", true)], None),
+                result("logout", &[], Some("connection refused")),
+            ],
+            data: Vec::new(),
+        };
+        let report = junit(&run);
+        assert!(report.contains(
+            r#"<testsuite name="API &amp; co" tests="3" failures="1" errors="1" time="4.500""#
+        ));
+        assert!(report
+            .contains(r#"<testcase name="login" classname="API &amp; co/auth" time="1.500"/>"#));
+        assert!(report.contains(
+            r#"<failure message="1 of 2 assertions failed">status: Was &lt;404&gt;</failure>"#
+        ));
+        assert!(report.contains(r#"<error message="connection refused"/>"#));
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::assertions::AssertionResult;
+use crate::collection::{self, Collection, CollectionRequest};
+use crate::data::DataRow;
+use crate::snapshot::{self, Snapshot, SnapshotCheck};
+use crate::{db, execute_with_data, rfc3339_now, ResponseData};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RunOptions {
+    /// Only requests in this folder and its subfolders are run, all of them when empty.
+    #[serde(default)]
+    pub folder: String,
+    /// Only the request with this name is run, all of them when empty.
+    #[serde(default)]
+    pub request: String,
+    /// Pause between requests, in milliseconds.
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub stop_on_failure: bool,
+    /// How many times the requests are run, in order.
+    #[serde(default = "one")]
+    pub iterations: u32,
+    /// Rows of a data file. When given the requests are run once per row, with the row's
+    /// columns as variables, instead of `iterations` times.
+    #[serde(default)]
+    pub data: Vec<DataRow>,
+    /// Approve the responses of requests without a snapshot or that differ from theirs as
+    /// their new snapshots.
+    #[serde(default)]
+    pub update_snapshots: bool,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunResult {
+    pub iteration: u32,
+    pub name: String,
+    pub folder: String,
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub response_time: f32,
+    pub tests: Vec<AssertionResult>,
+    /// Why the request couldn't be sent.
+    pub error: Option<String>,
+    /// How the response compared to the request's snapshot, when it was compared.
+    #[serde(default)]
+    pub snapshot: Option<SnapshotCheck>,
+}
+
+impl RunResult {
+    fn new(
+        iteration: u32,
+        item: &CollectionRequest,
+        outcome: &Result<ResponseData, String>,
+    ) -> Self {
+        let mut result = RunResult {
+            iteration,
+            name: item.name.clone(),
+            folder: item.folder.clone(),
+            method: item.request.method.as_str().to_string(),
+            url: item.request.url.clone(),
+            status: 0,
+            response_time: 0.0,
+            tests: Vec::new(),
+            error: None,
+            snapshot: None,
+        };
+        match outcome {
+            Ok(response) => {
+                result.status = response.status;
+                result.response_time = response.response_time;
+                result.tests = response.tests.clone();
+            }
+            Err(e) => result.error = Some(e.clone()),
+        }
+        result
+    }
+
+    /// A request passes when it was answered, all its tests passed and its response matched
+    /// its snapshot.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+            && self.tests.iter().all(|t| t.passed)
+            && self.snapshot.as_ref().is_none_or(SnapshotCheck::passed)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub requests: usize,
+    pub failed_requests: usize,
+    pub assertions: usize,
+    pub failed_assertions: usize,
+    /// Sum of the response times, in milliseconds.
+    pub total_time: f32,
+    pub average_time: f32,
+    /// Whether the run ended early, after a failure or because it was cancelled.
+    pub stopped: bool,
+}
+
+fn summarize(results: &[RunResult], stopped: bool) -> RunSummary {
+    let total_time: f32 = results.iter().map(|r| r.response_time).sum();
+    RunSummary {
+        requests: results.len(),
+        failed_requests: results.iter().filter(|r| !r.passed()).count(),
+        assertions: results.iter().map(|r| r.tests.len()).sum(),
+        failed_assertions: results
+            .iter()
+            .flat_map(|r| &r.tests)
+            .filter(|t| !t.passed)
+            .count(),
+        total_time,
+        average_time: match results.len() {
+            0 => 0.0,
+            count => total_time / count as f32,
+        },
+        stopped,
+    }
+}
+
+/// The record of a collection run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Run {
+    /// Zero until the run is saved.
+    pub id: i64,
+    pub collection: String,
+    pub folder: String,
+    pub iterations: u32,
+    pub started_at: String,
+    pub finished_at: String,
+    pub summary: RunSummary,
+    pub results: Vec<RunResult>,
+    /// Rows of the data file the run used, row `n` being iteration `n + 1`.
+    #[serde(default)]
+    pub data: Vec<DataRow>,
+}
+
+impl Run {
+    /// Rows of the data file with a failing request, to run again.
+    pub fn failed_rows(&self) -> Vec<DataRow> {
+        let mut failed: Vec<u32> = self
+            .results
+            .iter()
+            .filter(|r| !r.passed())
+            .map(|r| r.iteration)
+            .collect();
+        failed.dedup();
+        failed
+            .into_iter()
+            .filter_map(|iteration| self.data.get(iteration as usize - 1).cloned())
+            .collect()
+    }
+}
+
+/// Saves a run, setting its id.
+pub fn save_run(run: &mut Run) -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute(
+        "INSERT INTO runs (collection, folder, iterations, started_at, finished_at, summary, results, data) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            run.collection,
+            run.folder,
+            run.iterations,
+            run.started_at,
+            run.finished_at,
+            serde_json::to_string(&run.summary).map_err(|e| e.to_string())?,
+            serde_json::to_string(&run.results).map_err(|e| e.to_string())?,
+            serde_json::to_string(&run.data).map_err(|e| e.to_string())?,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    run.id = conn.last_insert_rowid();
+    Ok(())
+}
+
+/// Compares a response against the snapshot of its request, approving it as the new snapshot
+/// when `update` is set and it differs or there's none yet.
+fn check_snapshot(
+    snapshots: &mut HashMap<(String, String), Snapshot>,
+    collection_id: i64,
+    item: &CollectionRequest,
+    response: &ResponseData,
+    update: bool,
+) -> SnapshotCheck {
+    let key = (item.folder.clone(), item.name.clone());
+    let mut check = snapshot::check(snapshots.get(&key), response);
+    if !update {
+        return check;
+    }
+    let Some(received) = check.received.take() else {
+        return check;
+    };
+    let approved = snapshot::approve(Snapshot {
+        id: 0,
+        collection_id,
+        folder: item.folder.clone(),
+        name: item.name.clone(),
+        response: received.clone(),
+        ignore: snapshots
+            .get(&key)
+            .map(|s| s.ignore.clone())
+            .unwrap_or_default(),
+        approved_at: String::new(),
+    });
+    match approved {
+        Ok(approved) => {
+            snapshots.insert(key, approved);
+            check.updated = true;
+        }
+        Err(e) => {
+            log::warn!("Failed to update the snapshot of {}: {e}", item.name);
+            check.received = Some(received);
+        }
+    }
+    check
+}
+
+/// The requests of `collection` a run with `options` sends in each iteration.
+fn planned_requests<'a>(
+    collection: &'a Collection,
+    options: &RunOptions,
+) -> Vec<&'a CollectionRequest> {
+    collection
+        .requests
+        .iter()
+        .filter(|r| collection::in_folder(&r.folder, &options.folder))
+        .filter(|r| options.request.is_empty() || r.name == options.request)
+        .collect()
+}
+
+/// Iterations of a run: one per data row, `iterations` without data.
+fn planned_iterations(options: &RunOptions) -> u32 {
+    match options.data.len() {
+        0 => options.iterations.max(1),
+        rows => rows as u32,
+    }
+}
+
+/// Number of results a run with `options` gets unless it's stopped early.
+pub fn planned_results(collection: &Collection, options: &RunOptions) -> usize {
+    planned_requests(collection, options).len() * planned_iterations(options) as usize
+}
+
+/// Runs the requests of a collection in order, passing each result to `on_result` as it
+/// completes. The run stops once `cancelled` resolves, keeping the results it got to.
+/// Responses are compared against the snapshots of saved collections, except in data-driven
+/// runs whose responses differ by row.
+pub async fn run(
+    collection: &Collection,
+    options: &RunOptions,
+    mut on_result: impl FnMut(&RunResult, &Result<ResponseData, String>),
+    cancelled: impl Future<Output = ()>,
+) -> Run {
+    let requests = planned_requests(collection, options);
+    let iterations = planned_iterations(options);
+    let no_data = DataRow::new();
+    let snapshot_collection = collection.id.filter(|_| options.data.is_empty());
+    let mut snapshots = match snapshot_collection.map(snapshot::for_collection) {
+        Some(Ok(snapshots)) => snapshots,
+        Some(Err(e)) => {
+            log::warn!("Failed to load the snapshots of {}: {e}", collection.name);
+            HashMap::new()
+        }
+        None => HashMap::new(),
+    };
+
+    tokio::pin!(cancelled);
+    let started_at = rfc3339_now();
+    let mut results = Vec::new();
+    let mut stopped = false;
+    'run: for iteration in 1..=iterations {
+        let row = options.data.get(iteration as usize - 1).unwrap_or(&no_data);
+        for item in &requests {
+            if !results.is_empty() && options.delay_ms > 0 {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(options.delay_ms)) => {}
+                    _ = &mut cancelled => {
+                        stopped = true;
+                        break 'run;
+                    }
+                }
+            }
+            let outcome = tokio::select! {
+                outcome = execute_with_data(item.request.clone(), row) => outcome,
+                _ = &mut cancelled => {
+                    stopped = true;
+                    break 'run;
+                }
+            };
+
+            let mut result = RunResult::new(iteration, item, &outcome);
+            if let (Some(id), Ok(response)) = (snapshot_collection, &outcome) {
+                result.snapshot = Some(check_snapshot(
+                    &mut snapshots,
+                    id,
+                    item,
+                    response,
+                    options.update_snapshots,
+                ));
+            }
+            on_result(&result, &outcome);
+            let failed = !result.passed();
+            results.push(result);
+            if failed && options.stop_on_failure {
+                stopped = true;
+                break 'run;
+            }
+        }
+    }
+
+    Run {
+        id: 0,
+        collection: collection.name.clone(),
+        folder: options.folder.clone(),
+        iterations,
+        started_at,
+        finished_at: rfc3339_now(),
+        summary: summarize(&results, stopped),
+        results,
+        data: options.data.clone(),
+    }
+}
+
+/// Saved runs, the latest first.
+pub fn get_runs() -> Result<Vec<Run>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare("SELECT id, collection, folder, iterations, started_at, finished_at, summary, results, data FROM runs ORDER BY id DESC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Run {
+                id: row.get(0)?,
+                collection: row.get(1)?,
+                folder: row.get(2)?,
+                iterations: row.get(3)?,
+                started_at: row.get(4)?,
+                finished_at: row.get(5)?,
+                summary: serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
+                results: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default(),
+                data: row
+                    .get::<_, Option<String>>(8)?
+                    .and_then(|data| serde_json::from_str(&data).ok())
+                    .unwrap_or_default(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: u16, time: f32, tests: &[bool], error: Option<&str>) -> RunResult {
+        RunResult {
+            iteration: 1,
+            name: "request".to_string(),
+            folder: String::new(),
+            method: "GET".to_string(),
+            url: "https://example.com".to_string(),
+            status,
+            response_time: time,
+            tests: tests
+                .iter()
+                .map(|&passed| AssertionResult {
+                    name: "test".to_string(),
+                    passed,
+                    message: String::new(),
+                })
+                .collect(),
+            error: error.map(str::to_string),
+            snapshot: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_runs() {
+        let results = [
+            result(200, 10.0, &[true, true], None),
+            result(500, 20.0, &[true, false], None),
+            result(0, 0.0, &[], Some("connection refused")),
+        ];
+        assert_eq!(
+            summarize(&results, true),
+            RunSummary {
+                requests: 3,
+                failed_requests: 2,
+                assertions: 4,
+                failed_assertions: 1,
+                total_time: 30.0,
+                average_time: 10.0,
+                stopped: true,
+            }
+        );
+        assert_eq!(summarize(&[], false).average_time, 0.0);
+    }
+
+    #[test]
+    fn finds_failed_rows() {
+        let row = |user: &str| DataRow::from([("user".to_string(), user.to_string())]);
+        let mut results = Vec::new();
+        for (iteration, passed) in [(1, true), (1, true), (2, true), (2, false), (3, false)] {
+            let mut result = result(200, 1.0, &[passed], None);
+            result.iteration = iteration;
+            results.push(result);
+        }
+        let run = Run {
+            id: 0,
+            collection: "users".to_string(),
+            folder: String::new(),
+            iterations: 3,
+            started_at: String::new(),
+            finished_at: String::new(),
+            summary: summarize(&results, false),
+            results,
+            data: vec![row("ada"), row("grace"), row("linus")],
+        };
+        assert_eq!(run.failed_rows(), [row("grace"), row("linus")]);
+    }
+
+    #[test]
+    fn counts_planned_results() {
+        let request = |name: &str, folder: &str| CollectionRequest {
+            name: name.to_string(),
+            folder: folder.to_string(),
+            request: Default::default(),
+        };
+        let collection = Collection {
+            id: None,
+            name: "users".to_string(),
+            requests: vec![
+                request("list", "users"),
+                request("get", "users/one"),
+                request("health", ""),
+            ],
+        };
+        let mut options = RunOptions {
+            folder: "users".to_string(),
+            iterations: 3,
+            ..Default::default()
+        };
+        assert_eq!(planned_results(&collection, &options), 6);
+        options.request = "get".to_string();
+        assert_eq!(planned_results(&collection, &options), 3);
+        options.data = vec![DataRow::new(); 2];
+        assert_eq!(planned_results(&collection, &options), 2);
+        options.request = "missing".to_string();
+        assert_eq!(planned_results(&collection, &options), 0);
+    }
+}
@@ -0,0 +1,283 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use rhai::serde::{from_dynamic, to_dynamic};
+use rhai::{Dynamic, Engine, Map, Scope};
+
+use crate::assertions::AssertionResult;
+use crate::data::DataRow;
+use crate::{environment, RequestArgs, ResponseData};
+
+/// Operations a script may run before it's stopped, which keeps runaway loops in check.
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+/// What scripts see and change while they run.
+#[derive(Default)]
+struct ScriptState {
+    variables: HashMap<String, String>,
+    /// Variables to write back to the active environment.
+    environment: Vec<(String, String)>,
+    console: Vec<String>,
+    tests: Vec<AssertionResult>,
+}
+
+fn text(value: &Dynamic) -> String {
+    match value.clone().into_immutable_string() {
+        Ok(text) => text.to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+/// Builds a sandboxed engine: scripts can't touch files or the network, only the request,
+/// the response and the functions registered here.
+fn engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+    // The default resolver loads modules from the file system.
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 64);
+    engine.set_max_string_size(16 * 1024 * 1024);
+
+    let console = state.clone();
+    engine.on_print(move |line| console.borrow_mut().console.push(line.to_string()));
+    let console = state.clone();
+    engine
+        .on_debug(move |line, _, pos| console.borrow_mut().console.push(format!("[{pos}] {line}")));
+
+    let vars = state.clone();
+    engine.register_fn("get_var", move |name: &str| -> Dynamic {
+        match vars.borrow().variables.get(name) {
+            Some(value) => value.clone().into(),
+            None => Dynamic::UNIT,
+        }
+    });
+    let vars = state.clone();
+    engine.register_fn("set_var", move |name: &str, value: Dynamic| {
+        vars.borrow_mut()
+            .variables
+            .insert(name.to_string(), text(&value));
+    });
+    let vars = state.clone();
+    engine.register_fn("set_env", move |name: &str, value: Dynamic| {
+        let mut state = vars.borrow_mut();
+        state.variables.insert(name.to_string(), text(&value));
+        state.environment.push((name.to_string(), text(&value)));
+    });
+
+    let tests = state.clone();
+    engine.register_fn("test", move |name: &str, passed: bool| {
+        tests.borrow_mut().tests.push(AssertionResult {
+            name: name.to_string(),
+            passed,
+            message: String::new(),
+        });
+    });
+    let tests = state.clone();
+    engine.register_fn("test", move |name: &str, passed: bool, message: &str| {
+        tests.borrow_mut().tests.push(AssertionResult {
+            name: name.to_string(),
+            passed,
+            message: if passed {
+                String::new()
+            } else {
+                message.to_string()
+            },
+        });
+    });
+    engine
+}
+
+fn response_value(response: &ResponseData) -> Result<Dynamic, String> {
+    let mut value = Map::new();
+    value.insert("status".into(), (response.status as i64).into());
+    let headers: Map = response
+        .headers
+        .iter()
+        .map(|h| (h.key.to_ascii_lowercase().into(), h.value.clone().into()))
+        .collect();
+    value.insert("headers".into(), headers.into());
+    value.insert("body".into(), response.body.clone().into());
+    value.insert(
+        "response_time".into(),
+        (response.response_time as f64).into(),
+    );
+    let json = match serde_json::from_str::<serde_json::Value>(&response.body) {
+        Ok(json) => to_dynamic(json).map_err(|e| e.to_string())?,
+        Err(_) => Dynamic::UNIT,
+    };
+    value.insert("json".into(), json);
+    Ok(value.into())
+}
+
+/// Output of a request's scripts, carried from the pre-request to the post-response script.
+#[derive(Default)]
+pub struct ScriptRun {
+    pub variables: HashMap<String, String>,
+    console: Vec<String>,
+}
+
+fn run_pre_request(
+    args: &mut RequestArgs,
+    variables: HashMap<String, String>,
+) -> Result<ScriptState, String> {
+    let state = Rc::new(RefCell::new(ScriptState {
+        variables,
+        ..Default::default()
+    }));
+    if !args.pre_request_script.trim().is_empty() {
+        let engine = engine(&state);
+        let mut scope = Scope::new();
+        scope.push("request", to_dynamic(&*args).map_err(|e| e.to_string())?);
+        engine
+            .run_with_scope(&mut scope, &args.pre_request_script)
+            .map_err(|e| format!("Pre-request script: {e}"))?;
+        let request: Dynamic = scope.get_value("request").unwrap_or_default();
+        let scripts = (
+            std::mem::take(&mut args.pre_request_script),
+            std::mem::take(&mut args.post_response_script),
+        );
+        *args = from_dynamic(&request).map_err(|e| format!("Pre-request script: {e}"))?;
+        (args.pre_request_script, args.post_response_script) = scripts;
+    }
+    Ok(Rc::into_inner(state).unwrap_or_default().into_inner())
+}
+
+/// Runs the pre-request script, which may change `args` and set variables, then fills in
+/// the `{{variables}}` the request references. Columns of a data-driven run's `row` override
+/// environment variables of the same name.
+pub fn before_request(args: &mut RequestArgs, row: &DataRow) -> Result<ScriptRun, String> {
+    let mut variables = environment::active_variables()?;
+    variables.extend(row.clone());
+    let state = run_pre_request(args, variables)?;
+    environment::set_variables(&state.environment)?;
+    environment::substitute_request(args, &state.variables);
+    Ok(ScriptRun {
+        variables: state.variables,
+        console: state.console,
+    })
+}
+
+/// Runs the post-response script, adding its console output and test results to the
+/// response. A failing script is reported on the console rather than failing the request.
+pub fn after_response(
+    args: &RequestArgs,
+    run: ScriptRun,
+    response: &mut ResponseData,
+) -> Result<(), String> {
+    response.console = run.console;
+    if args.post_response_script.trim().is_empty() {
+        return Ok(());
+    }
+
+    let state = Rc::new(RefCell::new(ScriptState {
+        variables: run.variables,
+        ..Default::default()
+    }));
+    let mut scope = Scope::new();
+    scope.push("request", to_dynamic(args).map_err(|e| e.to_string())?);
+    scope.push("response", response_value(response)?);
+    let result = engine(&state).run_with_scope(&mut scope, &args.post_response_script);
+
+    let state = Rc::into_inner(state).unwrap_or_default().into_inner();
+    response.console.extend(state.console);
+    if let Err(e) = result {
+        response
+            .console
+            .push(format!("Post-response script failed: {e}"));
+    }
+    response.tests.extend(state.tests);
+    environment::set_variables(&state.environment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{body, KeyValue};
+
+    fn request(pre: &str, post: &str) -> RequestArgs {
+        RequestArgs {
+            method: "GET".parse().unwrap(),
+            url: "https://example.com/{{path}}".to_string(),
+            query_params: Vec::new(),
+            headers: Vec::new(),
+            body: body::RequestBody::None,
+            proxy: Default::default(),
+            stream: false,
+            wire: false,
+            assertions: Vec::new(),
+            pre_request_script: pre.to_string(),
+            post_response_script: post.to_string(),
+            extractors: Vec::new(),
+        }
+    }
+
+    fn response(body: &str) -> ResponseData {
+        ResponseData {
+            status: 200,
+            headers: vec![KeyValue {
+                key: "X-Request-Id".to_string(),
+                value: "42".to_string(),
+            }],
+            body: body.to_string(),
+            response_time: 3.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pre_request_scripts_change_the_request() {
+        let mut args = request(
+            r#"
+                set_var("path", "users/" + 7);
+                request.method = "POST";
+                request.headers.push(#{ key: "X-Trace", value: "on" });
+                print("prepared");
+            "#,
+            "",
+        );
+        let state = run_pre_request(&mut args, HashMap::new()).unwrap();
+        environment::substitute_request(&mut args, &state.variables);
+
+        assert_eq!(args.method.as_str(), "POST");
+        assert_eq!(args.url, "https://example.com/users/7");
+        assert_eq!(args.headers[0].key, "X-Trace");
+        assert_eq!(state.console, ["prepared"]);
+    }
+
+    #[test]
+    fn post_response_scripts_read_the_response_and_add_tests() {
+        let args = request(
+            "",
+            r#"
+                test("status is 200", response.status == 200);
+                test("has user", response.json.user.name == "ada");
+                test("fast", response.response_time < 1.0, "too slow");
+                print(response.headers["x-request-id"]);
+            "#,
+        );
+        let mut response = response(r#"{"user": {"name": "ada"}}"#);
+        after_response(&args, ScriptRun::default(), &mut response).unwrap();
+        let passed: Vec<bool> = response.tests.iter().map(|t| t.passed).collect();
+        assert_eq!(passed, [true, true, false]);
+        assert_eq!(response.tests[2].message, "too slow");
+        assert_eq!(response.console, ["42"]);
+    }
+
+    #[test]
+    fn stops_runaway_scripts() {
+        let state = Rc::new(RefCell::new(ScriptState::default()));
+        assert!(engine(&state).run("loop {}").is_err());
+    }
+
+    #[test]
+    fn cannot_import_modules() {
+        let path = std::env::temp_dir().join("requestor-script-module.rhai");
+        std::fs::write(&path, "export const SECRET = 1;").unwrap();
+        let state = Rc::new(RefCell::new(ScriptState::default()));
+        let script = format!("import {:?} as m; m::SECRET", path.with_extension(""));
+        assert!(engine(&state).run(&script).is_err());
+        let _ = std::fs::remove_file(path);
+    }
+}
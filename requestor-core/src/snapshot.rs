@@ -0,0 +1,266 @@
+//! Approved responses of saved requests, which later runs compare their responses against.
+//! Snapshots cover the status, the `Content-Type` header and the body.
+
+use std::collections::HashMap;
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{self, BodyDiff, DiffOptions, DiffSide, LineTag, ResponseDiff};
+use crate::{db, rfc3339_now, ResponseData};
+
+/// The approved response of a request of a collection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Zero until the snapshot is saved.
+    #[serde(default)]
+    pub id: i64,
+    pub collection_id: i64,
+    #[serde(default)]
+    pub folder: String,
+    pub name: String,
+    pub response: DiffSide,
+    /// JSON paths that may differ from the snapshot, see `DiffOptions::ignore`.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub approved_at: String,
+}
+
+/// How a response compared to its request's snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotCheck {
+    /// Differences from the approved snapshot, none when the request has no snapshot.
+    pub diff: Option<ResponseDiff>,
+    /// The response received, kept when it differs from the snapshot or there's none so it
+    /// can be approved.
+    pub received: Option<DiffSide>,
+    /// Whether the run approved the response as the request's new snapshot.
+    #[serde(default)]
+    pub updated: bool,
+}
+
+impl SnapshotCheck {
+    /// Whether the response matched the snapshot. Requests without one always pass.
+    pub fn passed(&self) -> bool {
+        self.updated || self.diff.as_ref().is_none_or(ResponseDiff::is_empty)
+    }
+
+    /// What differs from the snapshot, a line per change.
+    pub fn describe(&self) -> Vec<String> {
+        let Some(diff) = &self.diff else {
+            return Vec::new();
+        };
+        let json = |value: &Option<serde_json::Value>| match value {
+            Some(value) => value.to_string(),
+            None => "nothing".to_string(),
+        };
+        let mut lines = Vec::new();
+        if diff.left_status != diff.right_status {
+            lines.push(format!(
+                "status {} → {}",
+                diff.left_status, diff.right_status
+            ));
+        }
+        for header in &diff.headers {
+            let value =
+                |value: &Option<String>| value.clone().unwrap_or_else(|| "nothing".to_string());
+            lines.push(format!(
+                "{}: {} → {}",
+                header.name,
+                value(&header.left),
+                value(&header.right)
+            ));
+        }
+        match &diff.body {
+            BodyDiff::Json { changes } => lines.extend(changes.iter().map(|change| {
+                format!(
+                    "{}: {} → {}",
+                    change.path,
+                    json(&change.left),
+                    json(&change.right)
+                )
+            })),
+            BodyDiff::Text { lines: body } => {
+                let count = |tag| body.iter().filter(|line| line.tag == tag).count();
+                let (added, removed) = (count(LineTag::Added), count(LineTag::Removed));
+                if added + removed > 0 {
+                    lines.push(format!("body: {added} lines added, {removed} removed"));
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// The parts of a response that snapshots cover.
+pub fn received(response: &ResponseData) -> DiffSide {
+    DiffSide {
+        status: response.status,
+        headers: response
+            .headers
+            .iter()
+            .filter(|kv| kv.key.eq_ignore_ascii_case("content-type"))
+            .cloned()
+            .collect(),
+        body: response.body.clone(),
+    }
+}
+
+/// Compares a response against the snapshot of its request, if there's one.
+pub fn check(snapshot: Option<&Snapshot>, response: &ResponseData) -> SnapshotCheck {
+    let received = received(response);
+    let Some(snapshot) = snapshot else {
+        return SnapshotCheck {
+            diff: None,
+            received: Some(received),
+            updated: false,
+        };
+    };
+    let options = DiffOptions {
+        ignore: snapshot.ignore.clone(),
+    };
+    let diff = diff::diff_responses(&snapshot.response, &received, &options);
+    SnapshotCheck {
+        received: (!diff.is_empty()).then_some(received),
+        diff: Some(diff),
+        updated: false,
+    }
+}
+
+/// The snapshots of a collection's requests, by folder and name.
+pub fn for_collection(collection_id: i64) -> Result<HashMap<(String, String), Snapshot>, String> {
+    Ok(get_snapshots(collection_id)?
+        .into_iter()
+        .map(|snapshot| ((snapshot.folder.clone(), snapshot.name.clone()), snapshot))
+        .collect())
+}
+
+pub fn get_snapshots(collection_id: i64) -> Result<Vec<Snapshot>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare("SELECT id, collection_id, folder, name, status, headers, body, ignore, approved_at FROM snapshots WHERE collection_id = ?1 ORDER BY folder, name")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([collection_id], |row| {
+            Ok(Snapshot {
+                id: row.get(0)?,
+                collection_id: row.get(1)?,
+                folder: row.get(2)?,
+                name: row.get(3)?,
+                response: DiffSide {
+                    status: row.get(4)?,
+                    headers: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default(),
+                    body: row.get(6)?,
+                },
+                ignore: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default(),
+                approved_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// Saves a snapshot as the approved response of its request, replacing the one approved
+/// before, and returns it with its id and approval time set.
+pub fn approve(mut snapshot: Snapshot) -> Result<Snapshot, String> {
+    snapshot.approved_at = rfc3339_now();
+    let conn = db::open()?;
+    conn.execute(
+        "INSERT INTO snapshots (collection_id, folder, name, status, headers, body, ignore, approved_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT (collection_id, folder, name) DO UPDATE SET status = excluded.status, headers = excluded.headers, body = excluded.body, ignore = excluded.ignore, approved_at = excluded.approved_at",
+        params![
+            snapshot.collection_id,
+            snapshot.folder,
+            snapshot.name,
+            snapshot.response.status,
+            serde_json::to_string(&snapshot.response.headers).map_err(|e| e.to_string())?,
+            snapshot.response.body,
+            serde_json::to_string(&snapshot.ignore).map_err(|e| e.to_string())?,
+            snapshot.approved_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    snapshot.id = conn
+        .query_row(
+            "SELECT id FROM snapshots WHERE collection_id = ?1 AND folder = ?2 AND name = ?3",
+            params![snapshot.collection_id, snapshot.folder, snapshot.name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(snapshot)
+}
+
+pub fn delete_snapshot(id: i64) -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute("DELETE FROM snapshots WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyValue;
+
+    fn response(status: u16, body: &str) -> ResponseData {
+        ResponseData {
+            status,
+            headers: vec![
+                KeyValue {
+                    key: "Content-Type".to_string(),
+                    value: "application/json".to_string(),
+                },
+                KeyValue {
+                    key: "Date".to_string(),
+                    value: rfc3339_now(),
+                },
+            ],
+            body: body.to_string(),
+            response_time: 0.0,
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(body: &str, ignore: &[&str]) -> Snapshot {
+        Snapshot {
+            id: 1,
+            collection_id: 1,
+            folder: String::new(),
+            name: "Get user".to_string(),
+            response: received(&response(200, body)),
+            ignore: ignore.iter().map(|path| path.to_string()).collect(),
+            approved_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn passes_without_a_snapshot() {
+        let check = check(None, &response(200, "{}"));
+        assert!(check.passed());
+        assert!(check.received.is_some());
+    }
+
+    #[test]
+    fn compares_against_the_snapshot() {
+        let approved = snapshot(r#"{"id": 1, "name": "Ada", "seen": "monday"}"#, &["seen"]);
+
+        let same = check(
+            Some(&approved),
+            &response(200, r#"{"name": "Ada", "seen": "tuesday", "id": 1}"#),
+        );
+        assert!(same.passed());
+        assert!(same.received.is_none());
+
+        let changed = check(
+            Some(&approved),
+            &response(200, r#"{"id": 1, "name": "Grace"}"#),
+        );
+        assert!(!changed.passed());
+        assert_eq!(changed.describe(), [r#"$.name: "Ada" → "Grace""#]);
+        assert_eq!(changed.received.unwrap().headers.len(), 1);
+
+        assert!(!check(Some(&approved), &response(500, "{}")).passed());
+    }
+}
@@ -0,0 +1,285 @@
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+
+use crate::cancel::{self, Cancellation, CANCELLED};
+use crate::data::DataRow;
+use crate::{
+    client, complete_response, jsonrpc, prepare_request, raw, response_certificates, rfc3339_now,
+    script, RequestArgs, ResponseData,
+};
+
+/// An entry of a streamed response transcript. For server-sent events the fields are those of
+/// the event, otherwise `data` holds an NDJSON line or a raw chunk.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamEvent {
+    pub timestamp: String,
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+}
+
+impl StreamEvent {
+    fn new(id: Option<String>, event: Option<String>, data: String) -> StreamEvent {
+        StreamEvent {
+            timestamp: rfc3339_now(),
+            id,
+            event,
+            data,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Framing {
+    ServerSentEvents,
+    Lines,
+    Chunks,
+}
+
+impl Framing {
+    fn for_content_type(content_type: &str) -> Framing {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime.to_ascii_lowercase().as_str() {
+            "text/event-stream" => Framing::ServerSentEvents,
+            "application/x-ndjson"
+            | "application/ndjson"
+            | "application/jsonl"
+            | "application/x-jsonlines"
+            | "application/json-seq" => Framing::Lines,
+            _ => Framing::Chunks,
+        }
+    }
+}
+
+/// Splits the body into events as bytes arrive, holding back incomplete lines and UTF-8
+/// sequences until the rest of them is received.
+struct Decoder {
+    framing: Framing,
+    buffer: Vec<u8>,
+    id: Option<String>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl Decoder {
+    fn new(framing: Framing) -> Decoder {
+        Decoder {
+            framing,
+            buffer: Vec::new(),
+            id: None,
+            event: None,
+            data: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Vec<StreamEvent> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+
+        if self.framing == Framing::Chunks {
+            let valid = match std::str::from_utf8(&self.buffer) {
+                Ok(text) => text.len(),
+                // An invalid sequence won't become valid, only wait on a truncated one.
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => self.buffer.len(),
+            };
+            let chunk: Vec<u8> = self.buffer.drain(..valid).collect();
+            if !chunk.is_empty() {
+                let data = String::from_utf8_lossy(&chunk).into_owned();
+                events.push(StreamEvent::new(None, None, data));
+            }
+            return events;
+        }
+
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            events.extend(self.line(line.trim_end_matches(['\n', '\r'])));
+        }
+        events
+    }
+
+    /// Flushes whatever is left once the body has ended.
+    fn finish(&mut self) -> Vec<StreamEvent> {
+        let rest = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+        let mut events = Vec::new();
+        if !rest.is_empty() {
+            match self.framing {
+                Framing::Chunks => events.push(StreamEvent::new(None, None, rest)),
+                _ => events.extend(self.line(rest.trim_end_matches('\r'))),
+            }
+        }
+        if self.framing == Framing::ServerSentEvents {
+            events.extend(self.line(""));
+        }
+        events
+    }
+
+    fn line(&mut self, line: &str) -> Option<StreamEvent> {
+        match self.framing {
+            Framing::Lines if line.trim().is_empty() => None,
+            Framing::Lines => Some(StreamEvent::new(None, None, line.to_string())),
+            Framing::Chunks => None,
+            Framing::ServerSentEvents => self.sse_line(line),
+        }
+    }
+
+    fn sse_line(&mut self, line: &str) -> Option<StreamEvent> {
+        if line.is_empty() {
+            let event = self.event.take();
+            if self.data.is_empty() {
+                return None;
+            }
+            let data = std::mem::take(&mut self.data).join("\n");
+            return Some(StreamEvent::new(self.id.clone(), event, data));
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            // The last event id carries over to later events that don't set one.
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+async fn read_stream(
+    mut args: RequestArgs,
+    cancellation: &mut Cancellation,
+    on_event: &mut impl FnMut(&StreamEvent),
+) -> Result<ResponseData, String> {
+    let json_rpc_calls = jsonrpc::assign_ids(&mut args.body);
+    if raw::is_raw(&args) {
+        // Raw connections record the exchange instead of streaming it.
+        let mut response = tokio::select! {
+            response = raw::send(args) => response?,
+            _ = cancellation.cancelled() => return Err(CANCELLED.to_string()),
+        };
+        response.json_rpc = jsonrpc::match_responses(&response.body, &json_rpc_calls);
+        return Ok(response);
+    }
+    let request = prepare_request(args).await?;
+
+    let start = std::time::Instant::now();
+    let mut res = tokio::select! {
+        res = request.send() => res.map_err(|e| e.to_string())?,
+        _ = cancellation.cancelled() => return Err(CANCELLED.to_string()),
+    };
+    let status = res.status().as_u16();
+    let headers = client::header_rows(res.headers());
+    let certificates = response_certificates(&res);
+    let content_type = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .unwrap_or_default();
+    let mut decoder = Decoder::new(Framing::for_content_type(content_type));
+
+    let mut body = Vec::new();
+    let mut events = Vec::new();
+    loop {
+        // A cancelled stream keeps the transcript received so far.
+        let chunk = tokio::select! {
+            chunk = res.chunk() => chunk.map_err(|e| e.to_string())?,
+            _ = cancellation.cancelled() => None,
+        };
+        let Some(chunk) = chunk else { break };
+        body.extend_from_slice(&chunk);
+        for event in decoder.push(&chunk) {
+            on_event(&event);
+            events.push(event);
+        }
+    }
+    for event in decoder.finish() {
+        on_event(&event);
+        events.push(event);
+    }
+
+    let body = String::from_utf8_lossy(&body).into_owned();
+    Ok(ResponseData {
+        status,
+        headers,
+        json_rpc: jsonrpc::match_responses(&body, &json_rpc_calls),
+        body,
+        response_time: start.elapsed().as_secs_f32() * 1000.0,
+        certificates,
+        events,
+        ..Default::default()
+    })
+}
+
+/// Sends a request and passes its body to `on_event` as it arrives, resolving with the whole
+/// transcript once the stream ends or request `id` is cancelled.
+pub async fn stream_request(
+    id: u64,
+    mut args: RequestArgs,
+    mut on_event: impl FnMut(&StreamEvent),
+) -> Result<ResponseData, String> {
+    let run = script::before_request(&mut args, &DataRow::new())?;
+    let mut cancellation = cancel::register(id);
+    let mut response = read_stream(args.clone(), &mut cancellation, &mut on_event).await?;
+    complete_response(&args, run, &mut response)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(framing: Framing, chunks: &[&[u8]]) -> Vec<StreamEvent> {
+        let mut decoder = Decoder::new(framing);
+        let mut events: Vec<StreamEvent> = chunks.iter().flat_map(|c| decoder.push(c)).collect();
+        events.extend(decoder.finish());
+        events
+    }
+
+    #[test]
+    fn parses_server_sent_events_across_chunks() {
+        let events = decode(
+            Framing::ServerSentEvents,
+            &[
+                b": keep-alive\nid: 1\nevent: to",
+                b"ken\ndata: a\r\ndata: b\n\ndata: c",
+            ],
+        );
+        let fields: Vec<_> = events
+            .iter()
+            .map(|e| (e.id.as_deref(), e.event.as_deref(), e.data.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [(Some("1"), Some("token"), "a\nb"), (Some("1"), None, "c")]
+        );
+    }
+
+    #[test]
+    fn splits_ndjson_lines_and_keeps_utf8_chunks_intact() {
+        let lines = decode(Framing::Lines, &[b"{\"a\":1}\n{\"b\"", b":2}\n\n"]);
+        let data: Vec<_> = lines.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(data, ["{\"a\":1}", "{\"b\":2}"]);
+
+        let chunks = decode(Framing::Chunks, &[&[b'x', 0xc3], &[0xa9]]);
+        let data: Vec<_> = chunks.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(data, ["x", "é"]);
+    }
+
+    #[test]
+    fn picks_framing_from_content_type() {
+        assert_eq!(
+            Framing::for_content_type("text/event-stream; charset=utf-8"),
+            Framing::ServerSentEvents
+        );
+        assert_eq!(
+            Framing::for_content_type("application/x-ndjson"),
+            Framing::Lines
+        );
+        assert_eq!(Framing::for_content_type("text/plain"), Framing::Chunks);
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use p12_keystore::{KeyStore, KeyStoreEntry, Pkcs12ImportPolicy};
+use rusqlite::params;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::db;
+
+/// Certificate chains presented by servers, keyed by their leaf certificate. Populated during
+/// the handshake, the leaf of a response's connection then finds the chain that came with it,
+/// so concurrent requests to servers of the same name presenting different certificates don't
+/// see each other's chain.
+static PEER_CHAINS: Lazy<Mutex<HashMap<Vec<u8>, Vec<CertificateDer<'static>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Chains kept before the oldest are dropped, which only happens with many distinct servers.
+const MAX_PEER_CHAINS: usize = 256;
+
+/// Client certificate and trust settings for a host.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Host the settings apply to, `*.example.com` matches any subdomain.
+    pub host: String,
+    pub client_cert_path: String,
+    pub client_key_path: String,
+    pub pkcs12_path: String,
+    pub pkcs12_password: String,
+    /// Additional PEM files of trusted CA certificates.
+    pub ca_cert_paths: Vec<String>,
+}
+
+impl TlsConfig {
+    fn matches(&self, host: &str) -> bool {
+        let pattern = self.host.trim().to_ascii_lowercase();
+        let host = host.to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host
+                .strip_suffix(suffix)
+                .is_some_and(|rest| rest.ends_with('.')),
+            None => pattern == host,
+        }
+    }
+
+    pub fn identity(
+        &self,
+    ) -> Result<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>, String> {
+        if !self.pkcs12_path.trim().is_empty() {
+            let data = read(&self.pkcs12_path)?;
+            let store =
+                KeyStore::from_pkcs12(&data, &self.pkcs12_password, Pkcs12ImportPolicy::Relaxed)
+                    .map_err(|e| format!("{}: {e}", self.pkcs12_path))?;
+            let chain = store
+                .entries()
+                .find_map(|(_, entry)| match entry {
+                    KeyStoreEntry::PrivateKeyChain(chain) => Some(chain.clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| format!("{}: no private key found", self.pkcs12_path))?;
+            let certs = chain
+                .certs()
+                .iter()
+                .map(|c| CertificateDer::from(c.as_der().to_vec()))
+                .collect();
+            let key = PrivatePkcs8KeyDer::from(chain.key().as_der().to_vec()).into();
+            return Ok(Some((certs, key)));
+        }
+
+        if self.client_cert_path.trim().is_empty() {
+            return Ok(None);
+        }
+        let certs = CertificateDer::pem_slice_iter(&read(&self.client_cert_path)?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{}: {e}", self.client_cert_path))?;
+        // The key may live in the certificate file when no separate key path is given.
+        let key_path = if self.client_key_path.trim().is_empty() {
+            &self.client_cert_path
+        } else {
+            &self.client_key_path
+        };
+        let key = PrivateKeyDer::from_pem_slice(&read(key_path)?)
+            .map_err(|e| format!("{key_path}: {e}"))?;
+        Ok(Some((certs, key)))
+    }
+
+    pub fn ca_certificates(&self) -> Result<Vec<CertificateDer<'static>>, String> {
+        let mut certs = Vec::new();
+        for path in self.ca_cert_paths.iter().filter(|p| !p.trim().is_empty()) {
+            for cert in CertificateDer::pem_slice_iter(&read(path)?) {
+                certs.push(cert.map_err(|e| format!("{path}: {e}"))?);
+            }
+        }
+        Ok(certs)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    pub expired: bool,
+}
+
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let chain = std::iter::once(end_entity)
+            .chain(intermediates)
+            .map(|c| c.clone().into_owned())
+            .collect();
+        let mut chains = PEER_CHAINS.lock().unwrap();
+        if chains.len() >= MAX_PEER_CHAINS {
+            chains.clear();
+        }
+        chains.insert(end_entity.to_vec(), chain);
+
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path.trim()).map_err(|e| format!("{path}: {e}"))
+}
+
+/// Returns the first saved TLS configuration matching `host`, if any.
+pub fn config_for_host(host: &str) -> Result<Option<TlsConfig>, String> {
+    Ok(get_tls_configs()?.into_iter().find(|c| c.matches(host)))
+}
+
+/// Builds the rustls configuration used by the HTTP client, verifying servers against the
+/// platform trust store plus any extra CAs and presenting a client certificate if configured.
+pub fn client_config(config: Option<&TlsConfig>) -> Result<ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let extra_roots = match config {
+        Some(c) => c.ca_certificates()?,
+        None => Vec::new(),
+    };
+    let verifier =
+        rustls_platform_verifier::Verifier::new_with_extra_roots(extra_roots, provider.clone())
+            .map_err(|e| e.to_string())?;
+
+    let builder = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(RecordingVerifier {
+            inner: Arc::new(verifier),
+        }));
+
+    let mut tls = match config.map(TlsConfig::identity).transpose()?.flatten() {
+        Some((certs, key)) => builder
+            .with_client_auth_cert(certs, key)
+            .map_err(|e| e.to_string())?,
+        None => builder.with_no_client_auth(),
+    };
+    tls.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(tls)
+}
+
+fn describe_certificate(der: &CertificateDer<'_>) -> Option<CertificateInfo> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+    let validity = cert.validity();
+    let to_rfc3339 = |secs: i64| {
+        DateTime::<Utc>::from_timestamp(secs, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default()
+    };
+    let sans = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        sans,
+        not_before: to_rfc3339(validity.not_before.timestamp()),
+        not_after: to_rfc3339(validity.not_after.timestamp()),
+        expired: validity.not_after.timestamp() < Utc::now().timestamp(),
+    })
+}
+
+/// Describes a certificate chain, leaf first.
+pub fn describe_chain(chain: &[CertificateDer<'_>]) -> Vec<CertificateInfo> {
+    chain.iter().filter_map(describe_certificate).collect()
+}
+
+/// Describes the certificate chain presented along with the `leaf` certificate of a
+/// connection, leaf first.
+pub fn peer_certificates(leaf: &[u8]) -> Vec<CertificateInfo> {
+    match PEER_CHAINS.lock().unwrap().get(leaf) {
+        Some(chain) => describe_chain(chain),
+        None => describe_chain(&[CertificateDer::from(leaf)]),
+    }
+}
+
+pub fn get_tls_configs() -> Result<Vec<TlsConfig>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare("SELECT host, client_cert_path, client_key_path, pkcs12_path, pkcs12_password, ca_cert_paths FROM tls_configs ORDER BY host")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(TlsConfig {
+                host: row.get(0)?,
+                client_cert_path: row.get(1)?,
+                client_key_path: row.get(2)?,
+                pkcs12_path: row.get(3)?,
+                pkcs12_password: row.get(4)?,
+                ca_cert_paths: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+pub fn save_tls_configs(configs: Vec<TlsConfig>) -> Result<(), String> {
+    let mut conn = db::open()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM tls_configs", [])
+        .map_err(|e| e.to_string())?;
+    for config in configs.iter().filter(|c| !c.host.trim().is_empty()) {
+        tx.execute(
+            "INSERT OR REPLACE INTO tls_configs (host, client_cert_path, client_key_path, pkcs12_path, pkcs12_password, ca_cert_paths) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                config.host.trim(),
+                config.client_cert_path,
+                config.client_key_path,
+                config.pkcs12_path,
+                config.pkcs12_password,
+                serde_json::to_string(&config.ca_cert_paths).map_err(|e| e.to_string())?,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate(name: &str) -> CertificateDer<'static> {
+        let key = rcgen::KeyPair::generate().unwrap();
+        rcgen::CertificateParams::new(vec![name.to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap()
+            .der()
+            .clone()
+    }
+
+    #[test]
+    fn finds_the_chain_of_each_leaf() {
+        let (old, new, issuer) = (
+            certificate("old.example.com"),
+            certificate("new.example.com"),
+            certificate("issuer.example.com"),
+        );
+        let mut chains = PEER_CHAINS.lock().unwrap();
+        chains.insert(old.to_vec(), vec![old.clone(), issuer.clone()]);
+        chains.insert(new.to_vec(), vec![new.clone()]);
+        drop(chains);
+
+        let old_chain = peer_certificates(&old);
+        assert_eq!(old_chain.len(), 2);
+        assert_eq!(old_chain[0].sans, ["DNSName(old.example.com)"]);
+        assert_eq!(
+            peer_certificates(&new)[0].sans,
+            ["DNSName(new.example.com)"]
+        );
+
+        let unknown = certificate("unknown.example.com");
+        assert_eq!(
+            peer_certificates(&unknown)[0].sans,
+            ["DNSName(unknown.example.com)"]
+        );
+    }
+
+    #[test]
+    fn matches_hosts_regardless_of_case() {
+        let config = |host: &str| TlsConfig {
+            host: host.to_string(),
+            ..Default::default()
+        };
+        assert!(config("Example.com").matches("example.com"));
+        assert!(config("*.Example.com").matches("api.example.com"));
+        assert!(config("*.example.com").matches("API.Example.COM"));
+        assert!(!config("*.example.com").matches("example.com"));
+        assert!(!config("*.example.com").matches("badexample.com"));
+    }
+}
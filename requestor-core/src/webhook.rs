@@ -0,0 +1,262 @@
+//! A local endpoint for webhooks, capturing every request sent to it and answering with a
+//! configured reply.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::{db, mock, recorder, rfc3339_now, KeyValue, RequestArgs};
+
+const SETTINGS_KEY: &str = "webhook_receiver";
+
+/// Port the receiver listens on unless another one is saved.
+const DEFAULT_PORT: u16 = 8989;
+
+static SERVER: Lazy<Mutex<Option<Running>>> = Lazy::new(|| Mutex::new(None));
+
+/// The response sent to every captured request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookReply {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl Default for WebhookReply {
+    fn default() -> Self {
+        WebhookReply {
+            status: 200,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookSettings {
+    pub port: u16,
+    /// Only requests under this path are captured, others are answered with a 404.
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub reply: WebhookReply,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            port: DEFAULT_PORT,
+            path: "/".to_string(),
+            reply: WebhookReply::default(),
+        }
+    }
+}
+
+/// A request the receiver captured.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub time: String,
+    pub method: String,
+    /// Path and query of the request.
+    pub path: String,
+    pub headers: Vec<KeyValue>,
+    pub body: String,
+    /// Address of the client that sent the request.
+    pub source: String,
+}
+
+impl CapturedRequest {
+    /// The captured request, sent to `base_url` instead of the receiver, as a request to be
+    /// opened in the editor and saved.
+    pub fn request(&self, base_url: &str) -> RequestArgs {
+        let url = format!("{}{}", base_url.trim_end_matches('/'), self.path);
+        recorder::request_from_parts(&self.method, &url, &self.headers, &self.body)
+    }
+}
+
+struct Running {
+    port: u16,
+    settings: Arc<RwLock<WebhookSettings>>,
+    task: JoinHandle<()>,
+}
+
+/// Whether `path` is `prefix` or below it.
+fn under(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim().trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.is_empty(),
+        None => false,
+    }
+}
+
+async fn handle(
+    req: Request<Incoming>,
+    source: SocketAddr,
+    settings: Arc<RwLock<WebhookSettings>>,
+    on_capture: Arc<dyn Fn(&CapturedRequest) + Send + Sync>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let settings = settings.read().unwrap().clone();
+    if !under(&settings.path, req.uri().path()) {
+        let body = format!("Webhooks are received under {}", settings.path);
+        return Ok(mock::response(404, &[], body));
+    }
+
+    let (parts, body) = req.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|collected| String::from_utf8_lossy(&collected.to_bytes()).into_owned())
+        .unwrap_or_default();
+    on_capture(&CapturedRequest {
+        time: rfc3339_now(),
+        method: parts.method.to_string(),
+        path: parts
+            .uri
+            .path_and_query()
+            .map_or_else(|| parts.uri.path().to_string(), |p| p.to_string()),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(name, value)| KeyValue {
+                key: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+            .collect(),
+        body,
+        source: source.to_string(),
+    });
+
+    let reply = settings.reply;
+    Ok(mock::response(reply.status, &reply.headers, reply.body))
+}
+
+/// Starts receiving webhooks with `settings`, replacing the running receiver, and returns the
+/// port it listens on, which is picked by the system when the port is 0. Like the mock server
+/// it only listens on localhost, services elsewhere reach it through a tunnel. `on_capture` is
+/// called for every request under the configured path.
+pub async fn start(
+    settings: WebhookSettings,
+    on_capture: impl Fn(&CapturedRequest) + Send + Sync + 'static,
+) -> Result<u16, String> {
+    stop();
+    let listener = TcpListener::bind(("127.0.0.1", settings.port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {e}", settings.port))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let settings = Arc::new(RwLock::new(settings));
+    let on_capture: Arc<dyn Fn(&CapturedRequest) + Send + Sync> = Arc::new(on_capture);
+    let served = settings.clone();
+    let task = tokio::spawn(async move {
+        // Connections are aborted along with the receiver when it stops.
+        let mut connections = JoinSet::new();
+        loop {
+            let (stream, source) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    log::warn!("Webhook receiver failed to accept a connection: {e}");
+                    continue;
+                }
+            };
+            while connections.try_join_next().is_some() {}
+            let (settings, on_capture) = (served.clone(), on_capture.clone());
+            connections.spawn(async move {
+                let service = service_fn(move |req| {
+                    handle(req, source, settings.clone(), on_capture.clone())
+                });
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    log::debug!("Webhook receiver connection failed: {e}");
+                }
+            });
+        }
+    });
+
+    *SERVER.lock().unwrap() = Some(Running {
+        port,
+        settings,
+        task,
+    });
+    Ok(port)
+}
+
+pub fn stop() {
+    if let Some(running) = SERVER.lock().unwrap().take() {
+        running.task.abort();
+    }
+}
+
+/// The port the receiver listens on, if it's running.
+pub fn running_port() -> Option<u16> {
+    SERVER.lock().unwrap().as_ref().map(|running| running.port)
+}
+
+pub fn get_webhook_settings() -> Result<WebhookSettings, String> {
+    db::get_setting(SETTINGS_KEY)
+}
+
+/// Saves the receiver's settings. A running receiver uses the new path and reply right away,
+/// the port only changes when it's restarted.
+pub fn save_webhook_settings(settings: WebhookSettings) -> Result<(), String> {
+    db::put_setting(SETTINGS_KEY, &settings)?;
+    if let Some(running) = SERVER.lock().unwrap().as_ref() {
+        *running.settings.write().unwrap() = settings;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_requests_under_the_path() {
+        assert!(under("/", "/anything"));
+        assert!(under("", "/anything"));
+        assert!(under("/hooks", "/hooks"));
+        assert!(under("/hooks/", "/hooks/github"));
+        assert!(!under("/hooks", "/hooksmith"));
+        assert!(!under("/hooks", "/other"));
+    }
+
+    #[test]
+    fn turns_captures_into_requests() {
+        let captured = CapturedRequest {
+            time: String::new(),
+            method: "POST".to_string(),
+            path: "/hooks/github?delivery=1".to_string(),
+            headers: vec![
+                KeyValue {
+                    key: "Host".to_string(),
+                    value: "127.0.0.1:8989".to_string(),
+                },
+                KeyValue {
+                    key: "x-github-event".to_string(),
+                    value: "push".to_string(),
+                },
+            ],
+            body: "{}".to_string(),
+            source: "127.0.0.1:50000".to_string(),
+        };
+
+        let request = captured.request("http://localhost:3000/");
+        assert_eq!(request.url, "http://localhost:3000/hooks/github?delivery=1");
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.headers[0].key, "x-github-event");
+    }
+}
@@ -0,0 +1,418 @@
+//! Tauri commands for the functionality `requestor-core` provides.
+
+use requestor_core::bench::{self, BenchOptions, BenchProgress, BenchReport};
+use requestor_core::collection::{self, Collection};
+use requestor_core::data::{self, DataRow};
+use requestor_core::diff::{self, DiffOptions, DiffSide, ResponseDiff};
+use requestor_core::environment::{self, Environments};
+use requestor_core::graphql::{self, GraphQlSchema, IntrospectArgs, ValidateGraphQlArgs};
+use requestor_core::json_filter;
+use requestor_core::mock::{self, MockHit, MockRoute, MockSettings};
+use requestor_core::proxy::{self, ProxySettings};
+use requestor_core::raw::{self, RawRequest};
+use requestor_core::recorder::{self, RecorderMode, Recording};
+use requestor_core::runner::{self, Run, RunOptions, RunResult};
+use requestor_core::snapshot::{self, Snapshot};
+use requestor_core::stream::{self, StreamEvent};
+use requestor_core::tls::{self, TlsConfig};
+use requestor_core::webhook::{self, CapturedRequest, WebhookSettings};
+use requestor_core::{cancel, RequestArgs, ResponseData};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{save_request, CompletedRequestArgs};
+
+/// Event carrying each chunk or parsed event of a stream to the frontend.
+const STREAM_EVENT: &str = "stream-event";
+
+/// Event carrying the result of each request of a run to the frontend as it completes.
+const RUN_PROGRESS: &str = "run-progress";
+
+/// Event carrying each request the mock server answers to the frontend.
+const MOCK_HIT: &str = "mock-hit";
+
+/// Event carrying a running benchmark's progress to the frontend.
+const BENCH_PROGRESS: &str = "bench-progress";
+
+/// Event carrying each exchange that goes through the recorder to the frontend.
+const RECORDER_EXCHANGE: &str = "recorder-exchange";
+
+/// Event carrying each request the webhook receiver captures to the frontend.
+const WEBHOOK_CAPTURE: &str = "webhook-capture";
+
+#[derive(Clone, Debug, Serialize)]
+struct StreamMessage {
+    stream_id: u64,
+    event: StreamEvent,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RunProgress {
+    run_id: u64,
+    /// Number of requests the run sends when it isn't stopped early.
+    total: usize,
+    result: RunResult,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct BenchMessage {
+    bench_id: u64,
+    progress: BenchProgress,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecorderExchange {
+    recording: Recording,
+    /// The exchange as a history entry.
+    completed: CompletedRequestArgs,
+}
+
+impl From<&Recording> for RecorderExchange {
+    fn from(recording: &Recording) -> Self {
+        RecorderExchange {
+            recording: recording.clone(),
+            completed: CompletedRequestArgs {
+                req: recording.request(),
+                resp: recording.response(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecorderStatus {
+    port: u16,
+    mode: RecorderMode,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RunCollectionOptions {
+    collection_id: i64,
+    #[serde(flatten)]
+    run: RunOptions,
+}
+
+#[tauri::command]
+pub fn cancel_request(id: u64) {
+    cancel::cancel_request(id)
+}
+
+#[tauri::command]
+pub fn get_tls_configs() -> Result<Vec<TlsConfig>, String> {
+    tls::get_tls_configs()
+}
+
+#[tauri::command]
+pub fn save_tls_configs(configs: Vec<TlsConfig>) -> Result<(), String> {
+    tls::save_tls_configs(configs)
+}
+
+#[tauri::command]
+pub fn get_proxy_settings() -> Result<ProxySettings, String> {
+    proxy::get_proxy_settings()
+}
+
+#[tauri::command]
+pub fn save_proxy_settings(settings: ProxySettings) -> Result<(), String> {
+    proxy::save_proxy_settings(settings)
+}
+
+#[tauri::command]
+pub async fn graphql_introspect(args: IntrospectArgs) -> Result<GraphQlSchema, String> {
+    graphql::graphql_introspect(args).await
+}
+
+#[tauri::command]
+pub fn graphql_operations(query: String) -> Vec<String> {
+    graphql::graphql_operations(query)
+}
+
+#[tauri::command]
+pub fn validate_graphql(args: ValidateGraphQlArgs) -> Vec<String> {
+    graphql::validate_graphql(args)
+}
+
+#[tauri::command]
+pub fn get_environments() -> Result<Environments, String> {
+    environment::get_environments()
+}
+
+#[tauri::command]
+pub fn save_environments(environments: Environments) -> Result<(), String> {
+    environment::save_environments(environments)
+}
+
+/// Sets a variable of the active environment, adding it when it isn't there yet.
+#[tauri::command]
+pub fn set_variable(name: String, value: String) -> Result<(), String> {
+    environment::set_variables(&[(name, value)])
+}
+
+#[tauri::command]
+pub async fn render_raw_request(args: RequestArgs) -> Result<RawRequest, String> {
+    raw::render_raw_request(args).await
+}
+
+#[tauri::command]
+pub async fn send_raw_request(id: u64, args: RawRequest) -> Result<ResponseData, String> {
+    raw::send_raw_request(id, args).await
+}
+
+/// Sends a request and pushes its body to the frontend through `stream-event` events as it
+/// arrives, resolving with the whole transcript once the stream ends or is cancelled.
+#[tauri::command]
+pub async fn stream_request(
+    app: AppHandle,
+    id: u64,
+    args: RequestArgs,
+) -> Result<ResponseData, String> {
+    stream::stream_request(id, args, |event| {
+        let message = StreamMessage {
+            stream_id: id,
+            event: event.clone(),
+        };
+        if let Err(e) = app.emit(STREAM_EVENT, message) {
+            log::warn!("Failed to emit {STREAM_EVENT}: {e}");
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_collections() -> Result<Vec<Collection>, String> {
+    collection::get_collections()
+}
+
+#[tauri::command]
+pub fn save_collection(collection: Collection) -> Result<i64, String> {
+    collection::save_collection(collection)
+}
+
+#[tauri::command]
+pub fn delete_collection(id: i64) -> Result<(), String> {
+    collection::delete_collection(id)
+}
+
+/// Runs the requests of a collection in order, pushing each result to the frontend through
+/// `run-progress` events. `id` identifies the run to `cancel_request`, a cancelled run is
+/// saved with the results it got to.
+#[tauri::command]
+pub async fn run_collection(
+    app: AppHandle,
+    id: u64,
+    options: RunCollectionOptions,
+) -> Result<Run, String> {
+    let collection = collection::load(options.collection_id)?;
+    let total = runner::planned_results(&collection, &options.run);
+
+    let mut cancellation = cancel::register(id);
+    let on_result = |result: &RunResult, _: &Result<ResponseData, String>| {
+        let progress = RunProgress {
+            run_id: id,
+            total,
+            result: result.clone(),
+        };
+        if let Err(e) = app.emit(RUN_PROGRESS, progress) {
+            log::error!("Failed to emit run progress: {e}");
+        }
+    };
+    let mut run = runner::run(
+        &collection,
+        &options.run,
+        on_result,
+        cancellation.cancelled(),
+    )
+    .await;
+    runner::save_run(&mut run)?;
+    Ok(run)
+}
+
+#[tauri::command]
+pub fn get_runs() -> Result<Vec<Run>, String> {
+    runner::get_runs()
+}
+
+#[tauri::command]
+pub fn get_snapshots(collection: i64) -> Result<Vec<Snapshot>, String> {
+    snapshot::get_snapshots(collection)
+}
+
+/// Saves a response as the approved snapshot of its request, or a snapshot's edited ignored
+/// paths.
+#[tauri::command]
+pub fn approve_snapshot(snapshot: Snapshot) -> Result<Snapshot, String> {
+    snapshot::approve(snapshot)
+}
+
+#[tauri::command]
+pub fn delete_snapshot(id: i64) -> Result<(), String> {
+    snapshot::delete_snapshot(id)
+}
+
+#[tauri::command]
+pub fn load_data_file(path: String) -> Result<Vec<DataRow>, String> {
+    data::load(std::path::Path::new(&path))
+}
+
+/// Benchmarks a request, pushing its progress to the frontend through `bench-progress`
+/// events. `id` identifies the benchmark to `cancel_request`, a cancelled benchmark reports
+/// the requests it completed.
+#[tauri::command]
+pub async fn run_benchmark(
+    app: AppHandle,
+    id: u64,
+    args: RequestArgs,
+    options: BenchOptions,
+) -> Result<BenchReport, String> {
+    let mut cancellation = cancel::register(id);
+    let on_progress = |progress: &BenchProgress| {
+        let message = BenchMessage {
+            bench_id: id,
+            progress: progress.clone(),
+        };
+        if let Err(e) = app.emit(BENCH_PROGRESS, message) {
+            log::warn!("Failed to emit {BENCH_PROGRESS}: {e}");
+        }
+    };
+    bench::run(args, &options, on_progress, cancellation.cancelled()).await
+}
+
+#[tauri::command]
+pub fn get_mock_settings() -> Result<MockSettings, String> {
+    mock::get_mock_settings()
+}
+
+#[tauri::command]
+pub fn save_mock_settings(settings: MockSettings) -> Result<(), String> {
+    mock::save_mock_settings(settings)
+}
+
+#[tauri::command]
+pub fn add_mock_route(route: MockRoute) -> Result<MockSettings, String> {
+    mock::add_mock_route(route)
+}
+
+/// Starts the mock server with the saved settings, pushing each request it answers to the
+/// frontend through `mock-hit` events.
+#[tauri::command]
+pub async fn start_mock_server(app: AppHandle) -> Result<u16, String> {
+    let settings = mock::get_mock_settings()?;
+    mock::start(settings.port, settings.routes, move |hit: &MockHit| {
+        if let Err(e) = app.emit(MOCK_HIT, hit) {
+            log::warn!("Failed to emit {MOCK_HIT}: {e}");
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn stop_mock_server() {
+    mock::stop()
+}
+
+#[tauri::command]
+pub fn mock_server_port() -> Option<u16> {
+    mock::running_port()
+}
+
+/// Starts the recording proxy, saving each exchange it records to the history and pushing
+/// every exchange to the frontend through `recorder-exchange` events.
+#[tauri::command]
+pub async fn start_recorder(app: AppHandle, port: u16, mode: RecorderMode) -> Result<u16, String> {
+    recorder::start(port, mode, move |recording: &Recording| {
+        let exchange = RecorderExchange::from(recording);
+        if !recording.replayed {
+            if let Err(e) = save_request(exchange.completed.clone()) {
+                log::warn!("Failed to save a recorded request: {e}");
+            }
+        }
+        if let Err(e) = app.emit(RECORDER_EXCHANGE, exchange) {
+            log::warn!("Failed to emit {RECORDER_EXCHANGE}: {e}");
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn stop_recorder() {
+    recorder::stop()
+}
+
+#[tauri::command]
+pub fn recorder_status() -> Option<RecorderStatus> {
+    recorder::running().map(|(port, mode)| RecorderStatus { port, mode })
+}
+
+#[tauri::command]
+pub fn get_recordings() -> Result<Vec<RecorderExchange>, String> {
+    let recordings = recorder::get_recordings()?;
+    Ok(recordings.iter().map(RecorderExchange::from).collect())
+}
+
+#[tauri::command]
+pub fn clear_recordings() -> Result<(), String> {
+    recorder::clear_recordings()
+}
+
+#[tauri::command]
+pub fn recorder_ca_certificate() -> Result<String, String> {
+    recorder::ca_certificate()
+}
+
+/// Writes the recorder's CA certificate to `path`, to be imported into a browser or the
+/// system's trust store.
+#[tauri::command]
+pub fn export_recorder_ca(path: String) -> Result<(), String> {
+    std::fs::write(&path, recorder::ca_certificate()?)
+        .map_err(|e| format!("Failed to write {path}: {e}"))
+}
+
+#[tauri::command]
+pub fn get_webhook_settings() -> Result<WebhookSettings, String> {
+    webhook::get_webhook_settings()
+}
+
+#[tauri::command]
+pub fn save_webhook_settings(settings: WebhookSettings) -> Result<(), String> {
+    webhook::save_webhook_settings(settings)
+}
+
+/// Starts the webhook receiver with the saved settings, pushing each request it captures to
+/// the frontend through `webhook-capture` events.
+#[tauri::command]
+pub async fn start_webhook_receiver(app: AppHandle) -> Result<u16, String> {
+    let settings = webhook::get_webhook_settings()?;
+    webhook::start(settings, move |captured: &CapturedRequest| {
+        if let Err(e) = app.emit(WEBHOOK_CAPTURE, captured) {
+            log::warn!("Failed to emit {WEBHOOK_CAPTURE}: {e}");
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn stop_webhook_receiver() {
+    webhook::stop()
+}
+
+#[tauri::command]
+pub fn webhook_receiver_port() -> Option<u16> {
+    webhook::running_port()
+}
+
+/// A captured request as a request sent to `origin`, to be opened in the editor.
+#[tauri::command]
+pub fn captured_request(captured: CapturedRequest, origin: String) -> RequestArgs {
+    captured.request(&origin)
+}
+
+#[tauri::command]
+pub fn diff_responses(left: DiffSide, right: DiffSide, options: DiffOptions) -> ResponseDiff {
+    diff::diff_responses(&left, &right, &options)
+}
+
+/// The parts of a JSON response a JSONPath or jq-style path selects.
+#[tauri::command]
+pub fn filter_json(body: String, filter: String) -> Result<serde_json::Value, String> {
+    json_filter::filter(&body, &filter)
+}
@@ -0,0 +1,707 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use once_cell::sync::Lazy;
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, Kind, MessageDescriptor, MethodDescriptor, SerializeOptions,
+};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::codegen::http::Uri;
+use tonic::metadata::{
+    AsciiMetadataKey, AsciiMetadataValue, BinaryMetadataKey, BinaryMetadataValue, KeyAndValueRef,
+    MetadataMap,
+};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+use tonic::{Request, Status, Streaming};
+use tonic_reflection::pb::v1::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1::ServerReflectionRequest;
+
+use crate::{cancel, tls, KeyValue};
+
+/// Event carrying each message of a streaming response to the frontend.
+const MESSAGE_EVENT: &str = "grpc-message";
+
+/// Nesting depth up to which message templates expand nested messages, guarding against
+/// recursive types.
+const TEMPLATE_DEPTH: usize = 4;
+
+/// Descriptor pools loaded for each source, so calls don't recompile or re-fetch them.
+static POOLS: Lazy<Mutex<HashMap<String, DescriptorPool>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Where service definitions come from.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GrpcSource {
+    #[default]
+    Reflection,
+    ProtoFiles {
+        paths: Vec<String>,
+        /// Directories imports are resolved against, besides the directory of each file.
+        include_paths: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcServicesArgs {
+    url: String,
+    source: GrpcSource,
+    metadata: Vec<KeyValue>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcMethod {
+    name: String,
+    client_streaming: bool,
+    server_streaming: bool,
+    input_type: String,
+    output_type: String,
+    /// JSON skeleton of the request message with every field set to its default.
+    template: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcService {
+    name: String,
+    methods: Vec<GrpcMethod>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcCallArgs {
+    url: String,
+    source: GrpcSource,
+    service: String,
+    method: String,
+    metadata: Vec<KeyValue>,
+    /// The request message as JSON, or a JSON array of messages for client streaming methods.
+    message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GrpcResponse {
+    /// Response messages as JSON.
+    messages: Vec<String>,
+    status_code: i32,
+    status: String,
+    status_message: String,
+    /// Entries of the `google.rpc.Status` details attached to an error.
+    details: Vec<String>,
+    metadata: Vec<KeyValue>,
+    trailers: Vec<KeyValue>,
+    response_time: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct GrpcMessageEvent {
+    call_id: u64,
+    message: String,
+}
+
+/// The `google.rpc.Status` message servers encode into the `grpc-status-details-bin` trailer.
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+/// Encodes and decodes messages described at runtime rather than by generated types.
+struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+struct DynamicEncoder;
+
+struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> DynamicEncoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> DynamicDecoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: DynamicMessage, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<DynamicMessage>, Status> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+fn pem(label: &str, der: &[u8]) -> String {
+    let encoded = BASE64_STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {label}-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {label}-----\n"));
+    pem
+}
+
+fn identity_pem(certs: &[CertificateDer<'_>], key: &PrivateKeyDer<'_>) -> Identity {
+    let certs: String = certs.iter().map(|c| pem("CERTIFICATE", c)).collect();
+    let label = match key {
+        PrivateKeyDer::Pkcs1(_) => "RSA PRIVATE KEY",
+        PrivateKeyDer::Sec1(_) => "EC PRIVATE KEY",
+        _ => "PRIVATE KEY",
+    };
+    Identity::from_pem(certs, pem(label, key.secret_der()))
+}
+
+/// Connects to `url`, applying the TLS settings saved for its host to `https` URLs.
+async fn connect(url: &str) -> Result<Channel, String> {
+    let uri = Uri::from_str(url.trim()).map_err(|e| e.to_string())?;
+    let mut endpoint = Channel::builder(uri.clone());
+    if uri.scheme_str() == Some("https") {
+        let host = uri.host().unwrap_or_default();
+        let mut tls_config = ClientTlsConfig::new().with_native_roots().domain_name(host);
+        if let Some(config) = tls::config_for_host(host)? {
+            for ca in config.ca_certificates()? {
+                tls_config =
+                    tls_config.ca_certificate(Certificate::from_pem(pem("CERTIFICATE", &ca)));
+            }
+            if let Some((certs, key)) = config.identity()? {
+                tls_config = tls_config.identity(identity_pem(&certs, &key));
+            }
+        }
+        endpoint = endpoint.tls_config(tls_config).map_err(|e| e.to_string())?;
+    }
+    endpoint.connect().await.map_err(|e| e.to_string())
+}
+
+fn metadata_map(metadata: &[KeyValue]) -> Result<MetadataMap, String> {
+    let mut map = MetadataMap::new();
+    for kv in metadata.iter().filter(|kv| !kv.key.is_empty()) {
+        let key = kv.key.trim().to_ascii_lowercase();
+        if key.ends_with("-bin") {
+            let value = BASE64_STANDARD
+                .decode(kv.value.trim())
+                .map_err(|e| format!("{key}: binary metadata must be base64: {e}"))?;
+            map.append_bin(
+                BinaryMetadataKey::from_str(&key).map_err(|e| e.to_string())?,
+                BinaryMetadataValue::from_bytes(&value),
+            );
+        } else {
+            map.append(
+                AsciiMetadataKey::from_str(&key).map_err(|e| e.to_string())?,
+                AsciiMetadataValue::from_str(&kv.value).map_err(|e| e.to_string())?,
+            );
+        }
+    }
+    Ok(map)
+}
+
+fn key_values(map: &MetadataMap) -> Vec<KeyValue> {
+    map.iter()
+        .map(|entry| match entry {
+            KeyAndValueRef::Ascii(key, value) => KeyValue {
+                key: key.to_string(),
+                value: value.to_str().unwrap_or_default().to_string(),
+            },
+            KeyAndValueRef::Binary(key, value) => KeyValue {
+                key: key.to_string(),
+                value: value
+                    .to_bytes()
+                    .map(|b| BASE64_STANDARD.encode(b))
+                    .unwrap_or_default(),
+            },
+        })
+        .collect()
+}
+
+fn request<T>(message: T, metadata: &[KeyValue]) -> Result<Request<T>, String> {
+    let mut request = Request::new(message);
+    *request.metadata_mut() = metadata_map(metadata)?;
+    Ok(request)
+}
+
+async fn reflect(
+    client: &mut ServerReflectionClient<Channel>,
+    metadata: &[KeyValue],
+    message_request: MessageRequest,
+) -> Result<MessageResponse, String> {
+    let message = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(message_request),
+    };
+    let stream = futures_util::stream::iter(vec![message]);
+    let mut responses = client
+        .server_reflection_info(request(stream, metadata)?)
+        .await
+        .map_err(|e| format!("Server reflection failed: {}", e.message()))?
+        .into_inner();
+    let response = responses
+        .message()
+        .await
+        .map_err(|e| e.message().to_string())?
+        .and_then(|r| r.message_response)
+        .ok_or("Empty server reflection response")?;
+    match response {
+        MessageResponse::ErrorResponse(e) => Err(e.error_message),
+        response => Ok(response),
+    }
+}
+
+/// Fetches the descriptors of every service the server exposes through the reflection service.
+async fn reflection_pool(url: &str, metadata: &[KeyValue]) -> Result<DescriptorPool, String> {
+    let mut client = ServerReflectionClient::new(connect(url).await?);
+
+    let services = match reflect(
+        &mut client,
+        metadata,
+        MessageRequest::ListServices(String::new()),
+    )
+    .await?
+    {
+        MessageResponse::ListServicesResponse(list) => list.service,
+        _ => return Err("Unexpected server reflection response".to_string()),
+    };
+
+    let mut files = HashMap::new();
+    let mut pending: Vec<MessageRequest> = services
+        .into_iter()
+        .map(|s| MessageRequest::FileContainingSymbol(s.name))
+        .collect();
+    while let Some(message_request) = pending.pop() {
+        let MessageResponse::FileDescriptorResponse(response) =
+            reflect(&mut client, metadata, message_request).await?
+        else {
+            return Err("Unexpected server reflection response".to_string());
+        };
+        for bytes in response.file_descriptor_proto {
+            let file = prost_types::FileDescriptorProto::decode(bytes.as_slice())
+                .map_err(|e| e.to_string())?;
+            // Servers may leave out dependencies they sent earlier, fetch any still missing.
+            for dependency in &file.dependency {
+                if !files.contains_key(dependency) {
+                    pending.push(MessageRequest::FileByFilename(dependency.clone()));
+                }
+            }
+            files.insert(file.name().to_string(), file);
+        }
+    }
+
+    DescriptorPool::from_file_descriptor_set(prost_types::FileDescriptorSet {
+        file: files.into_values().collect(),
+    })
+    .map_err(|e| e.to_string())
+}
+
+fn proto_files_pool(paths: &[String], include_paths: &[String]) -> Result<DescriptorPool, String> {
+    let paths: Vec<&str> = paths
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if paths.is_empty() {
+        return Err("No .proto files given".to_string());
+    }
+    let mut includes: Vec<String> = include_paths
+        .iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    for path in &paths {
+        let parent = std::path::Path::new(path)
+            .parent()
+            .unwrap_or(std::path::Path::new("."));
+        let parent = match parent.to_string_lossy().as_ref() {
+            "" => ".".to_string(),
+            parent => parent.to_string(),
+        };
+        if !includes.contains(&parent) {
+            includes.push(parent);
+        }
+    }
+
+    let mut compiler = protox::Compiler::new(includes).map_err(|e| e.to_string())?;
+    compiler.include_imports(true);
+    compiler.open_files(paths).map_err(|e| e.to_string())?;
+    Ok(compiler.descriptor_pool())
+}
+
+fn source_key(url: &str, source: &GrpcSource) -> String {
+    match source {
+        GrpcSource::Reflection => format!("reflection {}", url.trim()),
+        GrpcSource::ProtoFiles {
+            paths,
+            include_paths,
+        } => format!("files {paths:?} {include_paths:?}"),
+    }
+}
+
+async fn load_pool(
+    url: &str,
+    source: &GrpcSource,
+    metadata: &[KeyValue],
+    refresh: bool,
+) -> Result<DescriptorPool, String> {
+    let key = source_key(url, source);
+    if !refresh {
+        if let Some(pool) = POOLS.lock().unwrap().get(&key) {
+            return Ok(pool.clone());
+        }
+    }
+    let pool = match source {
+        GrpcSource::Reflection => reflection_pool(url, metadata).await?,
+        GrpcSource::ProtoFiles {
+            paths,
+            include_paths,
+        } => proto_files_pool(paths, include_paths)?,
+    };
+    POOLS.lock().unwrap().insert(key, pool.clone());
+    Ok(pool)
+}
+
+fn kind_template(kind: &Kind, depth: usize) -> Value {
+    match kind {
+        Kind::Double | Kind::Float => json!(0.0),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 | Kind::Uint32 | Kind::Fixed32 => json!(0),
+        // The protobuf JSON mapping encodes 64-bit integers as strings.
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 | Kind::Uint64 | Kind::Fixed64 => json!("0"),
+        Kind::Bool => json!(false),
+        Kind::String | Kind::Bytes => json!(""),
+        Kind::Enum(e) => e
+            .values()
+            .next()
+            .map(|v| json!(v.name()))
+            .unwrap_or(Value::Null),
+        Kind::Message(m) => match m.full_name() {
+            "google.protobuf.Timestamp" => json!("1970-01-01T00:00:00Z"),
+            "google.protobuf.Duration" => json!("0s"),
+            name if name.starts_with("google.protobuf.") => Value::Null,
+            _ if depth >= TEMPLATE_DEPTH => json!({}),
+            _ => message_template(m, depth + 1),
+        },
+    }
+}
+
+/// A JSON object with every field of `message`, only the first member of each oneof is
+/// included as setting several is an error.
+fn message_template(message: &MessageDescriptor, depth: usize) -> Value {
+    let mut object = serde_json::Map::new();
+    for field in message.fields() {
+        if let Some(oneof) = field.containing_oneof() {
+            let first = oneof.fields().next().map(|f| f.number());
+            if !oneof.is_synthetic() && first != Some(field.number()) {
+                continue;
+            }
+        }
+        let value = if field.is_map() {
+            json!({})
+        } else if field.is_list() {
+            json!([kind_template(&field.kind(), depth)])
+        } else {
+            kind_template(&field.kind(), depth)
+        };
+        object.insert(field.json_name().to_string(), value);
+    }
+    Value::Object(object)
+}
+
+fn describe_method(method: &MethodDescriptor) -> GrpcMethod {
+    let template = message_template(&method.input(), 0);
+    let template = match method.is_client_streaming() {
+        true => json!([template]),
+        false => template,
+    };
+    GrpcMethod {
+        name: method.name().to_string(),
+        client_streaming: method.is_client_streaming(),
+        server_streaming: method.is_server_streaming(),
+        input_type: method.input().full_name().to_string(),
+        output_type: method.output().full_name().to_string(),
+        template: serde_json::to_string_pretty(&template).unwrap_or_default(),
+    }
+}
+
+fn to_json(message: &DynamicMessage) -> String {
+    let mut serializer = serde_json::Serializer::pretty(Vec::new());
+    let options = SerializeOptions::new().skip_default_fields(false);
+    match message.serialize_with_options(&mut serializer, &options) {
+        Ok(()) => String::from_utf8_lossy(&serializer.into_inner()).into_owned(),
+        Err(e) => format!("Failed to convert message to JSON: {e}"),
+    }
+}
+
+fn parse_messages(method: &MethodDescriptor, json: &str) -> Result<Vec<DynamicMessage>, String> {
+    let parse = |value: Value| {
+        DynamicMessage::deserialize(method.input(), value).map_err(|e| e.to_string())
+    };
+    let value: Value = match json.trim() {
+        "" => json!({}),
+        json => serde_json::from_str(json).map_err(|e| e.to_string())?,
+    };
+    match value {
+        Value::Array(values) if method.is_client_streaming() => {
+            values.into_iter().map(parse).collect()
+        }
+        _ if method.is_client_streaming() => {
+            Err("Client streaming methods take a JSON array of messages".to_string())
+        }
+        value => Ok(vec![parse(value)?]),
+    }
+}
+
+/// Describes the details of an error status, decoding them with the loaded descriptors where
+/// their types are known.
+fn status_details(pool: &DescriptorPool, status: &Status) -> Vec<String> {
+    let Ok(rpc_status) = RpcStatus::decode(status.details()) else {
+        return Vec::new();
+    };
+    rpc_status
+        .details
+        .into_iter()
+        .map(|any| {
+            let type_name = any.type_url.rsplit('/').next().unwrap_or_default();
+            match pool
+                .get_message_by_name(type_name)
+                .and_then(|desc| DynamicMessage::decode(desc, any.value.as_slice()).ok())
+            {
+                Some(message) => format!("{type_name} {}", to_json(&message)),
+                None => format!("{type_name} {}", BASE64_STANDARD.encode(&any.value)),
+            }
+        })
+        .collect()
+}
+
+fn apply_status(response: &mut GrpcResponse, pool: &DescriptorPool, status: &Status) {
+    response.status_code = status.code() as i32;
+    response.status = format!("{:?}", status.code());
+    response.status_message = status.message().to_string();
+    response.details = status_details(pool, status);
+    response.trailers = key_values(status.metadata());
+}
+
+async fn read_stream(
+    app: &AppHandle,
+    call_id: u64,
+    stream: &mut Streaming<DynamicMessage>,
+    response: &mut GrpcResponse,
+) -> Result<(), Status> {
+    while let Some(message) = stream.message().await? {
+        let message = to_json(&message);
+        let event = GrpcMessageEvent {
+            call_id,
+            message: message.clone(),
+        };
+        if let Err(e) = app.emit(MESSAGE_EVENT, event) {
+            log::warn!("Failed to emit {MESSAGE_EVENT}: {e}");
+        }
+        response.messages.push(message);
+    }
+    if let Some(trailers) = stream.trailers().await? {
+        response.trailers = key_values(&trailers);
+    }
+    Ok(())
+}
+
+async fn call(app: &AppHandle, call_id: u64, args: GrpcCallArgs) -> Result<GrpcResponse, String> {
+    let pool = load_pool(&args.url, &args.source, &args.metadata, false).await?;
+    let service = pool
+        .get_service_by_name(&args.service)
+        .ok_or_else(|| format!("Unknown service {}", args.service))?;
+    let method = service
+        .methods()
+        .find(|m| m.name() == args.method)
+        .ok_or_else(|| format!("Unknown method {}", args.method))?;
+    let messages = parse_messages(&method, &args.message)?;
+    let path = PathAndQuery::from_str(&format!("/{}/{}", service.full_name(), method.name()))
+        .map_err(|e| e.to_string())?;
+    let codec = DynamicCodec {
+        output: method.output(),
+    };
+
+    let mut grpc = tonic::client::Grpc::new(connect(&args.url).await?);
+    grpc.ready().await.map_err(|e| e.to_string())?;
+
+    let start = std::time::Instant::now();
+    let mut response = GrpcResponse {
+        status: "Ok".to_string(),
+        ..Default::default()
+    };
+    let result = match (method.is_client_streaming(), method.is_server_streaming()) {
+        (false, false) => {
+            let message = messages
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| DynamicMessage::new(method.input()));
+            grpc.unary(request(message, &args.metadata)?, path, codec)
+                .await
+                .map(|res| {
+                    response.metadata = key_values(res.metadata());
+                    response.messages.push(to_json(res.get_ref()));
+                })
+        }
+        (true, false) => {
+            let stream = futures_util::stream::iter(messages);
+            grpc.client_streaming(request(stream, &args.metadata)?, path, codec)
+                .await
+                .map(|res| {
+                    response.metadata = key_values(res.metadata());
+                    response.messages.push(to_json(res.get_ref()));
+                })
+        }
+        (false, true) => {
+            let message = messages
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| DynamicMessage::new(method.input()));
+            match grpc
+                .server_streaming(request(message, &args.metadata)?, path, codec)
+                .await
+            {
+                Ok(mut res) => {
+                    response.metadata = key_values(res.metadata());
+                    read_stream(app, call_id, res.get_mut(), &mut response).await
+                }
+                Err(status) => Err(status),
+            }
+        }
+        (true, true) => {
+            let stream = futures_util::stream::iter(messages);
+            match grpc
+                .streaming(request(stream, &args.metadata)?, path, codec)
+                .await
+            {
+                Ok(mut res) => {
+                    response.metadata = key_values(res.metadata());
+                    read_stream(app, call_id, res.get_mut(), &mut response).await
+                }
+                Err(status) => Err(status),
+            }
+        }
+    };
+    if let Err(status) = result {
+        apply_status(&mut response, &pool, &status);
+    }
+    response.response_time = start.elapsed().as_secs_f32() * 1000.0;
+    Ok(response)
+}
+
+/// Loads the service definitions from the source, replacing any cached for it.
+#[tauri::command]
+pub async fn grpc_services(args: GrpcServicesArgs) -> Result<Vec<GrpcService>, String> {
+    let pool = load_pool(&args.url, &args.source, &args.metadata, true).await?;
+    Ok(pool
+        .services()
+        .filter(|s| !s.full_name().starts_with("grpc.reflection."))
+        .map(|service| GrpcService {
+            name: service.full_name().to_string(),
+            methods: service.methods().map(|m| describe_method(&m)).collect(),
+        })
+        .collect())
+}
+
+/// Calls a method, `id` identifies the call to `cancel_request`. Messages of streaming
+/// responses are also pushed through `grpc-message` events as they arrive.
+#[tauri::command]
+pub async fn grpc_call(
+    app: AppHandle,
+    id: u64,
+    args: GrpcCallArgs,
+) -> Result<GrpcResponse, String> {
+    cancel::cancellable(id, call(&app, id, args)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO: &str = r#"
+        syntax = "proto3";
+        package demo;
+
+        message Point {
+            int64 id = 1;
+            repeated string tags = 2;
+            oneof shape {
+                double radius = 3;
+                string label = 4;
+            }
+            Point parent = 5;
+        }
+
+        service Geometry {
+            rpc Get (Point) returns (Point);
+            rpc Upload (stream Point) returns (Point);
+        }
+    "#;
+
+    fn pool() -> DescriptorPool {
+        let dir = std::env::temp_dir().join(format!("requestor-grpc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("demo.proto");
+        std::fs::write(&path, PROTO).unwrap();
+        proto_files_pool(&[path.to_string_lossy().into_owned()], &[]).unwrap()
+    }
+
+    #[test]
+    fn builds_templates_from_proto_files() {
+        let pool = pool();
+        let service = pool.get_service_by_name("demo.Geometry").unwrap();
+        let get = describe_method(&service.methods().next().unwrap());
+        let template: Value = serde_json::from_str(&get.template).unwrap();
+        assert_eq!(template["id"], json!("0"));
+        assert_eq!(template["tags"], json!([""]));
+        assert_eq!(template["radius"], json!(0.0));
+        assert!(template.get("label").is_none());
+        assert_eq!(template["parent"]["parent"]["id"], json!("0"));
+    }
+
+    #[test]
+    fn parses_request_messages() {
+        let pool = pool();
+        let service = pool.get_service_by_name("demo.Geometry").unwrap();
+        let mut methods = service.methods();
+        let (get, upload) = (methods.next().unwrap(), methods.next().unwrap());
+
+        let messages = parse_messages(&get, r#"{"id": "7", "label": "a"}"#).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(to_json(&messages[0]).contains("\"label\": \"a\""));
+
+        assert_eq!(parse_messages(&upload, "[{}, {}]").unwrap().len(), 2);
+        assert!(parse_messages(&upload, "{}").is_err());
+        assert!(parse_messages(&get, r#"{"nope": 1}"#).is_err());
+    }
+}
@@ -1,13 +1,9 @@
-use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use rusqlite::{
-    params,
-    types::{FromSql, FromSqlError, ValueRef},
-    Connection, Result,
+use requestor_core::{
+    body, cancel, client, db, execute, rfc3339_now, tls, KeyValue, RequestArgs, ResponseData,
 };
+use rusqlite::{params, Result};
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
 use syntect::easy::HighlightLines;
 use syntect::html::{
     start_highlighted_html_snippet, styled_line_to_highlighted_html, IncludeBackground,
@@ -15,9 +11,14 @@ use syntect::html::{
 use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 use vscode_theme_syntect::parse_vscode_theme;
+
+mod commands;
+mod grpc;
+mod jwt;
+mod websocket;
 // use tauri::Manager;
 
-static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(|| SyntaxSet::load_defaults_newlines());
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
 static THEME: Lazy<syntect::highlighting::Theme> = Lazy::new(|| {
     let vscode = parse_vscode_theme(include_str!("../highlight_themes/dark_plus.json"))
         .expect("Failed to parse VS Code theme");
@@ -25,198 +26,43 @@ static THEME: Lazy<syntect::highlighting::Theme> = Lazy::new(|| {
     syntect::highlighting::Theme::try_from(vscode).expect("Failed to convert to syntect Theme")
 });
 
-fn rfc3339_now() -> String {
-    let now = SystemTime::now();
-    let now: DateTime<Utc> = now.into();
-    now.to_rfc3339()
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum HttpMethod {
-    GET,
-    POST,
-    PUT,
-    DELETE,
-    PATCH,
-    HEAD,
-    OPTIONS,
-    TRACE,
-    CONNECT,
-}
-
-impl HttpMethod {
-    pub fn as_str(&self) -> &str {
-        match self {
-            HttpMethod::GET => "GET",
-            HttpMethod::POST => "POST",
-            HttpMethod::PUT => "PUT",
-            HttpMethod::DELETE => "DELETE",
-            HttpMethod::PATCH => "PATCH",
-            HttpMethod::HEAD => "HEAD",
-            HttpMethod::OPTIONS => "OPTIONS",
-            HttpMethod::TRACE => "TRACE",
-            HttpMethod::CONNECT => "CONNECT",
-        }
-    }
-}
-
-impl FromSql for HttpMethod {
-    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
-        match value.as_str()? {
-            "GET" => Ok(HttpMethod::GET),
-            "POST" => Ok(HttpMethod::POST),
-            "PUT" => Ok(HttpMethod::PUT),
-            "DELETE" => Ok(HttpMethod::DELETE),
-            "PATCH" => Ok(HttpMethod::PATCH),
-            "HEAD" => Ok(HttpMethod::HEAD),
-            "OPTIONS" => Ok(HttpMethod::OPTIONS),
-            "TRACE" => Ok(HttpMethod::TRACE),
-            "CONNECT" => Ok(HttpMethod::CONNECT),
-            _ => Err(FromSqlError::InvalidType),
-        }
-    }
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct KeyValue {
-    key: String,
-    value: String,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct RequestArgs {
-    method: HttpMethod,
-    url: String,
-    query_params: Vec<KeyValue>,
-    headers: Vec<KeyValue>,
-    body: String,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct ResponseData {
-    status: u16,
-    body: String,
-    response_time: f32,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct CompletedRequestArgs {
     req: RequestArgs,
     resp: ResponseData,
 }
 
+/// Sends a request, `id` identifies it to `cancel_request` while it is in flight.
 #[tauri::command]
-async fn send_request(args: RequestArgs) -> Result<ResponseData, String> {
-    let client = reqwest::Client::new();
-
-    let mut headers = HeaderMap::new();
-    for kv in args.headers {
-        if kv.key.is_empty() {
-            continue;
-        }
-        headers.insert(
-            HeaderName::from_bytes(kv.key.as_bytes()).map_err(|e| e.to_string())?,
-            HeaderValue::from_str(&kv.value).map_err(|e| e.to_string())?,
-        );
-    }
-
-    let start = std::time::Instant::now();
-
-    let res = match args.method {
-        HttpMethod::GET => client.get(&args.url).headers(headers).send().await,
-        HttpMethod::POST => {
-            client
-                .post(&args.url)
-                .headers(headers)
-                .body(args.body)
-                .send()
-                .await
-        }
-        HttpMethod::PUT => {
-            client
-                .put(&args.url)
-                .headers(headers)
-                .body(args.body)
-                .send()
-                .await
-        }
-        HttpMethod::DELETE => client.delete(&args.url).headers(headers).send().await,
-        HttpMethod::PATCH => {
-            client
-                .patch(&args.url)
-                .headers(headers)
-                .body(args.body)
-                .send()
-                .await
-        }
-        HttpMethod::HEAD => client.head(&args.url).headers(headers).send().await,
-        HttpMethod::OPTIONS => {
-            client
-                .request(reqwest::Method::OPTIONS, &args.url)
-                .headers(headers)
-                .send()
-                .await
-        }
-        HttpMethod::TRACE => {
-            client
-                .request(reqwest::Method::TRACE, &args.url)
-                .headers(headers)
-                .send()
-                .await
-        }
-        HttpMethod::CONNECT => {
-            client
-                .request(reqwest::Method::CONNECT, &args.url)
-                .headers(headers)
-                .send()
-                .await
-        }
-    };
-
-    let res = res.map_err(|e| e.to_string())?;
-    let status = res.status().as_u16();
-    let body = res.text().await.map_err(|e| e.to_string())?;
-    let elapsed = start.elapsed().as_secs_f32() * 1000.0;
-
-    Ok(ResponseData {
-        status,
-        response_time: elapsed,
-        body,
-    })
+async fn send_request(id: u64, args: RequestArgs) -> Result<ResponseData, String> {
+    cancel::cancellable(id, execute(args)).await
 }
 
 #[tauri::command]
 fn save_request(args: CompletedRequestArgs) -> Result<(), String> {
-    let conn = Connection::open("requests.db").map_err(|e| e.to_string())?;
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS requests (
-            id INTEGER PRIMARY KEY,
-            method TEXT,
-            url TEXT,
-            query_params TEXT,
-            headers TEXT,
-            body TEXT,
-            status INTEGER,
-            response_body TEXT,
-            response_time REAL,
-            created_at TEXT
-        )",
-        [],
-    )
-    .map_err(|e| e.to_string())?;
+    let conn = db::open()?;
     conn.execute(
-        "INSERT INTO requests (method, url, query_params, headers, body, status, response_body, response_time, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        "INSERT INTO requests (method, url, query_params, headers, body, status, response_body, response_time, created_at, proxy, stream, events, wire, assertions, response_headers, tests, pre_request_script, post_response_script, extractors) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
         params![
             args.req.method.as_str(),
             args.req.url,
             serde_json::to_string(&args.req.query_params).map_err(|e| e.to_string())?,
             serde_json::to_string(&args.req.headers).map_err(|e| e.to_string())?,
-            args.req.body,
+            serde_json::to_string(&args.req.body).map_err(|e| e.to_string())?,
             args.resp.status,
             args.resp.body,
             args.resp.response_time,
-            rfc3339_now()
+            rfc3339_now(),
+            serde_json::to_string(&args.req.proxy).map_err(|e| e.to_string())?,
+            args.req.stream,
+            serde_json::to_string(&args.resp.events).map_err(|e| e.to_string())?,
+            args.req.wire,
+            serde_json::to_string(&args.req.assertions).map_err(|e| e.to_string())?,
+            serde_json::to_string(&args.resp.headers).map_err(|e| e.to_string())?,
+            serde_json::to_string(&args.resp.tests).map_err(|e| e.to_string())?,
+            args.req.pre_request_script,
+            args.req.post_response_script,
+            serde_json::to_string(&args.req.extractors).map_err(|e| e.to_string())?
         ],
     ).map_err(|e| e.to_string())?;
     Ok(())
@@ -225,9 +71,9 @@ fn save_request(args: CompletedRequestArgs) -> Result<(), String> {
 #[tauri::command]
 fn get_requests() -> Result<Vec<CompletedRequestArgs>, String> {
     log::debug!("Getting requests!");
-    let conn = Connection::open("requests.db").map_err(|e| e.to_string())?;
+    let conn = db::open()?;
     let mut stmt = conn
-        .prepare("SELECT method, url, query_params, headers, body, status, response_body, response_time FROM requests")
+        .prepare("SELECT method, url, query_params, headers, body, status, response_body, response_time, proxy, stream, events, wire, assertions, response_headers, tests, pre_request_script, post_response_script, extractors FROM requests")
         .map_err(|e| e.to_string())?;
     let rows = stmt
         .query_map([], |row| {
@@ -238,12 +84,41 @@ fn get_requests() -> Result<Vec<CompletedRequestArgs>, String> {
                     query_params: serde_json::from_str(&row.get::<_, String>(2)?)
                         .unwrap_or_default(),
                     headers: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
-                    body: row.get(4)?,
+                    body: body::RequestBody::from_stored(&row.get::<_, String>(4)?),
+                    proxy: row
+                        .get::<_, Option<String>>(8)?
+                        .and_then(|p| serde_json::from_str(&p).ok())
+                        .unwrap_or_default(),
+                    stream: row.get::<_, Option<bool>>(9)?.unwrap_or_default(),
+                    wire: row.get::<_, Option<bool>>(11)?.unwrap_or_default(),
+                    assertions: row
+                        .get::<_, Option<String>>(12)?
+                        .and_then(|a| serde_json::from_str(&a).ok())
+                        .unwrap_or_default(),
+                    pre_request_script: row.get::<_, Option<String>>(15)?.unwrap_or_default(),
+                    post_response_script: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
+                    extractors: row
+                        .get::<_, Option<String>>(17)?
+                        .and_then(|e| serde_json::from_str(&e).ok())
+                        .unwrap_or_default(),
                 },
                 resp: ResponseData {
                     status: row.get(5)?,
-                    body: serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
+                    headers: row
+                        .get::<_, Option<String>>(13)?
+                        .and_then(|h| serde_json::from_str(&h).ok())
+                        .unwrap_or_default(),
+                    body: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
                     response_time: row.get(7)?,
+                    events: row
+                        .get::<_, Option<String>>(10)?
+                        .and_then(|e| serde_json::from_str(&e).ok())
+                        .unwrap_or_default(),
+                    tests: row
+                        .get::<_, Option<String>>(14)?
+                        .and_then(|t| serde_json::from_str(&t).ok())
+                        .unwrap_or_default(),
+                    ..Default::default()
                 },
             })
         })
@@ -260,8 +135,8 @@ fn get_requests() -> Result<Vec<CompletedRequestArgs>, String> {
 fn highlight_code(code: String, lang: String) -> Result<String, String> {
     let theme = &THEME;
     let syntax = SYNTAX_SET.find_syntax_by_extension(&lang).unwrap();
-    let mut h = HighlightLines::new(syntax, &theme);
-    let (mut html, _bg) = start_highlighted_html_snippet(&theme);
+    let mut h = HighlightLines::new(syntax, theme);
+    let (mut html, _bg) = start_highlighted_html_snippet(theme);
 
     for line in LinesWithEndings::from(&code) {
         let regions = h
@@ -296,7 +171,62 @@ pub fn run() {
             send_request,
             save_request,
             get_requests,
-            highlight_code
+            highlight_code,
+            jwt::find_jwts,
+            jwt::verify_jwt,
+            jwt::mint_jwt,
+            commands::get_tls_configs,
+            commands::save_tls_configs,
+            commands::get_proxy_settings,
+            commands::save_proxy_settings,
+            commands::graphql_introspect,
+            commands::graphql_operations,
+            commands::validate_graphql,
+            commands::stream_request,
+            commands::get_environments,
+            commands::save_environments,
+            commands::set_variable,
+            grpc::grpc_services,
+            grpc::grpc_call,
+            commands::render_raw_request,
+            commands::send_raw_request,
+            commands::get_collections,
+            commands::save_collection,
+            commands::delete_collection,
+            commands::run_collection,
+            commands::get_runs,
+            commands::get_snapshots,
+            commands::approve_snapshot,
+            commands::delete_snapshot,
+            commands::load_data_file,
+            commands::run_benchmark,
+            commands::get_mock_settings,
+            commands::save_mock_settings,
+            commands::add_mock_route,
+            commands::start_mock_server,
+            commands::stop_mock_server,
+            commands::mock_server_port,
+            commands::start_recorder,
+            commands::stop_recorder,
+            commands::recorder_status,
+            commands::get_recordings,
+            commands::clear_recordings,
+            commands::recorder_ca_certificate,
+            commands::export_recorder_ca,
+            commands::get_webhook_settings,
+            commands::save_webhook_settings,
+            commands::start_webhook_receiver,
+            commands::stop_webhook_receiver,
+            commands::webhook_receiver_port,
+            commands::captured_request,
+            commands::diff_responses,
+            commands::filter_json,
+            commands::cancel_request,
+            websocket::ws_connect,
+            websocket::ws_send,
+            websocket::ws_ping,
+            websocket::ws_close,
+            websocket::get_ws_sessions
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
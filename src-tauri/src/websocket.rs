@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderValue, SEC_WEBSOCKET_PROTOCOL};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::{client, db, rfc3339_now, tls, KeyValue};
+
+/// Event carrying each logged message of a session to the frontend.
+const MESSAGE_EVENT: &str = "ws-message";
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Open sessions, keyed by session id, with the channel feeding frames to their socket.
+static SESSIONS: Lazy<Mutex<HashMap<u64, UnboundedSender<Message>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+type Socket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsConnectArgs {
+    url: String,
+    headers: Vec<KeyValue>,
+    protocols: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Sent,
+    Received,
+    /// Connection events such as the handshake completing or the socket failing.
+    Info,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsMessage {
+    pub direction: Direction,
+    /// `text`, `binary`, `ping`, `pong`, `close`, `open` or `error`.
+    pub kind: String,
+    /// Text payload, base64 for binary frames.
+    pub data: String,
+    pub timestamp: String,
+}
+
+impl WsMessage {
+    fn new(direction: Direction, kind: &str, data: String) -> WsMessage {
+        WsMessage {
+            direction,
+            kind: kind.to_string(),
+            data,
+            timestamp: rfc3339_now(),
+        }
+    }
+
+    fn from_frame(direction: Direction, frame: &Message) -> Option<WsMessage> {
+        let (kind, data) = match frame {
+            Message::Text(text) => ("text", text.to_string()),
+            Message::Binary(data) => ("binary", BASE64_STANDARD.encode(data)),
+            Message::Ping(data) => ("ping", String::from_utf8_lossy(data).into_owned()),
+            Message::Pong(data) => ("pong", String::from_utf8_lossy(data).into_owned()),
+            Message::Close(Some(frame)) => (
+                "close",
+                format!("{} {}", u16::from(frame.code), frame.reason),
+            ),
+            Message::Close(None) => ("close", String::new()),
+            Message::Frame(_) => return None,
+        };
+        Some(WsMessage::new(direction, kind, data))
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct WsEvent {
+    session_id: u64,
+    /// Whether the session is still open after this message.
+    connected: bool,
+    message: WsMessage,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameKind {
+    Text,
+    /// Data is base64 encoded.
+    Binary,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsSendArgs {
+    session_id: u64,
+    kind: FrameKind,
+    data: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsCloseArgs {
+    session_id: u64,
+    code: u16,
+    reason: String,
+}
+
+/// A finished session as stored in the history.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsSession {
+    url: String,
+    headers: Vec<KeyValue>,
+    protocols: Vec<String>,
+    messages: Vec<WsMessage>,
+    created_at: String,
+}
+
+fn emit(app: &AppHandle, session_id: u64, connected: bool, message: WsMessage) {
+    let event = WsEvent {
+        session_id,
+        connected,
+        message,
+    };
+    if let Err(e) = app.emit(MESSAGE_EVENT, event) {
+        log::warn!("Failed to emit {MESSAGE_EVENT}: {e}");
+    }
+}
+
+fn queue(session_id: u64, frame: Message) -> Result<(), String> {
+    let sessions = SESSIONS.lock().unwrap();
+    let sender = sessions
+        .get(&session_id)
+        .ok_or_else(|| format!("WebSocket session {session_id} is not open"))?;
+    sender.send(frame).map_err(|e| e.to_string())
+}
+
+/// Pumps frames between the socket and the frontend until the connection ends, then saves the
+/// transcript into the history.
+async fn run_session(
+    app: AppHandle,
+    session_id: u64,
+    socket: Socket,
+    mut outgoing: UnboundedReceiver<Message>,
+    mut session: WsSession,
+) {
+    let (mut sink, mut source) = socket.split();
+    let mut record = |connected: bool, message: WsMessage| {
+        session.messages.push(message.clone());
+        emit(&app, session_id, connected, message);
+    };
+
+    loop {
+        tokio::select! {
+            Some(frame) = outgoing.recv() => {
+                let message = WsMessage::from_frame(Direction::Sent, &frame);
+                if let Err(e) = sink.send(frame).await {
+                    record(false, WsMessage::new(Direction::Info, "error", e.to_string()));
+                    break;
+                }
+                if let Some(message) = message {
+                    record(true, message);
+                }
+            }
+            incoming = source.next() => match incoming {
+                Some(Ok(frame)) => {
+                    if let Some(message) = WsMessage::from_frame(Direction::Received, &frame) {
+                        record(true, message);
+                    }
+                }
+                Some(Err(e)) => {
+                    record(false, WsMessage::new(Direction::Info, "error", e.to_string()));
+                    break;
+                }
+                None => {
+                    record(false, WsMessage::new(Direction::Info, "close", "Disconnected".to_string()));
+                    break;
+                }
+            }
+        }
+    }
+
+    SESSIONS.lock().unwrap().remove(&session_id);
+    if let Err(e) = save_session(&session) {
+        log::warn!("Failed to save WebSocket session: {e}");
+    }
+}
+
+fn save_session(session: &WsSession) -> Result<(), String> {
+    let conn = db::open()?;
+    conn.execute(
+        "INSERT INTO ws_sessions (url, headers, protocols, messages, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            session.url,
+            serde_json::to_string(&session.headers).map_err(|e| e.to_string())?,
+            serde_json::to_string(&session.protocols).map_err(|e| e.to_string())?,
+            serde_json::to_string(&session.messages).map_err(|e| e.to_string())?,
+            session.created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Opens a WebSocket connection and returns the id used to address the session. Messages are
+/// delivered through `ws-message` events.
+#[tauri::command]
+pub async fn ws_connect(app: AppHandle, args: WsConnectArgs) -> Result<u64, String> {
+    let mut request = args
+        .url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| e.to_string())?;
+    request
+        .headers_mut()
+        .extend(client::header_map(&args.headers)?);
+    let protocols: Vec<&str> = args
+        .protocols
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !protocols.is_empty() {
+        request.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_str(&protocols.join(", ")).map_err(|e| e.to_string())?,
+        );
+    }
+
+    let tls_config = tls::config_for_host(request.uri().host().unwrap_or_default())?;
+    let mut tls = tls::client_config(tls_config.as_ref())?;
+    // The upgrade handshake is an HTTP/1.1 request.
+    tls.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    let (socket, response) = tokio_tungstenite::connect_async_tls_with_config(
+        request,
+        None,
+        false,
+        Some(Connector::Rustls(Arc::new(tls))),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::unbounded_channel();
+    SESSIONS.lock().unwrap().insert(session_id, sender);
+
+    let protocol = response
+        .headers()
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|p| p.to_str().ok());
+    let opened = WsMessage::new(
+        Direction::Info,
+        "open",
+        match protocol {
+            Some(protocol) => format!("Connected ({} {protocol})", response.status()),
+            None => format!("Connected ({})", response.status()),
+        },
+    );
+    emit(&app, session_id, true, opened.clone());
+
+    let session = WsSession {
+        url: args.url,
+        headers: args.headers,
+        protocols: args.protocols,
+        messages: vec![opened],
+        created_at: rfc3339_now(),
+    };
+    tauri::async_runtime::spawn(run_session(app, session_id, socket, receiver, session));
+    Ok(session_id)
+}
+
+#[tauri::command]
+pub fn ws_send(args: WsSendArgs) -> Result<(), String> {
+    let frame = match args.kind {
+        FrameKind::Text => Message::text(args.data),
+        FrameKind::Binary => Message::binary(
+            BASE64_STANDARD
+                .decode(args.data.trim())
+                .map_err(|e| format!("Binary data must be base64: {e}"))?,
+        ),
+    };
+    queue(args.session_id, frame)
+}
+
+#[tauri::command]
+pub fn ws_ping(session: u64) -> Result<(), String> {
+    queue(session, Message::Ping(Default::default()))
+}
+
+/// Starts the closing handshake, the session ends once the server acknowledges it.
+#[tauri::command]
+pub fn ws_close(args: WsCloseArgs) -> Result<(), String> {
+    queue(
+        args.session_id,
+        Message::Close(Some(CloseFrame {
+            code: CloseCode::from(args.code),
+            reason: args.reason.into(),
+        })),
+    )
+}
+
+#[tauri::command]
+pub fn get_ws_sessions() -> Result<Vec<WsSession>, String> {
+    let conn = db::open()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT url, headers, protocols, messages, created_at FROM ws_sessions ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(WsSession {
+                url: row.get(0)?,
+                headers: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or_default(),
+                protocols: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+                messages: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_frames() {
+        let binary = WsMessage::from_frame(Direction::Sent, &Message::binary(vec![1, 2, 3]));
+        assert_eq!(binary.unwrap().data, "AQID");
+
+        let close = Message::Close(Some(CloseFrame {
+            code: CloseCode::from(4000),
+            reason: "bye".into(),
+        }));
+        let close = WsMessage::from_frame(Direction::Received, &close).unwrap();
+        assert_eq!(
+            (close.kind.as_str(), close.data.as_str()),
+            ("close", "4000 bye")
+        );
+    }
+}
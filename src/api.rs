@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_wasm_bindgen::{Serializer, from_value};
+use wasm_bindgen::prelude::*;
+
+use crate::app::invoke;
+use crate::models::CancelRequestArgs;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize)]
+struct CommandArgs<'a, T: Serialize> {
+    args: &'a T,
+}
+
+/// Invokes a Tauri command, passing the fields of `args` as its named arguments.
+pub async fn call<A: Serialize, R: DeserializeOwned>(cmd: &str, args: &A) -> Result<R, String> {
+    // JSON compatible so maps (e.g. `serde_json::Value` objects) cross the IPC boundary intact.
+    let js_args = args
+        .serialize(&Serializer::json_compatible())
+        .map_err(|e| format!("serialize err: {e:?}"))?;
+
+    let js_val = invoke(cmd, js_args).await.map_err(|e| {
+        e.as_string()
+            .unwrap_or_else(|| format!("{cmd} failed: {e:?}"))
+    })?;
+
+    from_value(js_val).map_err(|e| format!("deserialize err: {e:?}"))
+}
+
+/// Invokes a Tauri command that takes a single `args` parameter.
+pub async fn call_with_args<A: Serialize, R: DeserializeOwned>(
+    cmd: &str,
+    args: &A,
+) -> Result<R, String> {
+    call(cmd, &CommandArgs { args }).await
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen, catch)]
+    async fn tauri_listen(
+        event: &str,
+        handler: &Closure<dyn FnMut(JsValue)>,
+    ) -> Result<JsValue, JsValue>;
+}
+
+#[derive(Deserialize)]
+struct Event<T> {
+    payload: T,
+}
+
+/// Subscribes `handler` to an event emitted by the backend for the lifetime of the app.
+pub async fn listen<T: DeserializeOwned + 'static>(
+    event: &str,
+    mut handler: impl FnMut(T) + 'static,
+) -> Result<(), String> {
+    let closure = Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+        match from_value::<Event<T>>(value) {
+            Ok(event) => handler(event.payload),
+            Err(e) => web_sys::console::error_1(&format!("deserialize err: {e:?}").into()),
+        }
+    });
+    tauri_listen(event, &closure).await.map_err(|e| {
+        e.as_string()
+            .unwrap_or_else(|| format!("listen {event} failed: {e:?}"))
+    })?;
+    closure.forget();
+    Ok(())
+}
+
+/// A new id for a cancellable request, unique across all panels.
+pub fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Asks the backend to abort request `id`, its command then fails with "Request cancelled".
+pub fn cancel_request(id: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err) = call::<_, ()>("cancel_request", &CancelRequestArgs { id }).await {
+            web_sys::console::error_1(&err.into());
+        }
+    });
+}
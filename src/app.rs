@@ -4,63 +4,157 @@ use dioxus::prelude::*;
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::prelude::*;
 
+use crate::api::{call, cancel_request, listen, next_request_id};
+use crate::components::assertion_editor::{assertion_editor, test_results};
+use crate::components::benchmark::BenchmarkPanel;
+use crate::components::body_editor::body_editor;
+use crate::components::collections::CollectionsPanel;
+use crate::components::environments::EnvironmentsPanel;
+use crate::components::extractor_editor::{extracted_variables, extractor_editor};
+use crate::components::grpc_panel::GrpcPanel;
+use crate::components::json_view::JsonView;
+use crate::components::jsonrpc_editor::jsonrpc_results;
+use crate::components::jwt_panel::JwtPanel;
 use crate::components::key_value_editor::key_value_editor;
+use crate::components::mock_server::{MockServerPanel, add_route, route_from_history};
+use crate::components::proxy_settings::{ProxySettingsPanel, request_proxy_editor};
+use crate::components::raw_panel::{RawPanel, wire_view};
+use crate::components::recorder::RecorderPanel;
+use crate::components::response_diff::ResponseDiffPanel;
+use crate::components::script_editor::script_editor;
+use crate::components::stream_view::stream_event_list;
+use crate::components::tls_settings::TlsSettings;
+use crate::components::webhook_receiver::WebhookPanel;
+use crate::components::websocket_panel::WebSocketPanel;
 use crate::highlight::highlight_to_html;
 use crate::models::{
-    CompletedRequest, CompletedRequestArgs, RequestState, ResponseState, SendRequestArgs,
+    AssertionResult, CompletedRequest, CompletedRequestArgs, MockSettings, RequestState,
+    ResponseState, SendRequestArgs, StreamEvent, StreamMessage,
 };
 use crate::response_code_reference::http_status_meaning;
 static CSS: Asset = asset!("/assets/styles.css");
 
+/// Suggested methods, any other token (e.g. `PROPFIND`, `PURGE`) can be typed in.
+const HTTP_METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT",
+];
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
     pub async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
 }
 
+/// A request that has been sent and not answered yet.
+#[derive(Clone, Copy, PartialEq)]
+struct InFlight {
+    id: u64,
+    stream: bool,
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, ms: i32) -> i32;
+}
+
+fn tests_summary(tests: &[AssertionResult]) -> String {
+    let passed = tests.iter().filter(|t| t.passed).count();
+    format!("Tests ({passed}/{})", tests.len())
+}
+
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        set_timeout(&resolve, ms);
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
 pub fn App() -> Element {
-    let mut request = use_signal(RequestState::default);
+    let mut request = use_signal(|| RequestState {
+        method: "GET".to_string(),
+        ..Default::default()
+    });
     let response = use_signal(|| None::<ResponseState>);
     let mut request_history = use_signal(Vec::<CompletedRequest>::new);
+    let mock_settings = use_signal(MockSettings::default);
     let mut show_status_help = use_signal(|| false);
+    let mut response_tab = use_signal(|| "body");
+
+    let mut lang = use_signal(String::new);
+    let mut highlighted_html = use_signal(String::new);
 
-    let mut lang = use_signal(|| String::new());
-    let mut highlighted_html = use_signal(|| String::new());
+    let mut in_flight = use_signal(|| None::<InFlight>);
+    let mut elapsed = use_signal(|| 0.0);
+    let mut stream_events = use_signal(Vec::<StreamEvent>::new);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            let subscribed = listen("stream-event", move |message: StreamMessage| {
+                if in_flight().is_some_and(|f| f.id == message.stream_id) {
+                    stream_events.with_mut(|events| events.push(message.event));
+                }
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
 
     let send_request = {
-        let request_signal = request.clone();
-        let response_signal = response.clone();
+        let request_signal = request;
+        let response_signal = response;
 
         move |_| {
-            let req_owned = SendRequestArgs {
-                args: request_signal.read().clone(),
-            };
+            let req_owned = request_signal.read().clone();
 
-            let js_args = match to_value(&req_owned) {
-                Ok(v) => v,
-                Err(err) => {
-                    web_sys::console::error_1(&format!("serialize err: {err:?}").into());
-                    return;
+            // A new request supersedes the one still in flight.
+            if let Some(previous) = in_flight() {
+                cancel_request(previous.id);
+            }
+            let id = next_request_id();
+            let started = js_sys::Date::now();
+            in_flight.set(Some(InFlight {
+                id,
+                stream: req_owned.stream,
+            }));
+            stream_events.set(Vec::new());
+            elapsed.set(0.0);
+
+            wasm_bindgen_futures::spawn_local(async move {
+                while in_flight().is_some_and(|f| f.id == id) {
+                    elapsed.set(js_sys::Date::now() - started);
+                    sleep(100).await;
                 }
-            };
+            });
 
-            let mut response_signal = response_signal.clone();
+            let mut response_signal = response_signal;
 
             wasm_bindgen_futures::spawn_local(async move {
-                let result: Result<ResponseState, String> = invoke("send_request", js_args)
-                    .await
-                    .map_err(|e| format!("invoke failed: {e:?}"))
-                    .and_then(|js_val| {
-                        from_value::<ResponseState>(js_val)
-                            .map_err(|e| format!("deserialize err: {e:?}"))
-                    });
+                let command = if req_owned.stream {
+                    "stream_request"
+                } else {
+                    "send_request"
+                };
+                let args = SendRequestArgs {
+                    id,
+                    args: req_owned.clone(),
+                };
+                let result: Result<ResponseState, String> = call(command, &args).await;
+
+                // Responses to superseded requests must not replace the newer one's.
+                if in_flight().map(|f| f.id) != Some(id) {
+                    return;
+                }
+                in_flight.set(None);
 
                 match result {
                     Ok(resp) => {
                         response_signal.set(Some(resp.clone()));
                         let completed_request_data = CompletedRequest {
-                            req: request_signal.read().clone(),
-                            resp: resp,
+                            req: req_owned,
+                            resp,
                         };
                         let completed_request = CompletedRequestArgs {
                             args: completed_request_data.clone(),
@@ -94,6 +188,7 @@ pub fn App() -> Element {
                             status: 0,
                             response_time: 0.0,
                             body: msg,
+                            ..Default::default()
                         }));
                     }
                 }
@@ -101,8 +196,14 @@ pub fn App() -> Element {
         }
     };
 
+    let cancel = move |_| {
+        if let Some(current) = in_flight() {
+            cancel_request(current.id);
+        }
+    };
+
     use_effect(move || {
-        let mut history_signal = request_history.clone();
+        let mut history_signal = request_history;
 
         wasm_bindgen_futures::spawn_local(async move {
             let result: Result<Vec<CompletedRequest>, String> =
@@ -140,7 +241,7 @@ pub fn App() -> Element {
 
     use_effect(move || {
         let maybe_response = response.read().clone();
-        let language = lang.clone();
+        let language = lang;
 
         wasm_bindgen_futures::spawn_local(async move {
             if let Some(resp) = maybe_response.as_ref() {
@@ -157,8 +258,6 @@ pub fn App() -> Element {
         });
     });
 
-    let method = request.read().method.clone();
-
     rsx! {
         link { rel: "stylesheet", href: CSS }
 
@@ -166,15 +265,17 @@ pub fn App() -> Element {
             h1 { "Requestor" }
 
             div { class: "request-line",
-                select {
+                input {
+                    class: "method-input",
+                    list: "http-methods",
+                    placeholder: "Method",
                     value: "{request.read().method}",
-                    onchange: move |e| request.with_mut(|r| r.method = e.value()),
-                    option { value: "", disabled: true, "Method" }
-                    option { "GET" }
-                    option { "POST" }
-                    option { "PUT" }
-                    option { "DELETE" }
-                    option { "PATCH" }
+                    oninput: move |e| request.with_mut(|r| r.method = e.value().trim().to_string()),
+                }
+                datalist { id: "http-methods",
+                    for method in HTTP_METHODS {
+                        option { value: "{method}" }
+                    }
                 }
 
                 input {
@@ -183,7 +284,37 @@ pub fn App() -> Element {
                     oninput: move |e| request.with_mut(|r| r.url = e.value()),
                 }
 
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: request.read().stream,
+                        onchange: move |e| request.with_mut(|r| r.stream = e.checked()),
+                    }
+                    "Stream"
+                }
+
+                label {
+                    class: "checkbox",
+                    title: "Send over a direct HTTP/1.1 connection and show the bytes exchanged. Proxy settings don't apply.",
+                    input {
+                        r#type: "checkbox",
+                        checked: request.read().wire,
+                        onchange: move |e| request.with_mut(|r| r.wire = e.checked()),
+                    }
+                    "Wire"
+                }
+
                 button { onclick: send_request, "Send" }
+                if in_flight().is_some() {
+                    button { onclick: cancel, "Cancel" }
+                }
+            }
+
+            if in_flight().is_some() {
+                p { class: "in-flight",
+                    span { class: "spinner" }
+                    {format!("Waiting for response... {:.1} s", elapsed() / 1000.0)}
+                }
             }
 
             section {
@@ -191,21 +322,40 @@ pub fn App() -> Element {
                 {key_value_editor(request, |r| &mut r.headers)}
             }
 
-            if matches!(method.as_str(), "GET" | "DELETE") {
-                section {
-                    h3 { "Query Params" }
-                    {key_value_editor(request, |r| &mut r.query_params)}
-                }
+            section {
+                h3 { "Query Params" }
+                {key_value_editor(request, |r| &mut r.query_params)}
             }
 
-            if matches!(method.as_str(), "POST" | "PUT" | "PATCH") {
-                section {
-                    h3 { "Body" }
-                    textarea {
-                        placeholder: "Raw request body...",
-                        value: "{request.read().body}",
-                        oninput: move |e| request.with_mut(|r| r.body = e.value()),
-                    }
+            section {
+                h3 { "Body" }
+                {body_editor(request)}
+            }
+
+            section {
+                h3 { "Proxy" }
+                {request_proxy_editor(request)}
+            }
+
+            section {
+                h3 { "Assertions" }
+                {assertion_editor(request)}
+            }
+
+            section {
+                h3 { "Extract variables" }
+                {extractor_editor(request)}
+            }
+
+            section {
+                h3 { "Scripts" }
+                {script_editor(request)}
+            }
+
+            if in_flight().is_some_and(|f| f.stream) {
+                section { class: "response",
+                    h3 { "Streaming... ({stream_events.read().len()} events)" }
+                    {stream_event_list(stream_events.read().clone())}
                 }
             }
 
@@ -234,8 +384,99 @@ pub fn App() -> Element {
                                 "{resp.response_time}"
                             }
 
-                            // pre { "{resp.body}" }
-                            div { dangerous_inner_html: "{highlighted_html}" }
+                            if !resp.certificates.is_empty() {
+                                details { class: "cert-chain",
+                                    summary { "Server certificate chain ({resp.certificates.len()})" }
+                                    for cert in resp.certificates.iter() {
+                                        div { class: "cert",
+                                            p {
+                                                strong { "Subject: " }
+                                                "{cert.subject}"
+                                            }
+                                            p {
+                                                strong { "Issuer: " }
+                                                "{cert.issuer}"
+                                            }
+                                            if !cert.sans.is_empty() {
+                                                p {
+                                                    strong { "SANs: " }
+                                                    "{cert.sans.join(\", \")}"
+                                                }
+                                            }
+                                            p { class: if cert.expired { "status-error" } else { "" },
+                                                strong { "Valid: " }
+                                                "{cert.not_before} → {cert.not_after}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
+                            div { class: "tabs",
+                                button {
+                                    class: if response_tab() == "body" { "tab active" } else { "tab" },
+                                    onclick: move |_| response_tab.set("body"),
+                                    "Body"
+                                }
+                                button {
+                                    class: if response_tab() == "headers" { "tab active" } else { "tab" },
+                                    onclick: move |_| response_tab.set("headers"),
+                                    "Headers ({resp.headers.len()})"
+                                }
+                                button {
+                                    class: if response_tab() == "tests" { "tab active" } else { "tab" },
+                                    onclick: move |_| response_tab.set("tests"),
+                                    {tests_summary(&resp.tests)}
+                                }
+                                button {
+                                    class: if response_tab() == "console" { "tab active" } else { "tab" },
+                                    onclick: move |_| response_tab.set("console"),
+                                    "Console ({resp.console.len()})"
+                                }
+                            }
+
+                            if response_tab() == "headers" {
+                                table { class: "response-headers",
+                                    for (idx , header) in resp.headers.iter().enumerate() {
+                                        tr { key: "{idx}",
+                                            td { "{header.key}" }
+                                            td { "{header.value}" }
+                                        }
+                                    }
+                                }
+                            } else if response_tab() == "tests" {
+                                {test_results(resp.tests.clone())}
+                                {extracted_variables(resp.extracted.clone())}
+                            } else if response_tab() == "console" {
+                                pre { class: "script-console", {resp.console.join("\n")} }
+                            } else {
+                                    if !resp.json_rpc.is_empty() {
+                                        div { class: "jsonrpc-response",
+                                            h4 { "JSON-RPC results" }
+                                            {jsonrpc_results(resp.json_rpc.clone())}
+                                        }
+                                    }
+
+                                    if let Some(wire) = resp.wire.clone() {
+                                        details { class: "wire-exchange", open: true,
+                                            summary { "Wire" }
+                                            {wire_view(wire)}
+                                        }
+                                    }
+
+                                    if !resp.events.is_empty() {
+                                        details { class: "stream-transcript",
+                                            summary { "Events ({resp.events.len()})" }
+                                            {stream_event_list(resp.events.clone())}
+                                        }
+                                    }
+
+                                    if lang() == "json" {
+                                        JsonView { response }
+                                    } else {
+                                        div { dangerous_inner_html: "{highlighted_html}" }
+                                    }
+                            }
                         }
                     } else {
                         rsx! {}
@@ -243,6 +484,32 @@ pub fn App() -> Element {
                 }
             }
 
+            JwtPanel { request, response }
+
+            WebSocketPanel {}
+
+            GrpcPanel {}
+
+            RawPanel { request }
+
+            EnvironmentsPanel { response }
+
+            CollectionsPanel { request }
+
+            BenchmarkPanel { request }
+
+            MockServerPanel { settings: mock_settings }
+
+            RecorderPanel { request, history: request_history }
+
+            WebhookPanel { request }
+
+            ResponseDiffPanel { history: request_history, response }
+
+            TlsSettings {}
+
+            ProxySettingsPanel {}
+
             {
                 let history: Vec<CompletedRequest> = request_history.read().clone();
 
@@ -256,9 +523,10 @@ pub fn App() -> Element {
                                         key: "{idx}",
                                         class: "history-item",
                                         onclick: {
-                                            let mut request = request.clone();
+                                            let mut request = request;
+                                            let saved = completed.req.clone();
                                             move |_| {
-                                                request.set(completed.req.clone());
+                                                request.set(saved.clone());
                                             }
                                         },
                                         p {
@@ -269,6 +537,23 @@ pub fn App() -> Element {
                                             span { class: "response-time",
                                                 {format!("({:.2} ms)", completed.resp.response_time)}
                                             }
+                                            if !completed.resp.tests.is_empty() {
+                                                span { class: if completed.resp.tests.iter().all(|t| t.passed) { "test-badge test-passed" } else { "test-badge test-failed" },
+                                                    {tests_summary(&completed.resp.tests)}
+                                                }
+                                            }
+                                            button {
+                                                class: "history-mock",
+                                                title: "Serve this response from the mock server",
+                                                onclick: {
+                                                    let route = route_from_history(&completed);
+                                                    move |e: Event<MouseData>| {
+                                                        e.stop_propagation();
+                                                        add_route(route.clone(), mock_settings);
+                                                    }
+                                                },
+                                                "Mock"
+                                            }
                                         }
                                     }
                                 }
@@ -1 +1,22 @@
+pub mod assertion_editor;
+pub mod benchmark;
+pub mod body_editor;
+pub mod collections;
+pub mod environments;
+pub mod extractor_editor;
+pub mod graphql_editor;
+pub mod grpc_panel;
+pub mod json_view;
+pub mod jsonrpc_editor;
+pub mod jwt_panel;
 pub mod key_value_editor;
+pub mod mock_server;
+pub mod proxy_settings;
+pub mod raw_panel;
+pub mod recorder;
+pub mod response_diff;
+pub mod script_editor;
+pub mod stream_view;
+pub mod tls_settings;
+pub mod webhook_receiver;
+pub mod websocket_panel;
@@ -0,0 +1,155 @@
+use crate::models::{Assertion, AssertionKind, AssertionResult, RequestState};
+use dioxus::prelude::*;
+
+const KINDS: [AssertionKind; 11] = [
+    AssertionKind::Status,
+    AssertionKind::HeaderPresent,
+    AssertionKind::HeaderEquals,
+    AssertionKind::HeaderMatches,
+    AssertionKind::JsonPathEquals,
+    AssertionKind::JsonPathContains,
+    AssertionKind::JsonPathType,
+    AssertionKind::BodyContains,
+    AssertionKind::BodyMatches,
+    AssertionKind::ResponseTimeBelow,
+    AssertionKind::JsonSchema,
+];
+
+/// The select value and label of a kind.
+fn kind_name(kind: AssertionKind) -> (&'static str, &'static str) {
+    match kind {
+        AssertionKind::Status => ("status", "Status"),
+        AssertionKind::HeaderPresent => ("header_present", "Header present"),
+        AssertionKind::HeaderEquals => ("header_equals", "Header equals"),
+        AssertionKind::HeaderMatches => ("header_matches", "Header matches regex"),
+        AssertionKind::JsonPathEquals => ("json_path_equals", "JSONPath equals"),
+        AssertionKind::JsonPathContains => ("json_path_contains", "JSONPath contains"),
+        AssertionKind::JsonPathType => ("json_path_type", "JSONPath type"),
+        AssertionKind::BodyContains => ("body_contains", "Body contains"),
+        AssertionKind::BodyMatches => ("body_matches", "Body matches regex"),
+        AssertionKind::ResponseTimeBelow => ("response_time_below", "Response time below"),
+        AssertionKind::JsonSchema => ("json_schema", "JSON Schema"),
+    }
+}
+
+/// Placeholders of the target and expected inputs, empty when the input isn't used.
+fn placeholders(kind: AssertionKind) -> (&'static str, &'static str) {
+    match kind {
+        AssertionKind::Status => ("", "200, 2xx or 200-299"),
+        AssertionKind::HeaderPresent => ("Header name", ""),
+        AssertionKind::HeaderEquals => ("Header name", "Value"),
+        AssertionKind::HeaderMatches => ("Header name", "Regex"),
+        AssertionKind::JsonPathEquals | AssertionKind::JsonPathContains => ("$.path", "JSON value"),
+        AssertionKind::JsonPathType => ("$.path", "string, number, boolean, array, object or null"),
+        AssertionKind::BodyContains => ("", "Text"),
+        AssertionKind::BodyMatches => ("", "Regex"),
+        AssertionKind::ResponseTimeBelow => ("", "Milliseconds"),
+        AssertionKind::JsonSchema => ("", "JSON Schema"),
+    }
+}
+
+fn kind_for_value(value: &str) -> AssertionKind {
+    KINDS
+        .into_iter()
+        .find(|kind| kind_name(*kind).0 == value)
+        .unwrap_or_default()
+}
+
+pub fn assertion_editor(mut request: Signal<RequestState>) -> Element {
+    let assertions = request.read().assertions.clone();
+
+    rsx! {
+        div { class: "kv-editor",
+            for (idx , assertion) in assertions.into_iter().enumerate() {
+                {
+                    let (value, _) = kind_name(assertion.kind);
+                    let (target_placeholder, expected_placeholder) = placeholders(assertion.kind);
+                    rsx! {
+                        div { class: "assertion-row",
+                            input {
+                                r#type: "checkbox",
+                                title: "Enabled",
+                                checked: assertion.enabled,
+                                onchange: move |e| request.with_mut(|r| r.assertions[idx].enabled = e.checked()),
+                            }
+                            select {
+                                value: "{value}",
+                                onchange: move |e| {
+                                    request
+                                        .with_mut(|r| {
+                                            r.assertions[idx].kind = kind_for_value(&e.value());
+                                        })
+                                },
+                                for (value , label) in KINDS.map(kind_name) {
+                                    option { value: "{value}", "{label}" }
+                                }
+                            }
+                            if !target_placeholder.is_empty() {
+                                input {
+                                    placeholder: "{target_placeholder}",
+                                    value: "{assertion.target}",
+                                    oninput: move |e| request.with_mut(|r| r.assertions[idx].target = e.value()),
+                                }
+                            }
+                            if assertion.kind == AssertionKind::JsonSchema {
+                                textarea {
+                                    class: "assertion-schema",
+                                    placeholder: "{expected_placeholder}",
+                                    value: "{assertion.expected}",
+                                    oninput: move |e| request.with_mut(|r| r.assertions[idx].expected = e.value()),
+                                }
+                            } else if !expected_placeholder.is_empty() {
+                                input {
+                                    placeholder: "{expected_placeholder}",
+                                    value: "{assertion.expected}",
+                                    oninput: move |e| request.with_mut(|r| r.assertions[idx].expected = e.value()),
+                                }
+                            }
+                            button {
+                                onclick: move |_| {
+                                    request
+                                        .with_mut(|r| {
+                                            r.assertions.remove(idx);
+                                        });
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+
+            button {
+                onclick: move |_| request.with_mut(|r| r.assertions.push(Assertion::default())),
+                "+ Add"
+            }
+        }
+    }
+}
+
+pub fn test_results(results: Vec<AssertionResult>) -> Element {
+    rsx! {
+        if results.is_empty() {
+            p { class: "test-hint", "No assertions were run for this request." }
+        }
+        ul { class: "test-results",
+            for (idx , result) in results.into_iter().enumerate() {
+                li {
+                    key: "{idx}",
+                    class: if result.passed { "test-passed" } else { "test-failed" },
+                    span { class: "test-mark",
+                        if result.passed {
+                            "✓"
+                        } else {
+                            "✗"
+                        }
+                    }
+                    span { "{result.name}" }
+                    if !result.message.is_empty() {
+                        pre { "{result.message}" }
+                    }
+                }
+            }
+        }
+    }
+}
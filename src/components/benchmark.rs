@@ -0,0 +1,225 @@
+use crate::api::{call, cancel_request, listen, next_request_id};
+use crate::models::{
+    BenchMessage, BenchOptions, BenchProgress, BenchReport, RequestState, RunBenchmarkArgs,
+};
+use dioxus::prelude::*;
+
+/// Width of the longest bar of the latency histogram, in pixels.
+const HISTOGRAM_WIDTH: u64 = 300;
+
+/// Parses an optional number field, empty meaning unset.
+fn optional<T: std::str::FromStr>(text: &str) -> Result<Option<T>, ()> {
+    match text.trim() {
+        "" => Ok(None),
+        text => text.parse().map(Some).map_err(|_| ()),
+    }
+}
+
+fn bench_report(report: &BenchReport) -> Element {
+    let most = report.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    let latency = &report.latency;
+    let stats = [
+        ("min", latency.min),
+        ("mean", latency.mean),
+        ("p50", latency.p50),
+        ("p90", latency.p90),
+        ("p99", latency.p99),
+        ("max", latency.max),
+    ];
+    rsx! {
+        p { class: if report.failed == 0 { "test-passed" } else { "test-failed" },
+            {
+                format!(
+                    "{} requests in {:.2} s, {:.2} requests/s, {} failed{}",
+                    report.requests,
+                    report.duration / 1000.0,
+                    report.throughput,
+                    report.failed,
+                    if report.cancelled { ", cancelled" } else { "" },
+                )
+            }
+        }
+        ul { class: "bench-statuses",
+            for status in report.statuses.iter() {
+                li { key: "{status.status}",
+                    class: if status.status < 400 { "test-passed" } else { "test-failed" },
+                    "{status.status}: {status.count}"
+                }
+            }
+            for error in report.errors.iter() {
+                li { key: "{error.error}", class: "test-failed", "{error.error}: {error.count}" }
+            }
+        }
+        table { class: "bench-latency",
+            tr {
+                for (name , _) in stats {
+                    th { "{name}" }
+                }
+            }
+            tr {
+                for (_ , value) in stats {
+                    td { {format!("{value:.2} ms")} }
+                }
+            }
+        }
+        div { class: "bench-histogram",
+            for bucket in report.histogram.iter() {
+                div { class: "bench-bucket",
+                    span { class: "bench-range",
+                        {format!("{:.2} – {:.2} ms", bucket.start, bucket.end)}
+                    }
+                    span {
+                        class: "bench-bar",
+                        style: format!("width: {}px", bucket.count * HISTOGRAM_WIDTH / most.max(1)),
+                    }
+                    span { "{bucket.count}" }
+                }
+            }
+        }
+    }
+}
+
+/// Sends the current request many times concurrently and reports its latency.
+#[component]
+pub fn BenchmarkPanel(request: Signal<RequestState>) -> Element {
+    let mut requests = use_signal(|| "100".to_string());
+    let mut duration = use_signal(String::new);
+    let mut concurrency = use_signal(|| "10".to_string());
+    let mut rate = use_signal(String::new);
+    let mut running = use_signal(|| None::<u64>);
+    let mut progress = use_signal(|| None::<BenchProgress>);
+    let mut report = use_signal(|| None::<BenchReport>);
+    let mut error = use_signal(|| None::<String>);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            let subscribed = listen("bench-progress", move |message: BenchMessage| {
+                if running() == Some(message.bench_id) {
+                    progress.set(Some(message.progress));
+                }
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let start = move |_| {
+        let parsed = (
+            optional::<u64>(&requests()),
+            optional::<f64>(&duration()),
+            concurrency().trim().parse::<u32>(),
+            optional::<f64>(&rate()),
+        );
+        let (Ok(count), Ok(seconds), Ok(concurrency), Ok(rate)) = parsed else {
+            error.set(Some(
+                "Requests, duration, concurrency and rate must be numbers".to_string(),
+            ));
+            return;
+        };
+        if count.is_none() && seconds.is_none() {
+            error.set(Some("Set a number of requests or a duration".to_string()));
+            return;
+        }
+        let id = next_request_id();
+        let args = RunBenchmarkArgs {
+            id,
+            args: request.read().clone(),
+            options: BenchOptions {
+                requests: count,
+                duration_ms: seconds.map(|s| (s * 1000.0) as u64),
+                concurrency,
+                rate,
+            },
+        };
+        running.set(Some(id));
+        progress.set(None);
+        report.set(None);
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, BenchReport>("run_benchmark", &args).await {
+                Ok(finished) => report.set(Some(finished)),
+                Err(err) => error.set(Some(err)),
+            }
+            running.set(None);
+        });
+    };
+
+    let cancel = move |_| {
+        if let Some(id) = running() {
+            cancel_request(id);
+        }
+    };
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary { "Benchmark" }
+                p { class: "settings-hint",
+                    "Sends the current request repeatedly. Its pre-request script runs once and responses aren't checked."
+                }
+                div { class: "environment-row",
+                    label {
+                        "Requests "
+                        input {
+                            class: "run-number",
+                            value: "{requests}",
+                            oninput: move |e| requests.set(e.value()),
+                        }
+                    }
+                    label {
+                        "Duration (s) "
+                        input {
+                            class: "run-number",
+                            value: "{duration}",
+                            oninput: move |e| duration.set(e.value()),
+                        }
+                    }
+                    label {
+                        "Concurrency "
+                        input {
+                            class: "run-number",
+                            value: "{concurrency}",
+                            oninput: move |e| concurrency.set(e.value()),
+                        }
+                    }
+                    label {
+                        "Requests/s "
+                        input {
+                            class: "run-number",
+                            placeholder: "max",
+                            value: "{rate}",
+                            oninput: move |e| rate.set(e.value()),
+                        }
+                    }
+                    if running().is_some() {
+                        button { onclick: cancel, "Cancel" }
+                    } else {
+                        button { onclick: start, "Run" }
+                    }
+                }
+
+                if let Some(err) = error() {
+                    p { class: "status-error", "{err}" }
+                }
+                if running().is_some() {
+                    p { class: "settings-hint",
+                        match progress() {
+                            Some(p) => format!(
+                                "Running... {} requests, {} failed, {:.1} s",
+                                p.requests,
+                                p.failed,
+                                p.elapsed / 1000.0,
+                            ),
+                            None => "Running...".to_string(),
+                        }
+                    }
+                }
+                if let Some(report) = report() {
+                    {bench_report(&report)}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::components::graphql_editor::GraphQlEditor;
+use crate::components::jsonrpc_editor::jsonrpc_editor;
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{JsonRpcCall, KeyValue, MultipartPart, PartKind, RequestBody, RequestState};
+use dioxus::prelude::*;
+
+const CONTENT_TYPES: [&str; 7] = [
+    "application/json",
+    "application/xml",
+    "text/plain",
+    "text/html",
+    "text/csv",
+    "application/javascript",
+    "application/x-ndjson",
+];
+
+fn mode_name(body: &RequestBody) -> &'static str {
+    match body {
+        RequestBody::None => "none",
+        RequestBody::Raw { .. } => "raw",
+        RequestBody::UrlEncoded { .. } => "url_encoded",
+        RequestBody::Multipart { .. } => "multipart",
+        RequestBody::Binary { .. } => "binary",
+        RequestBody::GraphQl { .. } => "graphql",
+        RequestBody::JsonRpc { .. } => "json_rpc",
+    }
+}
+
+pub fn body_for_mode(mode: &str) -> RequestBody {
+    match mode {
+        "raw" => RequestBody::Raw {
+            content_type: "application/json".to_string(),
+            text: String::new(),
+        },
+        "url_encoded" => RequestBody::UrlEncoded { fields: Vec::new() },
+        "multipart" => RequestBody::Multipart { parts: Vec::new() },
+        "binary" => RequestBody::Binary {
+            path: String::new(),
+            content_type: String::new(),
+        },
+        "graphql" => RequestBody::GraphQl {
+            query: String::new(),
+            variables: String::new(),
+            operation_name: String::new(),
+        },
+        "json_rpc" => RequestBody::JsonRpc {
+            calls: vec![JsonRpcCall::default()],
+            batch: false,
+        },
+        _ => RequestBody::None,
+    }
+}
+
+fn form_fields(request: &mut RequestState) -> &mut Vec<KeyValue> {
+    if !matches!(request.body, RequestBody::UrlEncoded { .. }) {
+        request.body = body_for_mode("url_encoded");
+    }
+    match &mut request.body {
+        RequestBody::UrlEncoded { fields } => fields,
+        _ => unreachable!(),
+    }
+}
+
+fn multipart_parts(request: &mut RequestState) -> &mut Vec<MultipartPart> {
+    if !matches!(request.body, RequestBody::Multipart { .. }) {
+        request.body = body_for_mode("multipart");
+    }
+    match &mut request.body {
+        RequestBody::Multipart { parts } => parts,
+        _ => unreachable!(),
+    }
+}
+
+fn multipart_editor(mut request: Signal<RequestState>) -> Element {
+    let parts = {
+        let snapshot = request.read();
+        multipart_parts(&mut snapshot.clone()).clone()
+    };
+
+    rsx! {
+        div { class: "kv-editor",
+            for (idx , part) in parts.iter().enumerate() {
+                div { class: "multipart-row",
+                    input {
+                        placeholder: "Name",
+                        value: "{part.name}",
+                        oninput: move |e| request.with_mut(|r| multipart_parts(r)[idx].name = e.value()),
+                    }
+                    select {
+                        value: if part.kind == PartKind::File { "file" } else { "text" },
+                        onchange: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    multipart_parts(r)[idx].kind = if e.value() == "file" {
+                                        PartKind::File
+                                    } else {
+                                        PartKind::Text
+                                    };
+                                })
+                        },
+                        option { value: "text", "Text" }
+                        option { value: "file", "File" }
+                    }
+                    input {
+                        placeholder: if part.kind == PartKind::File { "File path" } else { "Value" },
+                        value: "{part.value}",
+                        oninput: move |e| request.with_mut(|r| multipart_parts(r)[idx].value = e.value()),
+                    }
+                    input {
+                        list: "content-types",
+                        placeholder: "Content-Type (optional)",
+                        value: "{part.content_type}",
+                        oninput: move |e| request.with_mut(|r| multipart_parts(r)[idx].content_type = e.value()),
+                    }
+                    button {
+                        onclick: move |_| {
+                            request
+                                .with_mut(|r| {
+                                    multipart_parts(r).remove(idx);
+                                });
+                        },
+                        "✕"
+                    }
+                }
+            }
+
+            button {
+                onclick: move |_| request.with_mut(|r| multipart_parts(r).push(MultipartPart::default())),
+                "+ Add"
+            }
+        }
+    }
+}
+
+pub fn body_editor(mut request: Signal<RequestState>) -> Element {
+    let body = request.read().body.clone();
+    let mode = mode_name(&body);
+
+    rsx! {
+        div { class: "body-editor",
+            select {
+                value: "{mode}",
+                onchange: move |e| request.with_mut(|r| r.body = body_for_mode(&e.value())),
+                option { value: "none", "None" }
+                option { value: "raw", "Raw" }
+                option { value: "url_encoded", "Form URL-encoded" }
+                option { value: "multipart", "Multipart form-data" }
+                option { value: "binary", "Binary file" }
+                option { value: "graphql", "GraphQL" }
+                option { value: "json_rpc", "JSON-RPC" }
+            }
+
+            datalist { id: "content-types",
+                for content_type in CONTENT_TYPES {
+                    option { value: "{content_type}" }
+                }
+            }
+
+            match body {
+                RequestBody::None => rsx! {},
+                RequestBody::GraphQl { .. } => rsx! {
+                    GraphQlEditor { request }
+                },
+                RequestBody::JsonRpc { .. } => jsonrpc_editor(request),
+                RequestBody::Raw { content_type, text } => rsx! {
+                    input {
+                        list: "content-types",
+                        placeholder: "Content-Type",
+                        value: "{content_type}",
+                        oninput: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    if let RequestBody::Raw { content_type, .. } = &mut r.body {
+                                        *content_type = e.value();
+                                    }
+                                })
+                        },
+                    }
+                    textarea {
+                        placeholder: "Raw request body...",
+                        value: "{text}",
+                        oninput: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    if let RequestBody::Raw { text, .. } = &mut r.body {
+                                        *text = e.value();
+                                    }
+                                })
+                        },
+                    }
+                },
+                RequestBody::UrlEncoded { .. } => key_value_editor(request, form_fields),
+                RequestBody::Multipart { .. } => multipart_editor(request),
+                RequestBody::Binary { path, content_type } => rsx! {
+                    input {
+                        placeholder: "Path of the file to send",
+                        value: "{path}",
+                        oninput: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    if let RequestBody::Binary { path, .. } = &mut r.body {
+                                        *path = e.value();
+                                    }
+                                })
+                        },
+                    }
+                    input {
+                        list: "content-types",
+                        placeholder: "Content-Type (defaults to application/octet-stream)",
+                        value: "{content_type}",
+                        oninput: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    if let RequestBody::Binary { content_type, .. } = &mut r.body {
+                                        *content_type = e.value();
+                                    }
+                                })
+                        },
+                    }
+                },
+            }
+        }
+    }
+}
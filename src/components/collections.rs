@@ -0,0 +1,647 @@
+use crate::api::{call, cancel_request, listen, next_request_id};
+use crate::components::response_diff::{diff_view, ignored};
+use crate::models::{
+    ApproveSnapshotArgs, Collection, CollectionRequest, DataRow, DeleteCollectionArgs,
+    DeleteSnapshotArgs, GetSnapshotsArgs, LoadDataFileArgs, RequestState, Run, RunCollectionArgs,
+    RunOptions, RunProgress, RunResult, RunSummary, SaveCollectionArgs, Snapshot,
+};
+use dioxus::prelude::*;
+
+fn summary_text(summary: &RunSummary) -> String {
+    let passed = summary.requests - summary.failed_requests;
+    let assertions = summary.assertions - summary.failed_assertions;
+    let stopped = if summary.stopped {
+        ", stopped early"
+    } else {
+        ""
+    };
+    format!(
+        "{passed}/{} requests and {assertions}/{} assertions passed, {:.2} ms total, {:.2} ms average{stopped}",
+        summary.requests, summary.assertions, summary.total_time, summary.average_time
+    )
+}
+
+fn data_hint(rows: &[DataRow]) -> String {
+    let mut columns: Vec<&String> = rows.iter().flat_map(|row| row.keys()).collect();
+    columns.sort();
+    columns.dedup();
+    let columns: Vec<&str> = columns.into_iter().map(String::as_str).collect();
+    format!("{} rows of {}", rows.len(), columns.join(", "))
+}
+
+/// The folders requests of a collection are in, sorted.
+fn folders(collection: &Collection) -> Vec<String> {
+    let mut folders: Vec<String> = collection
+        .requests
+        .iter()
+        .map(|r| r.folder.trim_matches('/').to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+/// Heading for the results of an iteration, with the data file row it used.
+fn iteration_heading(iteration: u32, data: &[DataRow]) -> String {
+    match data.get(iteration as usize - 1) {
+        Some(row) => {
+            let columns: Vec<String> = row.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            format!("Iteration {iteration}: {}", columns.join(", "))
+        }
+        None => format!("Iteration {iteration}"),
+    }
+}
+
+/// How a result compared to its snapshot. `approve` saves the received response of the result
+/// at an index as its snapshot, it's only given for the latest run.
+fn snapshot_check(result: &RunResult, idx: usize, approve: Option<Callback<usize>>) -> Element {
+    let Some(check) = result.snapshot.as_ref() else {
+        return rsx! {};
+    };
+    let approve_button = |label: &'static str| match (approve, &check.received) {
+        (Some(approve), Some(_)) => rsx! {
+            button { onclick: move |_| approve.call(idx), "{label}" }
+        },
+        _ => rsx! {},
+    };
+    rsx! {
+        if check.updated {
+            p { class: "settings-hint", "Snapshot updated" }
+        } else if let Some(diff) = check.diff.as_ref().filter(|diff| !diff.is_empty()) {
+            details { class: "snapshot-diff",
+                summary {
+                    "Snapshot changed "
+                    {approve_button("Accept")}
+                }
+                {diff_view(diff)}
+            }
+        } else if check.diff.is_none() {
+            {approve_button("Approve as snapshot")}
+        }
+    }
+}
+
+/// Results of a run, under a heading per iteration when there's more than one.
+fn run_results(
+    results: Vec<RunResult>,
+    data: &[DataRow],
+    approve: Option<Callback<usize>>,
+) -> Element {
+    let grouped = !data.is_empty() || results.iter().any(|r| r.iteration > 1);
+    let headings: Vec<Option<String>> = results
+        .iter()
+        .enumerate()
+        .map(|(idx, result)| {
+            let first = idx == 0 || results[idx - 1].iteration != result.iteration;
+            (grouped && first).then(|| iteration_heading(result.iteration, data))
+        })
+        .collect();
+    rsx! {
+        ul { class: "test-results run-results",
+            for (idx , (result , heading)) in results.into_iter().zip(headings).enumerate() {
+                Fragment { key: "{idx}",
+                    if let Some(heading) = heading {
+                        li { class: "run-iteration", "{heading}" }
+                    }
+                    li {
+                        class: if result.passed() { "test-passed" } else { "test-failed" },
+                        span { class: "test-mark",
+                            if result.passed() {
+                                "✓"
+                            } else {
+                                "✗"
+                            }
+                        }
+                        span { "#{result.iteration} {result.name} " }
+                        span { class: "response-time",
+                            "{result.method} {result.url} → {result.status} "
+                            {format!("({:.2} ms)", result.response_time)}
+                        }
+                        if let Some(err) = result.error.as_ref() {
+                            pre { "{err}" }
+                        }
+                        for test in result.tests.iter().filter(|t| !t.passed) {
+                            pre { "{test.name}: {test.message}" }
+                        }
+                        {snapshot_check(&result, idx, approve)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Saved requests grouped into collections and folders, which can be run in order.
+#[component]
+pub fn CollectionsPanel(request: Signal<RequestState>) -> Element {
+    let mut collections = use_signal(Vec::<Collection>::new);
+    let mut selected = use_signal(|| None::<i64>);
+    let mut new_name = use_signal(String::new);
+    let mut request_name = use_signal(String::new);
+    let mut request_folder = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut run_folder = use_signal(String::new);
+    let mut delay = use_signal(|| "0".to_string());
+    let mut iterations = use_signal(|| "1".to_string());
+    let mut stop_on_failure = use_signal(|| false);
+    let mut run_request = use_signal(String::new);
+    let mut data_path = use_signal(String::new);
+    let mut data = use_signal(Vec::<DataRow>::new);
+    let mut running = use_signal(|| None::<u64>);
+    let mut total = use_signal(|| 0usize);
+    let mut results = use_signal(Vec::<RunResult>::new);
+    let mut finished = use_signal(|| None::<Run>);
+    let mut run_data = use_signal(Vec::<DataRow>::new);
+    let mut runs = use_signal(Vec::<Run>::new);
+    let mut update_snapshots = use_signal(|| false);
+    let mut run_collection = use_signal(|| None::<i64>);
+    let mut snapshots = use_signal(Vec::<Snapshot>::new);
+
+    let load = move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Vec<Collection>>("get_collections", &()).await {
+                Ok(saved) => collections.set(saved),
+                Err(err) => error.set(Some(err)),
+            }
+            match call::<_, Vec<Run>>("get_runs", &()).await {
+                Ok(saved) => runs.set(saved),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let save = move |collection: Collection| {
+        wasm_bindgen_futures::spawn_local(async move {
+            let args = SaveCollectionArgs { collection };
+            match call::<_, i64>("save_collection", &args).await {
+                Ok(id) => {
+                    selected.set(Some(id));
+                    error.set(None);
+                    load();
+                }
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let load_snapshots = move |collection: Option<i64>| {
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(collection) = collection else {
+                snapshots.set(Vec::new());
+                return;
+            };
+            let args = GetSnapshotsArgs { collection };
+            match call::<_, Vec<Snapshot>>("get_snapshots", &args).await {
+                Ok(saved) => snapshots.set(saved),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    use_effect(move || load_snapshots(selected()));
+
+    let save_snapshot = move |snapshot: Snapshot| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Snapshot>("approve_snapshot", &ApproveSnapshotArgs { snapshot }).await {
+                Ok(_) => load_snapshots(selected()),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let delete_snapshot = move |id: i64| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("delete_snapshot", &DeleteSnapshotArgs { id }).await {
+                Ok(()) => load_snapshots(selected()),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let approve = Callback::new(move |idx: usize| {
+        let Some(collection_id) = run_collection() else {
+            return;
+        };
+        let Some(result) = results.read().get(idx).cloned() else {
+            return;
+        };
+        let Some(response) = result.snapshot.as_ref().and_then(|c| c.received.clone()) else {
+            return;
+        };
+        let ignore = snapshots
+            .read()
+            .iter()
+            .find(|s| s.folder == result.folder && s.name == result.name)
+            .map(|s| s.ignore.clone())
+            .unwrap_or_default();
+        let snapshot = Snapshot {
+            id: 0,
+            collection_id,
+            folder: result.folder,
+            name: result.name,
+            response,
+            ignore,
+            approved_at: String::new(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Snapshot>("approve_snapshot", &ApproveSnapshotArgs { snapshot }).await {
+                Ok(_) => {
+                    results.with_mut(|r| {
+                        if let Some(check) = r.get_mut(idx).and_then(|r| r.snapshot.as_mut()) {
+                            check.updated = true;
+                        }
+                    });
+                    load_snapshots(selected());
+                }
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    });
+
+    use_hook(move || {
+        load();
+        wasm_bindgen_futures::spawn_local(async move {
+            let subscribed = listen("run-progress", move |progress: RunProgress| {
+                if running() == Some(progress.run_id) {
+                    total.set(progress.total);
+                    results.with_mut(|r| r.push(progress.result));
+                }
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let current = collections
+        .read()
+        .iter()
+        .find(|c| c.id.is_some() && c.id == selected())
+        .cloned();
+
+    let add = move |_| {
+        let name = new_name.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        new_name.set(String::new());
+        save(Collection {
+            name,
+            ..Default::default()
+        });
+    };
+
+    let delete = move |_| {
+        let Some(id) = selected() else { return };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("delete_collection", &DeleteCollectionArgs { id }).await {
+                Ok(()) => {
+                    selected.set(None);
+                    load();
+                }
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let save_request = {
+        let current = current.clone();
+        move |_| {
+            let Some(mut collection) = current.clone() else {
+                return;
+            };
+            let name = request_name.read().trim().to_string();
+            let folder = request_folder.read().trim().trim_matches('/').to_string();
+            let saved = request.read().clone();
+            let name = if name.is_empty() {
+                format!("{} {}", saved.method, saved.url)
+            } else {
+                name
+            };
+            // Saving under an existing name and folder replaces that request.
+            match collection
+                .requests
+                .iter_mut()
+                .find(|r| r.name == name && r.folder == folder)
+            {
+                Some(existing) => existing.request = saved,
+                None => collection.requests.push(CollectionRequest {
+                    name,
+                    folder,
+                    request: saved,
+                }),
+            }
+            save(collection);
+        }
+    };
+
+    let load_data = move |_| {
+        let path = data_path.read().trim().to_string();
+        if path.is_empty() {
+            data.set(Vec::new());
+            return;
+        }
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Vec<DataRow>>("load_data_file", &LoadDataFileArgs { path }).await {
+                Ok(rows) => {
+                    data.set(rows);
+                    error.set(None);
+                }
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let mut start_run = move |rows: Vec<DataRow>| {
+        let Some(collection_id) = selected() else {
+            return;
+        };
+        let (Ok(delay_ms), Ok(count)) = (delay().trim().parse(), iterations().trim().parse())
+        else {
+            error.set(Some("Delay and iterations must be numbers".to_string()));
+            return;
+        };
+        let id = next_request_id();
+        let args = RunCollectionArgs {
+            id,
+            options: RunOptions {
+                collection_id,
+                folder: run_folder(),
+                request: run_request(),
+                delay_ms,
+                stop_on_failure: stop_on_failure(),
+                iterations: count,
+                data: rows.clone(),
+                update_snapshots: update_snapshots(),
+            },
+        };
+        running.set(Some(id));
+        run_collection.set(Some(collection_id));
+        run_data.set(rows);
+        results.set(Vec::new());
+        finished.set(None);
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Run>("run_collection", &args).await {
+                Ok(run) => {
+                    results.set(run.results.clone());
+                    finished.set(Some(run));
+                    load();
+                }
+                Err(err) => error.set(Some(err)),
+            }
+            running.set(None);
+        });
+    };
+
+    let cancel = move |_| {
+        if let Some(id) = running() {
+            cancel_request(id);
+        }
+    };
+
+    let selected_value = selected().map(|id| id.to_string()).unwrap_or_default();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary { "Collections" }
+                div { class: "environment-row",
+                    select {
+                        value: "{selected_value}",
+                        onchange: move |e| {
+                            selected.set(e.value().parse().ok());
+                            run_folder.set(String::new());
+                            run_request.set(String::new());
+                        },
+                        option { value: "", "Select a collection" }
+                        for collection in collections.read().iter() {
+                            option { value: "{collection.id.unwrap_or_default()}", "{collection.name}" }
+                        }
+                    }
+                    if current.is_some() {
+                        button { onclick: delete, "Delete" }
+                    }
+                    input {
+                        placeholder: "New collection",
+                        value: "{new_name}",
+                        oninput: move |e| new_name.set(e.value()),
+                    }
+                    button { onclick: add, "Add" }
+                }
+
+                if let Some(collection) = current.clone() {
+                    div { class: "environment-row",
+                        input {
+                            placeholder: "Request name",
+                            value: "{request_name}",
+                            oninput: move |e| request_name.set(e.value()),
+                        }
+                        input {
+                            placeholder: "Folder, e.g. auth/tokens",
+                            value: "{request_folder}",
+                            oninput: move |e| request_folder.set(e.value()),
+                        }
+                        button { onclick: save_request, "Save current request" }
+                    }
+
+                    ul { class: "collection-requests",
+                        for (idx , item) in collection.requests.iter().enumerate() {
+                            li { key: "{idx}",
+                                if !item.folder.is_empty() {
+                                    span { class: "collection-folder", "{item.folder}/" }
+                                }
+                                span { "{item.name} " }
+                                span { class: "response-time", "{item.request.method} {item.request.url}" }
+                                button {
+                                    onclick: {
+                                        let saved = item.request.clone();
+                                        move |_| request.set(saved.clone())
+                                    },
+                                    "Open"
+                                }
+                                button {
+                                    disabled: idx == 0,
+                                    onclick: {
+                                        let mut collection = collection.clone();
+                                        move |_| {
+                                            collection.requests.swap(idx - 1, idx);
+                                            save(collection.clone());
+                                        }
+                                    },
+                                    "↑"
+                                }
+                                button {
+                                    onclick: {
+                                        let mut collection = collection.clone();
+                                        move |_| {
+                                            collection.requests.remove(idx);
+                                            save(collection.clone());
+                                        }
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+
+                    h4 { "Run" }
+                    div { class: "environment-row",
+                        select {
+                            value: "{run_folder}",
+                            onchange: move |e| run_folder.set(e.value()),
+                            option { value: "", "Whole collection" }
+                            for folder in folders(&collection) {
+                                option { value: "{folder}", "{folder}" }
+                            }
+                        }
+                        select {
+                            value: "{run_request}",
+                            onchange: move |e| run_request.set(e.value()),
+                            option { value: "", "All requests" }
+                            for item in collection.requests.iter() {
+                                option { value: "{item.name}", "{item.name}" }
+                            }
+                        }
+                        label {
+                            "Delay (ms) "
+                            input {
+                                class: "run-number",
+                                value: "{delay}",
+                                oninput: move |e| delay.set(e.value()),
+                            }
+                        }
+                        label {
+                            "Iterations "
+                            input {
+                                class: "run-number",
+                                disabled: !data.read().is_empty(),
+                                value: "{iterations}",
+                                oninput: move |e| iterations.set(e.value()),
+                            }
+                        }
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: stop_on_failure(),
+                                onchange: move |e| stop_on_failure.set(e.checked()),
+                            }
+                            " Stop on failure"
+                        }
+                        label { title: "Save the responses that differ from their snapshots, or have none, as the new ones",
+                            input {
+                                r#type: "checkbox",
+                                checked: update_snapshots(),
+                                onchange: move |e| update_snapshots.set(e.checked()),
+                            }
+                            " Update snapshots"
+                        }
+                        if running().is_some() {
+                            button { onclick: cancel, "Cancel" }
+                        } else {
+                            button {
+                                disabled: collection.requests.is_empty(),
+                                onclick: move |_| start_run(data()),
+                                "Run"
+                            }
+                        }
+                    }
+                    div { class: "environment-row",
+                        input {
+                            placeholder: "Data file, CSV or JSON",
+                            value: "{data_path}",
+                            oninput: move |e| data_path.set(e.value()),
+                        }
+                        button { onclick: load_data, "Load" }
+                        if !data.read().is_empty() {
+                            span { class: "settings-hint", {data_hint(&data.read())} }
+                            button { onclick: move |_| data.set(Vec::new()), "Clear" }
+                        }
+                    }
+
+                    if !snapshots.read().is_empty() {
+                        details { class: "snapshots",
+                            summary { "Snapshots ({snapshots.read().len()})" }
+                            p { class: "settings-hint",
+                                "Runs compare responses against the approved ones. Paths listed next to a snapshot may differ, e.g. $.id, $.items[*].created_at, date."
+                            }
+                            ul { class: "collection-requests",
+                                for snapshot in snapshots.read().clone() {
+                                    li { key: "{snapshot.id}",
+                                        if !snapshot.folder.is_empty() {
+                                            span { class: "collection-folder", "{snapshot.folder}/" }
+                                        }
+                                        span { "{snapshot.name} " }
+                                        span { class: "response-time",
+                                            "{snapshot.response.status}, approved {snapshot.approved_at} "
+                                        }
+                                        input {
+                                            placeholder: "Ignored paths",
+                                            value: "{snapshot.ignore.join(\", \")}",
+                                            onchange: {
+                                                let snapshot = snapshot.clone();
+                                                move |e: Event<FormData>| {
+                                                    let mut snapshot = snapshot.clone();
+                                                    snapshot.ignore = ignored(&e.value());
+                                                    save_snapshot(snapshot);
+                                                }
+                                            },
+                                        }
+                                        button { onclick: move |_| delete_snapshot(snapshot.id), "✕" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(err) = error() {
+                    p { class: "status-error", "{err}" }
+                }
+
+                if running().is_some() {
+                    p { class: "settings-hint", "Running... {results.read().len()}/{total}" }
+                }
+                if let Some(run) = finished() {
+                    p { class: if run.summary.failed_requests == 0 { "test-passed" } else { "test-failed" },
+                        {summary_text(&run.summary)}
+                    }
+                    if running().is_none() && !run.failed_rows().is_empty() {
+                        button {
+                            onclick: {
+                                let rows = run.failed_rows();
+                                move |_| start_run(rows.clone())
+                            },
+                            "Re-run failed rows"
+                        }
+                    }
+                }
+                {run_results(results.read().clone(), &run_data.read(), Some(approve))}
+
+                if !runs.read().is_empty() {
+                    details { class: "ws-history",
+                        summary { "Run history" }
+                        for run in runs.read().clone() {
+                            details { key: "{run.id}",
+                                summary {
+                                    "{run.collection} "
+                                    if !run.folder.is_empty() {
+                                        "/{run.folder} "
+                                    }
+                                    span { class: "response-time",
+                                        "({run.started_at}) "
+                                        {summary_text(&run.summary)}
+                                    }
+                                }
+                                {run_results(run.results.clone(), &run.data, None)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
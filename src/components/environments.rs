@@ -0,0 +1,123 @@
+use crate::api::call;
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{Environment, Environments, KeyValue, ResponseState, SaveEnvironmentsArgs};
+use dioxus::prelude::*;
+
+fn active_variables(settings: &mut Environments) -> &mut Vec<KeyValue> {
+    let active = settings.active.clone().unwrap_or_default();
+    let idx = match settings.environments.iter().position(|e| e.name == active) {
+        Some(idx) => idx,
+        None => {
+            settings.environments.push(Environment {
+                name: active,
+                variables: Vec::new(),
+            });
+            settings.environments.len() - 1
+        }
+    };
+    &mut settings.environments[idx].variables
+}
+
+fn save(settings: Environments, mut status: Signal<String>) {
+    let args = SaveEnvironmentsArgs {
+        environments: settings,
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        match call::<_, ()>("save_environments", &args).await {
+            Ok(()) => status.set("Saved".to_string()),
+            Err(err) => status.set(err),
+        }
+    });
+}
+
+/// Environments hold the variables `{{name}}` references are filled in from. Scripts write
+/// to the active one, so it's reloaded after every response.
+#[component]
+pub fn EnvironmentsPanel(response: Signal<Option<ResponseState>>) -> Element {
+    let mut settings = use_signal(Environments::default);
+    let mut new_name = use_signal(String::new);
+    let status = use_signal(String::new);
+
+    use_effect(move || {
+        response.read();
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Environments>("get_environments", &()).await {
+                Ok(saved) => settings.set(saved),
+                Err(err) => web_sys::console::error_1(&err.into()),
+            }
+        });
+    });
+
+    let select = move |e: Event<FormData>| {
+        let name = e.value();
+        settings.with_mut(|s| s.active = (!name.is_empty()).then_some(name));
+        save(settings.read().clone(), status);
+    };
+
+    let add = move |_| {
+        let name = new_name.read().trim().to_string();
+        if name.is_empty() || settings.read().environments.iter().any(|e| e.name == name) {
+            return;
+        }
+        settings.with_mut(|s| {
+            s.environments.push(Environment {
+                name: name.clone(),
+                variables: Vec::new(),
+            });
+            s.active = Some(name);
+        });
+        new_name.set(String::new());
+        save(settings.read().clone(), status);
+    };
+
+    let delete = move |_| {
+        settings.with_mut(|s| {
+            if let Some(active) = s.active.take() {
+                s.environments.retain(|e| e.name != active);
+            }
+        });
+        save(settings.read().clone(), status);
+    };
+
+    let active = settings.read().active.clone().unwrap_or_default();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary {
+                    "Environments"
+                    if !active.is_empty() {
+                        " ({active})"
+                    }
+                }
+                div { class: "environment-row",
+                    select { value: "{active}", onchange: select,
+                        option { value: "", "No environment" }
+                        for env in settings.read().environments.iter() {
+                            option { value: "{env.name}", "{env.name}" }
+                        }
+                    }
+                    if !active.is_empty() {
+                        button { onclick: delete, "Delete" }
+                    }
+                    input {
+                        placeholder: "New environment",
+                        value: "{new_name}",
+                        oninput: move |e| new_name.set(e.value()),
+                    }
+                    button { onclick: add, "Add" }
+                }
+                if !active.is_empty() {
+                    p { class: "settings-hint",
+                        "Reference variables as {{{{name}}}} in the URL, query parameters, headers and body."
+                    }
+                    {key_value_editor(settings, active_variables)}
+                    div { class: "settings-actions",
+                        button { onclick: move |_| save(settings.read().clone(), status), "Save" }
+                    }
+                }
+                span { class: "settings-status", "{status}" }
+            }
+        }
+    }
+}
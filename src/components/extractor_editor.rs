@@ -0,0 +1,114 @@
+use crate::models::{Extraction, Extractor, ExtractorSource, RequestState};
+use dioxus::prelude::*;
+
+const SOURCES: [ExtractorSource; 4] = [
+    ExtractorSource::JsonPath,
+    ExtractorSource::Regex,
+    ExtractorSource::Header,
+    ExtractorSource::Cookie,
+];
+
+/// The select value, label and expression placeholder of a source.
+fn source_name(source: ExtractorSource) -> (&'static str, &'static str, &'static str) {
+    match source {
+        ExtractorSource::JsonPath => ("json_path", "JSONPath", "$.token"),
+        ExtractorSource::Regex => ("regex", "Regex", "Regex, the first group is extracted"),
+        ExtractorSource::Header => ("header", "Header", "Header name"),
+        ExtractorSource::Cookie => ("cookie", "Cookie", "Cookie name"),
+    }
+}
+
+fn source_for_value(value: &str) -> ExtractorSource {
+    SOURCES
+        .into_iter()
+        .find(|source| source_name(*source).0 == value)
+        .unwrap_or_default()
+}
+
+pub fn extractor_editor(mut request: Signal<RequestState>) -> Element {
+    let extractors = request.read().extractors.clone();
+
+    rsx! {
+        div { class: "kv-editor",
+            p { class: "settings-hint",
+                "Values extracted from successful responses are saved to the active environment."
+            }
+            for (idx , extractor) in extractors.into_iter().enumerate() {
+                {
+                    let (value, _, placeholder) = source_name(extractor.source);
+                    rsx! {
+                        div { class: "extractor-row",
+                            input {
+                                r#type: "checkbox",
+                                title: "Enabled",
+                                checked: extractor.enabled,
+                                onchange: move |e| request.with_mut(|r| r.extractors[idx].enabled = e.checked()),
+                            }
+                            select {
+                                value: "{value}",
+                                onchange: move |e| {
+                                    request
+                                        .with_mut(|r| {
+                                            r.extractors[idx].source = source_for_value(&e.value());
+                                        })
+                                },
+                                for (value , label , _) in SOURCES.map(source_name) {
+                                    option { value: "{value}", "{label}" }
+                                }
+                            }
+                            input {
+                                placeholder: "{placeholder}",
+                                value: "{extractor.expression}",
+                                oninput: move |e| request.with_mut(|r| r.extractors[idx].expression = e.value()),
+                            }
+                            span { "→" }
+                            input {
+                                class: "extractor-variable",
+                                placeholder: "Variable",
+                                value: "{extractor.variable}",
+                                oninput: move |e| request.with_mut(|r| r.extractors[idx].variable = e.value()),
+                            }
+                            button {
+                                onclick: move |_| {
+                                    request
+                                        .with_mut(|r| {
+                                            r.extractors.remove(idx);
+                                        });
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+
+            button {
+                onclick: move |_| request.with_mut(|r| r.extractors.push(Extractor::default())),
+                "+ Add"
+            }
+        }
+    }
+}
+
+pub fn extracted_variables(extracted: Vec<Extraction>) -> Element {
+    rsx! {
+        if !extracted.is_empty() {
+            h4 { "Extracted variables" }
+            table { class: "extracted-variables",
+                for (idx , extraction) in extracted.into_iter().enumerate() {
+                    tr { key: "{idx}",
+                        td { "{extraction.variable}" }
+                        match extraction.value {
+                            Some(value) => rsx! {
+                                td { class: "test-passed", "{value}" }
+                            },
+                            None => rsx! {
+                                td { class: "test-failed", "{extraction.message}" }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::api::{call, call_with_args};
+use crate::models::{
+    GraphQlField, GraphQlOperationsArgs, GraphQlSchema, GraphQlType, IntrospectArgs, RequestBody,
+    RequestState, ValidateGraphQlArgs,
+};
+use dioxus::prelude::*;
+
+fn format_field(field: &GraphQlField) -> String {
+    if field.args.is_empty() {
+        format!("{}: {}", field.name, field.type_name)
+    } else {
+        let args: Vec<String> = field
+            .args
+            .iter()
+            .map(|a| format!("{}: {}", a.name, a.type_name))
+            .collect();
+        format!("{}({}): {}", field.name, args.join(", "), field.type_name)
+    }
+}
+
+/// Root operation types first, then the remaining named types, without introspection types.
+fn browsable_types(schema: &GraphQlSchema, filter: &str) -> Vec<GraphQlType> {
+    let roots = [
+        &schema.query_type,
+        &schema.mutation_type,
+        &schema.subscription_type,
+    ];
+    let is_root = |t: &GraphQlType| roots.iter().any(|r| r.as_deref() == Some(t.name.as_str()));
+    let filter = filter.trim().to_lowercase();
+
+    let mut types: Vec<GraphQlType> = schema
+        .types
+        .iter()
+        .filter(|t| !t.name.starts_with("__"))
+        .filter(|t| {
+            filter.is_empty()
+                || t.name.to_lowercase().contains(&filter)
+                || t.fields
+                    .iter()
+                    .any(|f| f.name.to_lowercase().contains(&filter))
+        })
+        .cloned()
+        .collect();
+    types.sort_by_key(|t| (!is_root(t), t.name.clone()));
+    types
+}
+
+fn update_graphql(
+    mut request: Signal<RequestState>,
+    update: impl FnOnce(&mut String, &mut String, &mut String),
+) {
+    request.with_mut(|r| {
+        if let RequestBody::GraphQl {
+            query,
+            variables,
+            operation_name,
+        } = &mut r.body
+        {
+            update(query, variables, operation_name);
+        }
+    });
+}
+
+#[component]
+pub fn GraphQlEditor(request: Signal<RequestState>) -> Element {
+    let mut schema = use_signal(|| None::<GraphQlSchema>);
+    let mut operations = use_signal(Vec::<String>::new);
+    let mut errors = use_signal(Vec::<String>::new);
+    let mut status = use_signal(String::new);
+    let mut filter = use_signal(String::new);
+
+    let mut introspect = move |refresh: bool| {
+        let args = {
+            let r = request.read();
+            IntrospectArgs {
+                url: r.url.clone(),
+                headers: r.headers.clone(),
+                proxy: r.proxy.clone(),
+                refresh,
+            }
+        };
+        if args.url.trim().is_empty() {
+            return;
+        }
+        status.set("Fetching schema...".to_string());
+        wasm_bindgen_futures::spawn_local(async move {
+            match call_with_args::<_, GraphQlSchema>("graphql_introspect", &args).await {
+                Ok(fetched) => {
+                    status.set(format!("Schema loaded ({} types)", fetched.types.len()));
+                    schema.set(Some(fetched));
+                }
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    // Uses the cached schema when one exists, so reopening the editor doesn't refetch it.
+    use_hook(move || introspect(false));
+
+    use_effect(move || {
+        let _ = schema.read();
+        let (url, query) = match &request.read().body {
+            RequestBody::GraphQl { query, .. } => (request.read().url.clone(), query.clone()),
+            _ => return,
+        };
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let args = GraphQlOperationsArgs {
+                query: query.clone(),
+            };
+            if let Ok(names) = call::<_, Vec<String>>("graphql_operations", &args).await {
+                operations.set(names);
+            }
+            let args = ValidateGraphQlArgs { url, query };
+            match call_with_args::<_, Vec<String>>("validate_graphql", &args).await {
+                Ok(problems) => errors.set(problems),
+                Err(err) => errors.set(vec![err]),
+            }
+        });
+    });
+
+    let RequestBody::GraphQl {
+        query,
+        variables,
+        operation_name,
+    } = request.read().body.clone()
+    else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class: "graphql-editor",
+            div { class: "graphql-toolbar",
+                select {
+                    value: "{operation_name}",
+                    onchange: move |e| update_graphql(request, |_, _, op| *op = e.value()),
+                    option { value: "", "Default operation" }
+                    for name in operations.read().iter() {
+                        option { value: "{name}", "{name}" }
+                    }
+                }
+                button { onclick: move |_| introspect(true), "Refresh schema" }
+                span { class: "settings-status", "{status}" }
+            }
+
+            div { class: "graphql-panes",
+                textarea {
+                    class: "graphql-query",
+                    placeholder: "query {{ ... }}",
+                    value: "{query}",
+                    oninput: move |e| update_graphql(request, |q, _, _| *q = e.value()),
+                }
+                textarea {
+                    class: "graphql-variables",
+                    placeholder: "Variables (JSON)",
+                    value: "{variables}",
+                    oninput: move |e| update_graphql(request, |_, v, _| *v = e.value()),
+                }
+            }
+
+            if !errors.read().is_empty() {
+                ul { class: "graphql-errors",
+                    for error in errors.read().iter() {
+                        li { class: "status-error", "{error}" }
+                    }
+                }
+            }
+
+            if let Some(schema) = schema.read().as_ref() {
+                details { class: "graphql-schema",
+                    summary { "Schema" }
+                    input {
+                        placeholder: "Filter types and fields",
+                        value: "{filter}",
+                        oninput: move |e| filter.set(e.value()),
+                    }
+                    for schema_type in browsable_types(schema, &filter.read()) {
+                        details { class: "graphql-type", key: "{schema_type.name}",
+                            summary {
+                                strong { "{schema_type.name}" }
+                                span { class: "graphql-kind", " {schema_type.kind.to_lowercase()}" }
+                            }
+                            if let Some(description) = schema_type.description.as_ref() {
+                                p { class: "graphql-description", "{description}" }
+                            }
+                            ul {
+                                for field in schema_type.fields.iter() {
+                                    li {
+                                        code { "{format_field(field)}" }
+                                        if let Some(description) = field.description.as_ref() {
+                                            span { class: "graphql-description", " — {description}" }
+                                        }
+                                    }
+                                }
+                                for value in schema_type.enum_values.iter() {
+                                    li {
+                                        code { "{value}" }
+                                    }
+                                }
+                                for possible in schema_type.possible_types.iter() {
+                                    li {
+                                        code { "… on {possible}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::api::{call, call_with_args, cancel_request, listen, next_request_id};
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{
+    GrpcCallArgs, GrpcCallRequest, GrpcMessageEvent, GrpcMethod, GrpcResponse, GrpcService,
+    GrpcServicesArgs, GrpcSource, KeyValue,
+};
+use dioxus::prelude::*;
+
+fn lines(text: &str) -> Vec<String> {
+    text.split('\n').map(str::to_string).collect()
+}
+
+fn call_kind(method: &GrpcMethod) -> &'static str {
+    match (method.client_streaming, method.server_streaming) {
+        (false, false) => "unary",
+        (true, false) => "client streaming",
+        (false, true) => "server streaming",
+        (true, true) => "bidirectional streaming",
+    }
+}
+
+fn metadata_list(title: &str, entries: &[KeyValue]) -> Element {
+    rsx! {
+        if !entries.is_empty() {
+            details { class: "grpc-metadata",
+                summary { "{title} ({entries.len()})" }
+                for kv in entries.iter() {
+                    p {
+                        strong { "{kv.key}: " }
+                        "{kv.value}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn GrpcPanel() -> Element {
+    let mut grpc = use_signal(|| GrpcCallArgs {
+        url: "http://localhost:50051".to_string(),
+        ..Default::default()
+    });
+    let mut services = use_signal(Vec::<GrpcService>::new);
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut in_flight = use_signal(|| None::<u64>);
+    let mut live_messages = use_signal(Vec::<String>::new);
+    let mut response = use_signal(|| None::<GrpcResponse>);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            let subscribed = listen("grpc-message", move |event: GrpcMessageEvent| {
+                if in_flight() == Some(event.call_id) {
+                    live_messages.with_mut(|m| m.push(event.message));
+                }
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let load_services = move |_| {
+        let args = {
+            let grpc = grpc.read();
+            GrpcServicesArgs {
+                url: grpc.url.clone(),
+                source: grpc.source.clone(),
+                metadata: grpc.metadata.clone(),
+            }
+        };
+        loading.set(true);
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            match call_with_args::<_, Vec<GrpcService>>("grpc_services", &args).await {
+                Ok(loaded) => services.set(loaded),
+                Err(err) => error.set(Some(err)),
+            }
+            loading.set(false);
+        });
+    };
+
+    let mut select_method = move |value: String| {
+        let Some((service, method)) = value.rsplit_once('/') else {
+            return;
+        };
+        let template = services
+            .read()
+            .iter()
+            .filter(|s| s.name == service)
+            .flat_map(|s| s.methods.iter())
+            .find(|m| m.name == method)
+            .map(|m| m.template.clone())
+            .unwrap_or_default();
+        grpc.with_mut(|g| {
+            g.service = service.to_string();
+            g.method = method.to_string();
+            g.message = template;
+        });
+    };
+
+    let invoke = move |_| {
+        // A new call supersedes the one still in flight.
+        if let Some(previous) = in_flight() {
+            cancel_request(previous);
+        }
+        let id = next_request_id();
+        let args = GrpcCallRequest {
+            id,
+            args: grpc.read().clone(),
+        };
+        in_flight.set(Some(id));
+        live_messages.set(Vec::new());
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = call::<_, GrpcResponse>("grpc_call", &args).await;
+            if in_flight() != Some(id) {
+                return;
+            }
+            in_flight.set(None);
+            match result {
+                Ok(resp) => response.set(Some(resp)),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let cancel = move |_| {
+        if let Some(id) = in_flight() {
+            cancel_request(id);
+        }
+    };
+
+    let (source_kind, paths, include_paths) = match &grpc.read().source {
+        GrpcSource::Reflection => ("reflection", String::new(), String::new()),
+        GrpcSource::ProtoFiles {
+            paths,
+            include_paths,
+        } => ("proto_files", paths.join("\n"), include_paths.join("\n")),
+    };
+    let selected = format!("{}/{}", grpc.read().service, grpc.read().method);
+    let selected_method = services
+        .read()
+        .iter()
+        .filter(|s| s.name == grpc.read().service)
+        .flat_map(|s| s.methods.iter())
+        .find(|m| m.name == grpc.read().method)
+        .cloned();
+
+    rsx! {
+        section { class: "grpc-panel",
+            h3 { "gRPC" }
+
+            div { class: "request-line",
+                input {
+                    class: "url-input",
+                    placeholder: "http://localhost:50051",
+                    value: "{grpc.read().url}",
+                    oninput: move |e| grpc.with_mut(|g| g.url = e.value()),
+                }
+                button { disabled: loading(), onclick: load_services,
+                    if loading() {
+                        "Loading..."
+                    } else {
+                        "Load services"
+                    }
+                }
+            }
+
+            select {
+                value: "{source_kind}",
+                onchange: move |e| {
+                    grpc.with_mut(|g| {
+                        g.source = match e.value().as_str() {
+                            "proto_files" => GrpcSource::ProtoFiles {
+                                paths: Vec::new(),
+                                include_paths: Vec::new(),
+                            },
+                            _ => GrpcSource::Reflection,
+                        };
+                    })
+                },
+                option { value: "reflection", "Server reflection" }
+                option { value: "proto_files", ".proto files" }
+            }
+            if source_kind == "proto_files" {
+                div { class: "grpc-proto-files",
+                    textarea {
+                        placeholder: "Paths of .proto files, one per line",
+                        value: "{paths}",
+                        oninput: move |e| {
+                            grpc.with_mut(|g| {
+                                if let GrpcSource::ProtoFiles { paths, .. } = &mut g.source {
+                                    *paths = lines(&e.value());
+                                }
+                            })
+                        },
+                    }
+                    textarea {
+                        placeholder: "Import paths, one per line (each file's directory is included)",
+                        value: "{include_paths}",
+                        oninput: move |e| {
+                            grpc.with_mut(|g| {
+                                if let GrpcSource::ProtoFiles { include_paths, .. } = &mut g.source {
+                                    *include_paths = lines(&e.value());
+                                }
+                            })
+                        },
+                    }
+                }
+            }
+
+            h4 { "Metadata" }
+            {key_value_editor(grpc, |g| &mut g.metadata)}
+
+            if !services.read().is_empty() {
+                select {
+                    class: "grpc-method",
+                    value: "{selected}",
+                    onchange: move |e| select_method(e.value()),
+                    option { value: "/", "Select a method..." }
+                    for service in services.read().iter() {
+                        optgroup { label: "{service.name}",
+                            for method in service.methods.iter() {
+                                option { value: "{service.name}/{method.name}",
+                                    "{method.name}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(method) = selected_method {
+                p { class: "grpc-signature",
+                    "{method.input_type} → {method.output_type} ({call_kind(&method)})"
+                }
+                textarea {
+                    class: "grpc-message",
+                    placeholder: if method.client_streaming { "JSON array of request messages" } else { "Request message (JSON)" },
+                    value: "{grpc.read().message}",
+                    oninput: move |e| grpc.with_mut(|g| g.message = e.value()),
+                }
+                div { class: "settings-actions",
+                    button { onclick: invoke, "Invoke" }
+                    if in_flight().is_some() {
+                        button { onclick: cancel, "Cancel" }
+                    }
+                }
+            }
+
+            if let Some(err) = error() {
+                p { class: "status-error", "{err}" }
+            }
+
+            if in_flight().is_some() {
+                p { class: "in-flight",
+                    span { class: "spinner" }
+                    "Waiting for response... ({live_messages.read().len()} messages)"
+                }
+                for (idx , message) in live_messages.read().iter().enumerate() {
+                    pre { key: "{idx}", class: "grpc-response-message", "{message}" }
+                }
+            }
+
+            if let Some(resp) = response() {
+                div { class: "grpc-response",
+                    p { class: if resp.status_code == 0 { "status-ok" } else { "status-error" },
+                        strong { "Status: " }
+                        "{resp.status} ({resp.status_code})"
+                        if !resp.status_message.is_empty() {
+                            " {resp.status_message}"
+                        }
+                        span { class: "response-time", {format!(" ({:.2} ms)", resp.response_time)} }
+                    }
+                    for detail in resp.details.iter() {
+                        pre { class: "status-error", "{detail}" }
+                    }
+                    for (idx , message) in resp.messages.iter().enumerate() {
+                        pre { key: "{idx}", class: "grpc-response-message", "{message}" }
+                    }
+                    {metadata_list("Response metadata", &resp.metadata)}
+                    {metadata_list("Trailers", &resp.trailers)}
+                }
+            }
+        }
+    }
+}
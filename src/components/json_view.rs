@@ -0,0 +1,334 @@
+use std::collections::HashSet;
+
+use crate::api::call;
+use crate::highlight::highlight_to_html;
+use crate::models::{FilterJsonArgs, ResponseState};
+use dioxus::prelude::*;
+use serde_json::Value;
+
+/// Nodes above this depth are expanded until toggled.
+const OPEN_DEPTH: usize = 1;
+
+/// Children of a node shown in the tree, the rest are left to the filter.
+const MAX_CHILDREN: usize = 500;
+
+#[derive(Clone, Copy, PartialEq)]
+enum BodyView {
+    Raw,
+    Pretty,
+    Tree,
+}
+
+/// JSONPath of `key` in the object at `parent`.
+fn key_path(parent: &str, key: &str) -> String {
+    let plain = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("{parent}.{key}")
+    } else {
+        let quoted = key.replace('\\', "\\\\").replace('\'', "\\'");
+        format!("{parent}['{quoted}']")
+    }
+}
+
+fn count_label(value: &Value) -> Option<String> {
+    let (count, unit) = match value {
+        Value::Object(map) => (map.len(), "key"),
+        Value::Array(items) => (items.len(), "item"),
+        _ => return None,
+    };
+    Some(match count {
+        1 => format!("1 {unit}"),
+        count => format!("{count} {unit}s"),
+    })
+}
+
+fn preview(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{…}".to_string(),
+        Value::Array(_) => "[…]".to_string(),
+        scalar => scalar.to_string(),
+    }
+}
+
+fn value_class(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "json-string",
+        Value::Number(_) => "json-number",
+        Value::Bool(_) | Value::Null => "json-literal",
+        _ => "json-preview",
+    }
+}
+
+/// JSON Pointer of `key` in the object or array at `parent`.
+fn pointer(parent: &str, key: &str) -> String {
+    format!("{parent}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn copy(text: String) {
+    // A JSON string is a valid JavaScript string literal.
+    let _ = document::eval(&format!(
+        "navigator.clipboard.writeText({})",
+        Value::String(text)
+    ));
+}
+
+/// The value shown in the tree and the paths of the nodes whose expansion was toggled.
+/// `whole` is unset while the tree shows a filter's result, whose node paths aren't the
+/// paths in the body.
+#[derive(Clone, Copy)]
+struct Tree {
+    root: Memo<Option<Value>>,
+    toggled: Signal<HashSet<String>>,
+    whole: bool,
+}
+
+/// A node and, when it's expanded, its children. `path` is the node's JSONPath, `at` its
+/// JSON Pointer for looking its value up when it's copied.
+fn tree_node(
+    label: Option<String>,
+    value: &Value,
+    path: String,
+    at: String,
+    depth: usize,
+    tree: Tree,
+) -> Element {
+    let Tree {
+        root,
+        mut toggled,
+        whole,
+    } = tree;
+    let container = value.is_object() || value.is_array();
+    let open = container && ((depth < OPEN_DEPTH) != toggled.read().contains(&path));
+    let children: Vec<(String, String, String, &Value)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (key.clone(), key_path(&path, key), pointer(&at, key), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(idx, child)| {
+                let key = idx.to_string();
+                (
+                    key.clone(),
+                    format!("{path}[{idx}]"),
+                    pointer(&at, &key),
+                    child,
+                )
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let hidden = children.len().saturating_sub(MAX_CHILDREN);
+
+    rsx! {
+        div { class: "json-node",
+            div { class: "json-row",
+                span {
+                    class: "json-toggle",
+                    onclick: {
+                        let path = path.clone();
+                        move |_| {
+                            toggled.with_mut(|t| {
+                                if !t.remove(&path) {
+                                    t.insert(path.clone());
+                                }
+                            })
+                        }
+                    },
+                    if !container {
+                        ""
+                    } else if open {
+                        "▾"
+                    } else {
+                        "▸"
+                    }
+                }
+                if let Some(label) = label {
+                    span { class: "json-key", "{label}: " }
+                }
+                if !open {
+                    span { class: value_class(value), {preview(value)} }
+                }
+                if let Some(count) = count_label(value) {
+                    span { class: "json-count", " {count}" }
+                }
+                span { class: "json-actions",
+                    if whole {
+                        button {
+                            title: "Copy the JSONPath of this node",
+                            onclick: {
+                                let path = path.clone();
+                                move |_| copy(path.clone())
+                            },
+                            "Path"
+                        }
+                    }
+                    button {
+                        title: "Copy the value of this node",
+                        onclick: move |_| {
+                            let text = root
+                                .read()
+                                .as_ref()
+                                .and_then(|root| root.pointer(&at))
+                                .and_then(|value| serde_json::to_string_pretty(value).ok());
+                            if let Some(text) = text {
+                                copy(text);
+                            }
+                        },
+                        "Value"
+                    }
+                }
+            }
+            if open {
+                div { class: "json-children",
+                    for (key , child_path , child_at , child) in children.into_iter().take(MAX_CHILDREN) {
+                        Fragment { key: "{child_path}",
+                            {tree_node(Some(key), child, child_path.clone(), child_at, depth + 1, tree)}
+                        }
+                    }
+                    if hidden > 0 {
+                        p { class: "settings-hint", "{hidden} more, narrow them down with a filter" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A JSON response as raw text, pretty-printed or as a collapsible tree, narrowed down by a
+/// JSONPath or jq-style filter.
+#[component]
+pub fn JsonView(response: Signal<Option<ResponseState>>) -> Element {
+    let mut view = use_signal(|| BodyView::Tree);
+    let mut filter = use_signal(String::new);
+    let mut filtered = use_signal(|| None::<Result<Value, String>>);
+    let mut filter_id = use_signal(|| 0u64);
+    let toggled = use_signal(HashSet::<String>::new);
+    let mut pretty_html = use_signal(String::new);
+
+    use_effect(move || {
+        let body = response
+            .read()
+            .as_ref()
+            .map(|r| r.body.clone())
+            .unwrap_or_default();
+        let filter = filter.read().trim().to_string();
+        let id = *filter_id.peek() + 1;
+        filter_id.set(id);
+        if filter.is_empty() {
+            filtered.set(None);
+            return;
+        }
+        wasm_bindgen_futures::spawn_local(async move {
+            let args = FilterJsonArgs { body, filter };
+            let result = call::<_, Value>("filter_json", &args).await;
+            // Results of filters typed over since must not replace the newer one's.
+            if *filter_id.peek() == id {
+                filtered.set(Some(result));
+            }
+        });
+    });
+
+    // The filtered value, or the whole body when there's no filter.
+    let shown = use_memo(move || match filtered() {
+        Some(result) => result.ok(),
+        None => response
+            .read()
+            .as_ref()
+            .and_then(|r| serde_json::from_str::<Value>(&r.body).ok()),
+    });
+
+    use_effect(move || {
+        if view() != BodyView::Pretty {
+            return;
+        }
+        let Some(value) = shown() else {
+            pretty_html.set(String::new());
+            return;
+        };
+        let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+        wasm_bindgen_futures::spawn_local(async move {
+            match highlight_to_html(&text, "json").await {
+                Ok(html) => pretty_html.set(html),
+                Err(err) => {
+                    web_sys::console::error_1(&err.into());
+                    pretty_html.set(text);
+                }
+            }
+        });
+    });
+
+    let raw = match (filtered.read().is_some(), shown()) {
+        (true, Some(value)) => value.to_string(),
+        _ => response
+            .read()
+            .as_ref()
+            .map(|r| r.body.clone())
+            .unwrap_or_default(),
+    };
+    let tab = move |this: BodyView| if view() == this { "tab active" } else { "tab" };
+
+    rsx! {
+        div { class: "json-view",
+            div { class: "environment-row",
+                button { class: tab(BodyView::Tree), onclick: move |_| view.set(BodyView::Tree), "Tree" }
+                button { class: tab(BodyView::Pretty), onclick: move |_| view.set(BodyView::Pretty), "Pretty" }
+                button { class: tab(BodyView::Raw), onclick: move |_| view.set(BodyView::Raw), "Raw" }
+                input {
+                    class: "json-filter",
+                    placeholder: "Filter, e.g. $.items[*].id or .items[].id",
+                    value: "{filter}",
+                    oninput: move |e| filter.set(e.value()),
+                }
+            }
+            if let Some(Err(err)) = filtered() {
+                p { class: "status-error", "{err}" }
+            }
+            match view() {
+                BodyView::Raw => rsx! {
+                    pre { class: "json-raw", "{raw}" }
+                },
+                BodyView::Pretty => rsx! {
+                    div { dangerous_inner_html: "{pretty_html}" }
+                },
+                BodyView::Tree => match shown() {
+                    Some(value) => tree_node(
+                        None,
+                        &value,
+                        "$".to_string(),
+                        String::new(),
+                        0,
+                        Tree {
+                            root: shown,
+                            toggled,
+                            whole: filtered.read().is_none(),
+                        },
+                    ),
+                    None => rsx! {
+                        pre { class: "json-raw", "{raw}" }
+                    },
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_node_paths() {
+        assert_eq!(key_path("$", "items"), "$.items");
+        assert_eq!(
+            key_path("$.items[0]", "first_name"),
+            "$.items[0].first_name"
+        );
+        assert_eq!(key_path("$", "content-type"), "$['content-type']");
+        assert_eq!(key_path("$", "2fa"), "$['2fa']");
+        assert_eq!(key_path("$", "it's"), r"$['it\'s']");
+        assert_eq!(pointer("/items/0", "a/b~c"), "/items/0/a~1b~0c");
+    }
+}
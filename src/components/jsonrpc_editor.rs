@@ -0,0 +1,110 @@
+use crate::components::body_editor::body_for_mode;
+use crate::models::{JsonRpcCall, JsonRpcOutcome, RequestBody, RequestState};
+use dioxus::prelude::*;
+
+fn json_rpc_calls(request: &mut RequestState) -> &mut Vec<JsonRpcCall> {
+    if !matches!(request.body, RequestBody::JsonRpc { .. }) {
+        request.body = body_for_mode("json_rpc");
+    }
+    match &mut request.body {
+        RequestBody::JsonRpc { calls, .. } => calls,
+        _ => unreachable!(),
+    }
+}
+
+pub fn jsonrpc_editor(mut request: Signal<RequestState>) -> Element {
+    let (calls, batch) = match &request.read().body {
+        RequestBody::JsonRpc { calls, batch } => (calls.clone(), *batch),
+        _ => (Vec::new(), false),
+    };
+
+    rsx! {
+        div { class: "jsonrpc-editor",
+            for (idx , call) in calls.iter().enumerate() {
+                div { class: "jsonrpc-call",
+                    div { class: "jsonrpc-call-header",
+                        input {
+                            placeholder: "Method",
+                            value: "{call.method}",
+                            oninput: move |e| request.with_mut(|r| json_rpc_calls(r)[idx].method = e.value()),
+                        }
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: call.notification,
+                                onchange: move |e| request.with_mut(|r| json_rpc_calls(r)[idx].notification = e.checked()),
+                            }
+                            "Notification"
+                        }
+                        button {
+                            onclick: move |_| {
+                                request
+                                    .with_mut(|r| {
+                                        json_rpc_calls(r).remove(idx);
+                                    });
+                            },
+                            "✕"
+                        }
+                    }
+                    textarea {
+                        placeholder: "Params (JSON array or object, optional)",
+                        value: "{call.params}",
+                        oninput: move |e| request.with_mut(|r| json_rpc_calls(r)[idx].params = e.value()),
+                    }
+                }
+            }
+
+            div { class: "settings-actions",
+                button {
+                    onclick: move |_| request.with_mut(|r| json_rpc_calls(r).push(JsonRpcCall::default())),
+                    "+ Add call"
+                }
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: batch,
+                        onchange: move |e| {
+                            request
+                                .with_mut(|r| {
+                                    if let RequestBody::JsonRpc { batch, .. } = &mut r.body {
+                                        *batch = e.checked();
+                                    }
+                                })
+                        },
+                    }
+                    "Send as batch"
+                }
+                if !batch && calls.len() > 1 {
+                    span { class: "jsonrpc-hint", "Only the first call is sent unless batching" }
+                }
+            }
+        }
+    }
+}
+
+pub fn jsonrpc_results(outcomes: Vec<JsonRpcOutcome>) -> Element {
+    rsx! {
+        ul { class: "jsonrpc-results",
+            for (idx , outcome) in outcomes.into_iter().enumerate() {
+                li { key: "{idx}",
+                    div { class: "jsonrpc-call-header",
+                        if let Some(id) = outcome.id {
+                            span { class: "jsonrpc-id", "#{id}" }
+                        }
+                        strong { "{outcome.method}" }
+                    }
+                    if let Some(error) = outcome.error {
+                        p { class: "status-error", "Error {error.code}: {error.message}" }
+                        if let Some(data) = error.data {
+                            pre { "{data}" }
+                        }
+                    } else if let Some(result) = outcome.result {
+                        pre { "{result}" }
+                    } else {
+                        p { class: "jsonrpc-hint", "No response" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use crate::api::{call, call_with_args};
+use crate::models::{
+    DecodedJwt, FindJwtsArgs, JwtSource, KeyValue, MintJwtArgs, RequestState, ResponseState,
+    SetVariableArgs, VerifyJwtArgs, VerifyJwtResult,
+};
+use dioxus::prelude::*;
+
+const DEFAULT_CLAIMS: &str = "{\n  \"sub\": \"test-user\"\n}";
+
+fn describe_duration(secs: i64) -> String {
+    let secs = secs.abs();
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
+    }
+}
+
+fn describe_expiry(jwt: &DecodedJwt) -> (String, &'static str) {
+    match (jwt.expires_in, jwt.expires_at.as_deref()) {
+        (Some(secs), Some(at)) if secs >= 0 => (
+            format!("Expires in {} ({at})", describe_duration(secs)),
+            "status-ok",
+        ),
+        (Some(secs), Some(at)) => (
+            format!("Expired {} ago ({at})", describe_duration(secs)),
+            "status-error",
+        ),
+        _ => ("No expiry".to_string(), "jwt-muted"),
+    }
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+#[component]
+pub fn JwtPanel(request: Signal<RequestState>, response: Signal<Option<ResponseState>>) -> Element {
+    let mut tokens = use_signal(Vec::<DecodedJwt>::new);
+    let mut verify_results = use_signal(HashMap::<String, VerifyJwtResult>::new);
+    let mut verify_key = use_signal(String::new);
+    let mut verify_key_path = use_signal(String::new);
+
+    let mut mint_algorithm = use_signal(|| "HS256".to_string());
+    let mut mint_key = use_signal(String::new);
+    let mut mint_key_path = use_signal(String::new);
+    let mut mint_claims = use_signal(|| DEFAULT_CLAIMS.to_string());
+    let mut mint_expires_in = use_signal(|| "3600".to_string());
+    let mut minted = use_signal(|| None::<Result<String, String>>);
+    let mut variable = use_signal(|| "token".to_string());
+    let mut stored = use_signal(String::new);
+
+    use_effect(move || {
+        let mut sources = Vec::new();
+        let req = request.read();
+        sources.push(JwtSource {
+            label: "Request URL".to_string(),
+            text: req.url.clone(),
+        });
+        for header in &req.headers {
+            sources.push(JwtSource {
+                label: format!("Request header {}", header.key),
+                text: header.value.clone(),
+            });
+        }
+        // Tokens in any body mode are found in its JSON form, the token alphabet needs no
+        // escaping.
+        sources.push(JwtSource {
+            label: "Request body".to_string(),
+            text: serde_json::to_string(&req.body).unwrap_or_default(),
+        });
+        if let Some(resp) = response.read().as_ref() {
+            for header in &resp.headers {
+                sources.push(JwtSource {
+                    label: format!("Response header {}", header.key),
+                    text: header.value.clone(),
+                });
+            }
+            sources.push(JwtSource {
+                label: "Response body".to_string(),
+                text: resp.body.clone(),
+            });
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Vec<DecodedJwt>>("find_jwts", &FindJwtsArgs { sources }).await {
+                Ok(found) => tokens.set(found),
+                Err(err) => web_sys::console::error_1(&err.into()),
+            }
+        });
+    });
+
+    let verify = move |token: String| {
+        let args = VerifyJwtArgs {
+            token: token.clone(),
+            key: verify_key(),
+            key_path: verify_key_path(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = call_with_args::<_, VerifyJwtResult>("verify_jwt", &args)
+                .await
+                .unwrap_or_else(|message| VerifyJwtResult {
+                    valid: false,
+                    message,
+                });
+            verify_results.with_mut(|results| results.insert(token, result));
+        });
+    };
+
+    let mint = move |_| {
+        let args = MintJwtArgs {
+            algorithm: mint_algorithm(),
+            key: mint_key(),
+            key_path: mint_key_path(),
+            claims: mint_claims(),
+            expires_in: mint_expires_in().trim().parse().ok(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            minted.set(Some(call_with_args::<_, String>("mint_jwt", &args).await));
+        });
+    };
+
+    let mut use_as_bearer = move |token: String| {
+        request.with_mut(|r| {
+            let value = format!("Bearer {token}");
+            match r
+                .headers
+                .iter_mut()
+                .find(|h| h.key.eq_ignore_ascii_case("authorization"))
+            {
+                Some(header) => header.value = value,
+                None => r.headers.push(KeyValue {
+                    key: "Authorization".to_string(),
+                    value,
+                }),
+            }
+        });
+    };
+
+    let mut store_as_variable = move |token: String| {
+        let name = variable.read().trim().to_string();
+        if name.is_empty() {
+            stored.set("Name the variable first".to_string());
+            return;
+        }
+        let args = SetVariableArgs {
+            name: name.clone(),
+            value: token,
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("set_variable", &args).await {
+                Ok(()) => stored.set(format!("Stored as {{{{{name}}}}}")),
+                Err(err) => stored.set(err),
+            }
+        });
+    };
+
+    rsx! {
+        section { class: "jwt-panel",
+            h3 { "JWT" }
+
+            if tokens.read().is_empty() {
+                p { class: "jwt-muted", "No tokens found in the request or response." }
+            }
+
+            for jwt in tokens.read().clone() {
+                div { class: "jwt-card", key: "{jwt.token}",
+                    {
+                        let (expiry, expiry_class) = describe_expiry(&jwt);
+                        rsx! {
+                            p {
+                                strong { "{jwt.source}" }
+                                span { class: "{expiry_class} jwt-expiry", "{expiry}" }
+                            }
+                        }
+                    }
+                    div { class: "jwt-parts",
+                        pre { "{pretty(&jwt.header)}" }
+                        pre { "{pretty(&jwt.payload)}" }
+                    }
+                    div { class: "jwt-actions",
+                        button {
+                            onclick: {
+                                let token = jwt.token.clone();
+                                move |_| verify(token.clone())
+                            },
+                            "Verify signature"
+                        }
+                        if let Some(result) = verify_results.read().get(&jwt.token) {
+                            span { class: if result.valid { "status-ok" } else { "status-error" },
+                                "{result.message}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !tokens.read().is_empty() {
+                div { class: "jwt-key",
+                    textarea {
+                        class: "jwt-key-input",
+                        placeholder: "Secret, PEM public key or JWKS used for verification...",
+                        value: "{verify_key}",
+                        oninput: move |e| verify_key.set(e.value()),
+                    }
+                    input {
+                        placeholder: "...or path to a key / JWKS file",
+                        value: "{verify_key_path}",
+                        oninput: move |e| verify_key_path.set(e.value()),
+                    }
+                }
+            }
+
+            h3 { "Mint test token" }
+            div { class: "jwt-mint",
+                div { class: "jwt-mint-options",
+                    select {
+                        value: "{mint_algorithm}",
+                        onchange: move |e| mint_algorithm.set(e.value()),
+                        option { "HS256" }
+                        option { "RS256" }
+                        option { "ES256" }
+                    }
+                    input {
+                        placeholder: "Expires in (seconds)",
+                        value: "{mint_expires_in}",
+                        oninput: move |e| mint_expires_in.set(e.value()),
+                    }
+                }
+                textarea {
+                    class: "jwt-key-input",
+                    placeholder: "Claims template (JSON)",
+                    value: "{mint_claims}",
+                    oninput: move |e| mint_claims.set(e.value()),
+                }
+                textarea {
+                    class: "jwt-key-input",
+                    placeholder: "Secret or PKCS#8 PEM private key used for signing...",
+                    value: "{mint_key}",
+                    oninput: move |e| mint_key.set(e.value()),
+                }
+                input {
+                    placeholder: "...or path to a signing key file",
+                    value: "{mint_key_path}",
+                    oninput: move |e| mint_key_path.set(e.value()),
+                }
+                button { onclick: mint, "Mint" }
+
+                match minted.read().clone() {
+                    Some(Ok(token)) => rsx! {
+                        pre { class: "jwt-token", "{token}" }
+                        div { class: "jwt-actions",
+                            button {
+                                onclick: {
+                                    let token = token.clone();
+                                    move |_| use_as_bearer(token.clone())
+                                },
+                                "Use as Bearer token"
+                            }
+                            input {
+                                placeholder: "Variable name",
+                                value: "{variable}",
+                                oninput: move |e| variable.set(e.value()),
+                            }
+                            button {
+                                title: "Store the token in a variable of the active environment",
+                                onclick: move |_| store_as_variable(token.clone()),
+                                "Store as variable"
+                            }
+                            span { class: "jwt-muted", "{stored}" }
+                        }
+                    },
+                    Some(Err(err)) => rsx! {
+                        p { class: "status-error", "{err}" }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
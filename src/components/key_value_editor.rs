@@ -1,12 +1,12 @@
-use crate::models::{KeyValue, RequestState};
+use crate::models::KeyValue;
 use dioxus::prelude::*;
 
-pub fn key_value_editor(
-    mut request: Signal<RequestState>,
-    field: fn(&mut RequestState) -> &mut Vec<KeyValue>,
+pub fn key_value_editor<T: Clone + 'static>(
+    mut state: Signal<T>,
+    field: fn(&mut T) -> &mut Vec<KeyValue>,
 ) -> Element {
     let items = {
-        let snapshot = request.read();
+        let snapshot = state.read();
         field(&mut snapshot.clone()).clone()
     };
 
@@ -18,9 +18,9 @@ pub fn key_value_editor(
                         placeholder: "Key",
                         value: "{kv.key}",
                         oninput: move |e| {
-                            request
-                                .with_mut(|r| {
-                                    field(r)[idx].key = e.value();
+                            state
+                                .with_mut(|s| {
+                                    field(s)[idx].key = e.value();
                                 });
                         },
                     }
@@ -29,18 +29,18 @@ pub fn key_value_editor(
                         placeholder: "Value",
                         value: "{kv.value}",
                         oninput: move |e| {
-                            request
-                                .with_mut(|r| {
-                                    field(r)[idx].value = e.value();
+                            state
+                                .with_mut(|s| {
+                                    field(s)[idx].value = e.value();
                                 });
                         },
                     }
 
                     button {
                         onclick: move |_| {
-                            request
-                                .with_mut(|r| {
-                                    field(r).remove(idx);
+                            state
+                                .with_mut(|s| {
+                                    field(s).remove(idx);
                                 });
                         },
                         "✕"
@@ -50,9 +50,9 @@ pub fn key_value_editor(
 
             button {
                 onclick: move |_| {
-                    request
-                        .with_mut(|r| {
-                            field(r).push(KeyValue::default());
+                    state
+                        .with_mut(|s| {
+                            field(s).push(KeyValue::default());
                         });
                 },
                 "+ Add"
@@ -0,0 +1,375 @@
+use crate::api::{call, listen};
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{
+    AddMockRouteArgs, CompletedRequest, KeyValue, MockHit, MockRoute, MockSettings,
+    SaveMockSettingsArgs,
+};
+use dioxus::prelude::*;
+
+/// Number of answered requests kept in the log.
+const LOG_SIZE: usize = 100;
+
+const TEMPLATE_HINT: &str = "The response body and headers can use {{params.id}}, {{query.name}}, {{headers.name}}, {{body}}, {{body.field}}, {{method}} and {{path}}.";
+
+/// Response headers that describe how the original response was transferred rather than its
+/// content, which the mock server sets itself.
+const TRANSFER_HEADERS: [&str; 5] = [
+    "content-length",
+    "transfer-encoding",
+    "content-encoding",
+    "connection",
+    "date",
+];
+
+/// The path of a URL, without its query.
+fn url_path(url: &str) -> String {
+    // The host may be given by a variable, as in `{{host}}/users`.
+    let rest = match (url.split_once("://"), url.strip_prefix("{{")) {
+        (Some((_, rest)), _) => rest.find('/').map_or("", |idx| &rest[idx..]),
+        (None, Some(variable)) => variable.split_once("}}").map_or(url, |(_, rest)| rest),
+        (None, None) => url,
+    };
+    match rest.split(['?', '#']).next().unwrap_or_default() {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    }
+}
+
+/// A route answering the request of a history entry with its response.
+pub fn route_from_history(completed: &CompletedRequest) -> MockRoute {
+    let path = url_path(&completed.req.url);
+    MockRoute {
+        name: format!("{} {path}", completed.req.method),
+        method: completed.req.method.to_string(),
+        path,
+        status: completed.resp.status,
+        response_headers: completed
+            .resp
+            .headers
+            .iter()
+            .filter(|kv| !TRANSFER_HEADERS.contains(&kv.key.to_lowercase().as_str()))
+            .cloned()
+            .collect(),
+        response_body: completed.resp.body.clone(),
+        ..Default::default()
+    }
+}
+
+/// Adds a route to the saved mock routes, updating `settings` with the result.
+pub fn add_route(route: MockRoute, mut settings: Signal<MockSettings>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match call::<_, MockSettings>("add_mock_route", &AddMockRouteArgs { route }).await {
+            Ok(saved) => settings.set(saved),
+            Err(err) => web_sys::console::error_1(&err.into()),
+        }
+    });
+}
+
+fn save(settings: MockSettings, mut status: Signal<String>) {
+    let args = SaveMockSettingsArgs { settings };
+    wasm_bindgen_futures::spawn_local(async move {
+        match call::<_, ()>("save_mock_settings", &args).await {
+            Ok(()) => status.set("Saved".to_string()),
+            Err(err) => status.set(err),
+        }
+    });
+}
+
+fn match_headers(route: &mut MockRoute) -> &mut Vec<KeyValue> {
+    &mut route.headers
+}
+
+fn match_query(route: &mut MockRoute) -> &mut Vec<KeyValue> {
+    &mut route.query
+}
+
+fn response_headers(route: &mut MockRoute) -> &mut Vec<KeyValue> {
+    &mut route.response_headers
+}
+
+/// Editor for the route being edited, which is copied back into the settings on save.
+fn route_editor(mut route: Signal<MockRoute>) -> Element {
+    let current = route.read().clone();
+    rsx! {
+        div { class: "mock-route-editor",
+            div { class: "environment-row",
+                input {
+                    placeholder: "Name",
+                    value: "{current.name}",
+                    oninput: move |e| route.with_mut(|r| r.name = e.value()),
+                }
+                input {
+                    class: "mock-method",
+                    placeholder: "Method, * for any",
+                    value: "{current.method}",
+                    oninput: move |e| route.with_mut(|r| r.method = e.value()),
+                }
+                input {
+                    placeholder: "/users/:id",
+                    value: "{current.path}",
+                    oninput: move |e| route.with_mut(|r| r.path = e.value()),
+                }
+            }
+            h4 { "Request must have" }
+            p { class: "settings-hint", "Headers" }
+            {key_value_editor(route, match_headers)}
+            p { class: "settings-hint", "Query parameters" }
+            {key_value_editor(route, match_query)}
+            input {
+                placeholder: "Text the body contains",
+                value: "{current.body}",
+                oninput: move |e| route.with_mut(|r| r.body = e.value()),
+            }
+            h4 { "Response" }
+            div { class: "environment-row",
+                label {
+                    "Status "
+                    input {
+                        class: "run-number",
+                        value: "{current.status}",
+                        oninput: move |e| {
+                            if let Ok(status) = e.value().trim().parse() {
+                                route.with_mut(|r| r.status = status);
+                            }
+                        },
+                    }
+                }
+                label {
+                    "Delay (ms) "
+                    input {
+                        class: "run-number",
+                        value: "{current.delay_ms}",
+                        oninput: move |e| {
+                            if let Ok(delay) = e.value().trim().parse() {
+                                route.with_mut(|r| r.delay_ms = delay);
+                            }
+                        },
+                    }
+                }
+            }
+            {key_value_editor(route, response_headers)}
+            textarea {
+                placeholder: "Response body",
+                value: "{current.response_body}",
+                oninput: move |e| route.with_mut(|r| r.response_body = e.value()),
+            }
+            p { class: "settings-hint", "{TEMPLATE_HINT}" }
+        }
+    }
+}
+
+/// A local server answering requests with canned responses, for developing against APIs that
+/// don't exist yet.
+#[component]
+pub fn MockServerPanel(settings: Signal<MockSettings>) -> Element {
+    let mut port = use_signal(|| None::<u16>);
+    let mut editing = use_signal(|| None::<usize>);
+    let mut route = use_signal(MockRoute::default);
+    let mut hits = use_signal(Vec::<MockHit>::new);
+    let mut status = use_signal(String::new);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, MockSettings>("get_mock_settings", &()).await {
+                Ok(saved) => settings.set(saved),
+                Err(err) => status.set(err),
+            }
+            if let Ok(running) = call::<_, Option<u16>>("mock_server_port", &()).await {
+                port.set(running);
+            }
+            let subscribed = listen("mock-hit", move |hit: MockHit| {
+                hits.with_mut(|h| {
+                    h.insert(0, hit);
+                    h.truncate(LOG_SIZE);
+                });
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let start = move |_| {
+        // The server reads the saved settings.
+        let args = SaveMockSettingsArgs {
+            settings: settings.read().clone(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let started = match call::<_, ()>("save_mock_settings", &args).await {
+                Ok(()) => call::<_, u16>("start_mock_server", &()).await,
+                Err(err) => Err(err),
+            };
+            match started {
+                Ok(listening) => {
+                    port.set(Some(listening));
+                    status.set(String::new());
+                }
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let stop = move |_| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("stop_mock_server", &()).await {
+                Ok(()) => port.set(None),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let save_route = move |_| {
+        let Some(idx) = editing() else { return };
+        let edited = route.read().clone();
+        settings.with_mut(|s| {
+            if let Some(existing) = s.routes.get_mut(idx) {
+                *existing = edited;
+            }
+        });
+        editing.set(None);
+        save(settings.read().clone(), status);
+    };
+
+    let add = move |_| {
+        settings.with_mut(|s| s.routes.push(MockRoute::default()));
+        let idx = settings.read().routes.len() - 1;
+        route.set(MockRoute::default());
+        editing.set(Some(idx));
+    };
+
+    let routes = settings.read().routes.clone();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary {
+                    "Mock server"
+                    if let Some(port) = port() {
+                        " (serving on :{port})"
+                    }
+                }
+                div { class: "environment-row",
+                    label {
+                        "Port "
+                        input {
+                            class: "run-number",
+                            disabled: port().is_some(),
+                            value: "{settings.read().port}",
+                            oninput: move |e| {
+                                if let Ok(port) = e.value().trim().parse() {
+                                    settings.with_mut(|s| s.port = port);
+                                }
+                            },
+                        }
+                    }
+                    if port().is_some() {
+                        button { onclick: stop, "Stop" }
+                    } else {
+                        button { onclick: start, "Start" }
+                    }
+                    if let Some(port) = port() {
+                        span { class: "settings-hint", "http://127.0.0.1:{port}" }
+                    }
+                }
+
+                p { class: "settings-hint",
+                    "Routes are matched in order, the first enabled one matching a request answers it."
+                }
+                ul { class: "collection-requests",
+                    for (idx , item) in routes.iter().enumerate() {
+                        li { key: "{idx}",
+                            input {
+                                r#type: "checkbox",
+                                title: "Enabled",
+                                checked: item.enabled,
+                                onchange: move |e| {
+                                    settings.with_mut(|s| s.routes[idx].enabled = e.checked());
+                                    save(settings.read().clone(), status);
+                                },
+                            }
+                            span { "{item.name} " }
+                            span { class: "response-time",
+                                "{item.method} {item.path} → {item.status}"
+                            }
+                            button {
+                                onclick: {
+                                    let item = item.clone();
+                                    move |_| {
+                                        route.set(item.clone());
+                                        editing.set(Some(idx));
+                                    }
+                                },
+                                "Edit"
+                            }
+                            button {
+                                disabled: idx == 0,
+                                onclick: move |_| {
+                                    settings.with_mut(|s| s.routes.swap(idx - 1, idx));
+                                    editing.set(None);
+                                    save(settings.read().clone(), status);
+                                },
+                                "↑"
+                            }
+                            button {
+                                onclick: move |_| {
+                                    settings
+                                        .with_mut(|s| {
+                                            s.routes.remove(idx);
+                                        });
+                                    editing.set(None);
+                                    save(settings.read().clone(), status);
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+                button { onclick: add, "+ Add route" }
+
+                if editing().is_some() {
+                    {route_editor(route)}
+                    div { class: "settings-actions",
+                        button { onclick: save_route, "Save route" }
+                        button { onclick: move |_| editing.set(None), "Close" }
+                    }
+                }
+                span { class: "settings-status", "{status}" }
+
+                if !hits.read().is_empty() {
+                    details { class: "ws-history",
+                        summary { "Requests ({hits.read().len()})" }
+                        ul { class: "test-results",
+                            for (idx , hit) in hits.read().iter().enumerate() {
+                                li {
+                                    key: "{idx}",
+                                    class: if hit.route.is_some() { "test-passed" } else { "test-failed" },
+                                    span { class: "response-time", "{hit.time} " }
+                                    "{hit.method} {hit.path} → {hit.status} "
+                                    span { class: "response-time",
+                                        {hit.route.clone().unwrap_or_else(|| "no matching route".to_string())}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_paths_from_urls() {
+        assert_eq!(
+            url_path("https://api.example.com/users/1?page=2"),
+            "/users/1"
+        );
+        assert_eq!(url_path("http://localhost:8080"), "/");
+        assert_eq!(url_path("{{host}}/users#top"), "/users");
+    }
+}
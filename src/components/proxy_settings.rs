@@ -0,0 +1,133 @@
+use crate::api::call;
+use crate::models::{
+    ProxyConfig, ProxySettings, RequestProxy, RequestState, SaveProxySettingsArgs,
+};
+use dioxus::prelude::*;
+
+fn proxy_config_editor<T: Clone + 'static>(
+    mut state: Signal<T>,
+    field: fn(&mut T) -> &mut ProxyConfig,
+) -> Element {
+    let config = {
+        let snapshot = state.read();
+        field(&mut snapshot.clone()).clone()
+    };
+
+    rsx! {
+        div { class: "proxy-config",
+            input {
+                placeholder: "http://proxy:8080, https://... or socks5://...",
+                value: "{config.url}",
+                oninput: move |e| state.with_mut(|s| field(s).url = e.value()),
+            }
+            div { class: "proxy-config-row",
+                input {
+                    placeholder: "Username",
+                    value: "{config.username}",
+                    oninput: move |e| state.with_mut(|s| field(s).username = e.value()),
+                }
+                input {
+                    r#type: "password",
+                    placeholder: "Password",
+                    value: "{config.password}",
+                    oninput: move |e| state.with_mut(|s| field(s).password = e.value()),
+                }
+            }
+            input {
+                placeholder: "No proxy for (e.g. localhost, .internal, 10.0.0.0/8)",
+                value: "{config.no_proxy}",
+                oninput: move |e| state.with_mut(|s| field(s).no_proxy = e.value()),
+            }
+        }
+    }
+}
+
+fn custom_proxy(request: &mut RequestState) -> &mut ProxyConfig {
+    if !matches!(request.proxy, RequestProxy::Custom(_)) {
+        request.proxy = RequestProxy::Custom(ProxyConfig::default());
+    }
+    match &mut request.proxy {
+        RequestProxy::Custom(config) => config,
+        _ => unreachable!(),
+    }
+}
+
+pub fn request_proxy_editor(mut request: Signal<RequestState>) -> Element {
+    let mode = match request.read().proxy {
+        RequestProxy::Inherit => "inherit",
+        RequestProxy::Direct => "direct",
+        RequestProxy::Custom(_) => "custom",
+    };
+
+    rsx! {
+        div { class: "request-proxy",
+            select {
+                value: "{mode}",
+                onchange: move |e| {
+                    request
+                        .with_mut(|r| {
+                            r.proxy = match e.value().as_str() {
+                                "direct" => RequestProxy::Direct,
+                                "custom" => RequestProxy::Custom(ProxyConfig::default()),
+                                _ => RequestProxy::Inherit,
+                            };
+                        })
+                },
+                option { value: "inherit", "Use global proxy settings" }
+                option { value: "direct", "No proxy" }
+                option { value: "custom", "Custom proxy" }
+            }
+            if mode == "custom" {
+                {proxy_config_editor(request, custom_proxy)}
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ProxySettingsPanel() -> Element {
+    let mut settings = use_signal(ProxySettings::default);
+    let mut status = use_signal(String::new);
+
+    use_effect(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ProxySettings>("get_proxy_settings", &()).await {
+                Ok(saved) => settings.set(saved),
+                Err(err) => web_sys::console::error_1(&err.into()),
+            }
+        });
+    });
+
+    let save = move |_| {
+        let args = SaveProxySettingsArgs {
+            settings: settings.read().clone(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("save_proxy_settings", &args).await {
+                Ok(()) => status.set("Saved".to_string()),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary { "Proxy" }
+                {proxy_config_editor(settings, |s| &mut s.proxy)}
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: settings.read().use_system,
+                        onchange: move |e| settings.with_mut(|s| s.use_system = e.checked()),
+                    }
+                    "Use system / environment proxy when no proxy URL is set"
+                }
+                div { class: "settings-actions",
+                    button { onclick: save, "Save" }
+                    span { class: "settings-status", "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::api::{call, call_with_args, cancel_request, next_request_id};
+use crate::models::{
+    RawRequest, RequestState, ResponseState, SegmentKind, SendRawRequestArgs, WireExchange,
+};
+use dioxus::prelude::*;
+
+fn segment_class(kind: SegmentKind) -> &'static str {
+    match kind {
+        SegmentKind::Head => "wire-head",
+        SegmentKind::ChunkSize => "wire-chunk-size",
+        SegmentKind::Data => "wire-data",
+        SegmentKind::Trailers => "wire-trailers",
+    }
+}
+
+/// The bytes of a request and its response, with each chunk of a chunked body marked.
+pub fn wire_view(exchange: WireExchange) -> Element {
+    rsx! {
+        div { class: "wire-view",
+            h4 { "Request" }
+            pre { class: "wire-request", "{exchange.request}" }
+            h4 { "Response" }
+            pre { class: "wire-response",
+                for (idx , segment) in exchange.response.into_iter().enumerate() {
+                    span { key: "{idx}", class: segment_class(segment.kind), "{segment.text}" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn RawPanel(request: Signal<RequestState>) -> Element {
+    let mut raw = use_signal(|| RawRequest {
+        target: "http://localhost:8080".to_string(),
+        text: "GET / HTTP/1.1\nHost: localhost:8080\nConnection: close\n".to_string(),
+        fix_content_length: true,
+    });
+    let mut error = use_signal(|| None::<String>);
+    let mut in_flight = use_signal(|| None::<u64>);
+    let mut response = use_signal(|| None::<ResponseState>);
+
+    let from_request = move |_| {
+        let args = request.read().clone();
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            match call_with_args::<_, RawRequest>("render_raw_request", &args).await {
+                Ok(rendered) => raw.set(rendered),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let send = move |_| {
+        // A new request supersedes the one still in flight.
+        if let Some(previous) = in_flight() {
+            cancel_request(previous);
+        }
+        let id = next_request_id();
+        let args = SendRawRequestArgs {
+            id,
+            args: raw.read().clone(),
+        };
+        in_flight.set(Some(id));
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = call::<_, ResponseState>("send_raw_request", &args).await;
+            if in_flight() != Some(id) {
+                return;
+            }
+            in_flight.set(None);
+            match result {
+                Ok(resp) => response.set(Some(resp)),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let cancel = move |_| {
+        if let Some(id) = in_flight() {
+            cancel_request(id);
+        }
+    };
+
+    rsx! {
+        section { class: "raw-panel",
+            h3 { "Raw HTTP" }
+            p { class: "settings-hint",
+                "Raw requests connect to the target directly, proxy settings don't apply."
+            }
+
+            div { class: "request-line",
+                input {
+                    class: "url-input",
+                    placeholder: "http://host:port, https://host:port or unix:/path/to.sock",
+                    value: "{raw.read().target}",
+                    oninput: move |e| raw.with_mut(|r| r.target = e.value()),
+                }
+                button { onclick: from_request, "From request" }
+            }
+
+            textarea {
+                class: "raw-request",
+                spellcheck: false,
+                placeholder: "Request line, headers, a blank line and the body",
+                value: "{raw.read().text}",
+                oninput: move |e| raw.with_mut(|r| r.text = e.value()),
+            }
+
+            div { class: "settings-actions",
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: raw.read().fix_content_length,
+                        onchange: move |e| raw.with_mut(|r| r.fix_content_length = e.checked()),
+                    }
+                    "Update Content-Length"
+                }
+                button { onclick: send, "Send" }
+                if in_flight().is_some() {
+                    button { onclick: cancel, "Cancel" }
+                }
+            }
+
+            if let Some(err) = error() {
+                p { class: "status-error", "{err}" }
+            }
+
+            if in_flight().is_some() {
+                p { class: "in-flight",
+                    span { class: "spinner" }
+                    "Waiting for response..."
+                }
+            }
+
+            if let Some(resp) = response() {
+                p {
+                    strong { "Status: " }
+                    "{resp.status}"
+                    span { class: "response-time", {format!(" ({:.2} ms)", resp.response_time)} }
+                }
+                if let Some(wire) = resp.wire {
+                    {wire_view(wire)}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::api::{call, listen};
+use crate::models::{
+    CompletedRequest, ExportRecorderCaArgs, KeyValue, RecorderExchange, RecorderMode,
+    RecorderStatus, RequestState, StartRecorderArgs,
+};
+use dioxus::prelude::*;
+
+/// Port suggested for the recorder.
+const DEFAULT_PORT: &str = "8888";
+
+/// Whether an exchange matches the filter text, by its method, URL or status.
+fn matches(exchange: &RecorderExchange, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    let recording = &exchange.recording;
+    filter.is_empty()
+        || recording.url.to_lowercase().contains(&filter)
+        || recording.method.to_lowercase() == filter
+        || recording.status.to_string().starts_with(&filter)
+}
+
+fn headers_text(headers: &[KeyValue]) -> String {
+    headers
+        .iter()
+        .map(|kv| format!("{}: {}", kv.key, kv.value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn exchange_details(exchange: &RecorderExchange) -> Element {
+    let recording = &exchange.recording;
+    rsx! {
+        h4 { "Request" }
+        pre { "{headers_text(&recording.request_headers)}" }
+        if !recording.request_body.is_empty() {
+            pre { "{recording.request_body}" }
+        }
+        h4 { "Response" }
+        pre { "{headers_text(&recording.response_headers)}" }
+        if !recording.response_body.is_empty() {
+            pre { "{recording.response_body}" }
+        }
+    }
+}
+
+/// A proxy recording the traffic of other clients into the history, or serving the recorded
+/// responses back to them offline.
+#[component]
+pub fn RecorderPanel(
+    request: Signal<RequestState>,
+    history: Signal<Vec<CompletedRequest>>,
+) -> Element {
+    let mut port = use_signal(|| DEFAULT_PORT.to_string());
+    let mut mode = use_signal(RecorderMode::default);
+    let mut running = use_signal(|| None::<RecorderStatus>);
+    let mut exchanges = use_signal(Vec::<RecorderExchange>::new);
+    let mut filter = use_signal(String::new);
+    let mut certificate = use_signal(|| None::<String>);
+    let mut export_path = use_signal(String::new);
+    let mut status = use_signal(String::new);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Ok(Some(current)) =
+                call::<_, Option<RecorderStatus>>("recorder_status", &()).await
+            {
+                port.set(current.port.to_string());
+                mode.set(current.mode);
+                running.set(Some(current));
+            }
+            match call::<_, Vec<RecorderExchange>>("get_recordings", &()).await {
+                Ok(recorded) => exchanges.set(recorded.into_iter().rev().collect()),
+                Err(err) => status.set(err),
+            }
+            let subscribed = listen("recorder-exchange", move |exchange: RecorderExchange| {
+                // Recorded exchanges are saved to the history as well.
+                if !exchange.recording.replayed {
+                    history.with_mut(|h| h.push(exchange.completed.clone()));
+                }
+                exchanges.with_mut(|e| e.insert(0, exchange));
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let start = move |_| {
+        let Ok(port) = port().trim().parse() else {
+            status.set("The port must be a number".to_string());
+            return;
+        };
+        let args = StartRecorderArgs { port, mode: mode() };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, u16>("start_recorder", &args).await {
+                Ok(port) => {
+                    running.set(Some(RecorderStatus {
+                        port,
+                        mode: args.mode,
+                    }));
+                    status.set(String::new());
+                }
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let stop = move |_| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("stop_recorder", &()).await {
+                Ok(()) => running.set(None),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let clear = move |_| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("clear_recordings", &()).await {
+                Ok(()) => exchanges.set(Vec::new()),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let show_certificate = move |_| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, String>("recorder_ca_certificate", &()).await {
+                Ok(pem) => certificate.set(Some(pem)),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let export = move |_| {
+        let args = ExportRecorderCaArgs {
+            path: export_path().trim().to_string(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("export_recorder_ca", &args).await {
+                Ok(()) => status.set(format!("Saved the certificate to {}", args.path)),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let shown: Vec<RecorderExchange> = exchanges
+        .read()
+        .iter()
+        .filter(|e| matches(e, &filter.read()))
+        .cloned()
+        .collect();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary {
+                    "Recorder"
+                    if let Some(current) = running() {
+                        match current.mode {
+                            RecorderMode::Record => " (recording on :{current.port})",
+                            RecorderMode::Replay => " (replaying on :{current.port})",
+                        }
+                    }
+                }
+                p { class: "settings-hint",
+                    "A proxy for other clients. Recording forwards their requests and saves every exchange to the history, replaying answers with the recorded responses without reaching the network."
+                }
+                div { class: "environment-row",
+                    label {
+                        "Port "
+                        input {
+                            class: "run-number",
+                            disabled: running().is_some(),
+                            value: "{port}",
+                            oninput: move |e| port.set(e.value()),
+                        }
+                    }
+                    select {
+                        disabled: running().is_some(),
+                        value: if mode() == RecorderMode::Replay { "replay" } else { "record" },
+                        onchange: move |e| {
+                            mode.set(
+                                if e.value() == "replay" { RecorderMode::Replay } else { RecorderMode::Record },
+                            )
+                        },
+                        option { value: "record", "Record" }
+                        option { value: "replay", "Replay" }
+                    }
+                    if running().is_some() {
+                        button { onclick: stop, "Stop" }
+                    } else {
+                        button { onclick: start, "Start" }
+                    }
+                    if let Some(current) = running() {
+                        span { class: "settings-hint", "http://127.0.0.1:{current.port}" }
+                    }
+                }
+
+                details { class: "ws-history",
+                    summary { onclick: show_certificate, "HTTPS certificate" }
+                    p { class: "settings-hint",
+                        "HTTPS traffic is decrypted with certificates issued by a local certificate authority. Clients must trust its certificate, for example with curl --cacert or by importing it into the browser."
+                    }
+                    if let Some(pem) = certificate() {
+                        textarea { class: "recorder-certificate", readonly: true, value: "{pem}" }
+                    }
+                    div { class: "environment-row",
+                        input {
+                            placeholder: "Path to save the certificate to, e.g. requestor-ca.pem",
+                            value: "{export_path}",
+                            oninput: move |e| export_path.set(e.value()),
+                        }
+                        button {
+                            disabled: export_path().trim().is_empty(),
+                            onclick: export,
+                            "Save"
+                        }
+                    }
+                }
+
+                div { class: "environment-row",
+                    input {
+                        placeholder: "Filter by URL, method or status",
+                        value: "{filter}",
+                        oninput: move |e| filter.set(e.value()),
+                    }
+                    button { onclick: clear, "Clear recordings" }
+                }
+                span { class: "settings-status", "{status}" }
+
+                ul { class: "test-results recorder-exchanges",
+                    for exchange in shown.iter() {
+                        li {
+                            key: "{exchange.recording.id}-{exchange.recording.created_at}",
+                            class: if exchange.recording.status < 400 { "test-passed" } else { "test-failed" },
+                            details {
+                                summary {
+                                    "{exchange.recording.method} {exchange.recording.url} → {exchange.recording.status} "
+                                    span { class: "response-time",
+                                        {format!("({:.2} ms)", exchange.recording.response_time)}
+                                        if exchange.recording.replayed {
+                                            " replayed"
+                                        }
+                                    }
+                                    button {
+                                        class: "history-mock",
+                                        title: "Open this request in the editor",
+                                        onclick: {
+                                            let saved = exchange.completed.req.clone();
+                                            move |e: Event<MouseData>| {
+                                                e.stop_propagation();
+                                                request.set(saved.clone());
+                                            }
+                                        },
+                                        "Open"
+                                    }
+                                }
+                                {exchange_details(exchange)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Recording, ResponseState};
+
+    #[test]
+    fn filters_by_url_method_and_status() {
+        let exchange = RecorderExchange {
+            recording: Recording {
+                id: 1,
+                method: "POST".to_string(),
+                url: "https://api.example.com/Users".to_string(),
+                request_headers: Vec::new(),
+                request_body: String::new(),
+                status: 201,
+                response_headers: Vec::new(),
+                response_body: String::new(),
+                response_time: 0.0,
+                created_at: String::new(),
+                replayed: false,
+            },
+            completed: CompletedRequest {
+                req: RequestState::default(),
+                resp: ResponseState::default(),
+            },
+        };
+        assert!(matches(&exchange, ""));
+        assert!(matches(&exchange, "users"));
+        assert!(matches(&exchange, "post"));
+        assert!(matches(&exchange, "2"));
+        assert!(!matches(&exchange, "get"));
+        assert!(!matches(&exchange, "404"));
+    }
+}
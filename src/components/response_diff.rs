@@ -0,0 +1,215 @@
+use crate::api::call;
+use crate::models::{
+    BodyDiff, ChangeKind, CompletedRequest, DiffOptions, DiffResponsesArgs, DiffSide, LineTag,
+    ResponseDiff, ResponseState,
+};
+use dioxus::prelude::*;
+
+/// Value of the option comparing against the response currently shown.
+const CURRENT: &str = "current";
+
+fn side(resp: &ResponseState) -> DiffSide {
+    DiffSide {
+        status: resp.status,
+        headers: resp.headers.clone(),
+        body: resp.body.clone(),
+    }
+}
+
+fn entry_label(idx: usize, completed: &CompletedRequest) -> String {
+    format!(
+        "#{} {} {} → {}",
+        idx + 1,
+        completed.req.method,
+        completed.req.url,
+        completed.resp.status
+    )
+}
+
+/// Paths to ignore, separated by commas or new lines.
+pub fn ignored(text: &str) -> Vec<String> {
+    text.split([',', '\n'])
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn json_text(value: &Option<serde_json::Value>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
+
+pub fn diff_view(diff: &ResponseDiff) -> Element {
+    rsx! {
+        if diff.left_status == diff.right_status {
+            p { class: "settings-hint", "Status {diff.left_status} on both" }
+        } else {
+            p { class: "diff-changed", "Status {diff.left_status} → {diff.right_status}" }
+        }
+
+        h4 { "Headers" }
+        if diff.headers.is_empty() {
+            p { class: "settings-hint", "No differences" }
+        } else {
+            table { class: "response-headers diff-table",
+                for change in diff.headers.iter() {
+                    tr { key: "{change.name}",
+                        td { "{change.name}" }
+                        td { class: "diff-removed", {change.left.clone().unwrap_or_else(|| "—".to_string())} }
+                        td { class: "diff-added", {change.right.clone().unwrap_or_else(|| "—".to_string())} }
+                    }
+                }
+            }
+        }
+
+        h4 { "Body" }
+        match &diff.body {
+            BodyDiff::Json { changes } if changes.is_empty() => rsx! {
+                p { class: "settings-hint", "No differences" }
+            },
+            BodyDiff::Json { changes } => rsx! {
+                table { class: "diff-table",
+                    for change in changes.iter() {
+                        tr { key: "{change.path}",
+                            class: match change.kind {
+                                ChangeKind::Added => "diff-added",
+                                ChangeKind::Removed => "diff-removed",
+                                ChangeKind::Changed => "diff-changed",
+                            },
+                            td { "{change.path}" }
+                            td { {json_text(&change.left)} }
+                            td { {json_text(&change.right)} }
+                        }
+                    }
+                }
+            },
+            BodyDiff::Text { lines } => rsx! {
+                pre { class: "diff-lines",
+                    for (idx , line) in lines.iter().enumerate() {
+                        match line.tag {
+                            LineTag::Same => rsx! {
+                                div { key: "{idx}", "  {line.text}" }
+                            },
+                            LineTag::Added => rsx! {
+                                div { key: "{idx}", class: "diff-added", "+ {line.text}" }
+                            },
+                            LineTag::Removed => rsx! {
+                                div { key: "{idx}", class: "diff-removed", "- {line.text}" }
+                            },
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Compares two history entries, or one against the current response.
+#[component]
+pub fn ResponseDiffPanel(
+    history: Signal<Vec<CompletedRequest>>,
+    response: Signal<Option<ResponseState>>,
+) -> Element {
+    let mut left = use_signal(String::new);
+    let mut right = use_signal(|| CURRENT.to_string());
+    let mut ignore = use_signal(String::new);
+    let mut diff = use_signal(|| None::<ResponseDiff>);
+    let mut error = use_signal(|| None::<String>);
+
+    let pick = move |value: &str| -> Option<DiffSide> {
+        match value {
+            CURRENT => response.read().as_ref().map(side),
+            idx => idx
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| history.read().get(idx).map(|c| side(&c.resp))),
+        }
+    };
+
+    let compare = move |_| {
+        let (Some(l), Some(r)) = (pick(&left()), pick(&right())) else {
+            error.set(Some("Pick two responses to compare".to_string()));
+            return;
+        };
+        let args = DiffResponsesArgs {
+            left: l,
+            right: r,
+            options: DiffOptions {
+                ignore: ignored(&ignore()),
+            },
+        };
+        error.set(None);
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ResponseDiff>("diff_responses", &args).await {
+                Ok(result) => diff.set(Some(result)),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let entries: Vec<(usize, String)> = history
+        .read()
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(idx, completed)| (idx, entry_label(idx, completed)))
+        .collect();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary { "Compare responses" }
+                div { class: "environment-row",
+                    select {
+                        value: "{left}",
+                        onchange: move |e| left.set(e.value()),
+                        option { value: "", "Pick a history entry" }
+                        for (idx , label) in entries.iter() {
+                            option { key: "{idx}", value: "{idx}", "{label}" }
+                        }
+                    }
+                    span { "with" }
+                    select {
+                        value: "{right}",
+                        onchange: move |e| right.set(e.value()),
+                        option { value: CURRENT, "Current response" }
+                        for (idx , label) in entries.iter() {
+                            option { key: "{idx}", value: "{idx}", "{label}" }
+                        }
+                    }
+                }
+                textarea {
+                    class: "diff-ignore",
+                    placeholder: "JSON paths or headers to ignore, e.g. $.meta.generated_at, $.items[*].id, updated_at, date",
+                    value: "{ignore}",
+                    oninput: move |e| ignore.set(e.value()),
+                }
+                div { class: "settings-actions",
+                    button { onclick: compare, "Compare" }
+                }
+                if let Some(err) = error() {
+                    p { class: "status-error", "{err}" }
+                }
+                if let Some(diff) = diff() {
+                    {diff_view(&diff)}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_ignored_paths() {
+        assert_eq!(
+            ignored("$.id, updated_at\n\n date "),
+            ["$.id", "updated_at", "date"]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::models::RequestState;
+use dioxus::prelude::*;
+
+const PRE_REQUEST_HELP: &str = "Rhai script run before sending. `request` can be changed, \
+    get_var/set_var read and set variables for this request, set_env writes to the active \
+    environment and print logs to the console.";
+
+const POST_RESPONSE_HELP: &str = "Rhai script run after the response. `response` has status, \
+    headers, body, json and response_time; test(name, passed) and test(name, passed, message) \
+    add test results, set_env writes to the active environment.";
+
+const PRE_REQUEST_EXAMPLE: &str =
+    "set_var(\"user\", \"ada\");\nrequest.headers.push(#{ key: \"X-Trace\", value: \"on\" });";
+
+const POST_RESPONSE_EXAMPLE: &str =
+    "test(\"created\", response.status == 201);\nset_env(\"user_id\", response.json.id);";
+
+pub fn script_editor(mut request: Signal<RequestState>) -> Element {
+    let (pre, post) = {
+        let r = request.read();
+        (r.pre_request_script.clone(), r.post_response_script.clone())
+    };
+
+    rsx! {
+        div { class: "script-editor",
+            h4 { "Pre-request" }
+            p { class: "settings-hint", "{PRE_REQUEST_HELP}" }
+            textarea {
+                spellcheck: false,
+                placeholder: PRE_REQUEST_EXAMPLE,
+                value: "{pre}",
+                oninput: move |e| request.with_mut(|r| r.pre_request_script = e.value()),
+            }
+            h4 { "Post-response" }
+            p { class: "settings-hint", "{POST_RESPONSE_HELP}" }
+            textarea {
+                spellcheck: false,
+                placeholder: POST_RESPONSE_EXAMPLE,
+                value: "{post}",
+                oninput: move |e| request.with_mut(|r| r.post_response_script = e.value()),
+            }
+        }
+    }
+}
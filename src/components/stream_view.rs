@@ -0,0 +1,24 @@
+use crate::components::websocket_panel::time_of_day;
+use crate::models::StreamEvent;
+use dioxus::prelude::*;
+
+pub fn stream_event_list(events: Vec<StreamEvent>) -> Element {
+    rsx! {
+        ul { class: "stream-events",
+            for (idx , event) in events.into_iter().enumerate() {
+                li { key: "{idx}",
+                    div { class: "stream-event-meta",
+                        span { class: "ws-time", "{time_of_day(&event.timestamp)}" }
+                        if let Some(name) = event.event {
+                            span { class: "stream-event-name", "{name}" }
+                        }
+                        if let Some(id) = event.id {
+                            span { class: "stream-event-id", "id: {id}" }
+                        }
+                    }
+                    pre { "{event.data}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::api::call;
+use crate::models::{SaveTlsConfigsArgs, TlsConfig};
+use dioxus::prelude::*;
+
+#[component]
+pub fn TlsSettings() -> Element {
+    let mut configs = use_signal(Vec::<TlsConfig>::new);
+    let mut status = use_signal(String::new);
+
+    use_effect(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Vec<TlsConfig>>("get_tls_configs", &()).await {
+                Ok(saved) => configs.set(saved),
+                Err(err) => web_sys::console::error_1(&err.into()),
+            }
+        });
+    });
+
+    let save = move |_| {
+        let args = SaveTlsConfigsArgs {
+            configs: configs.read().clone(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("save_tls_configs", &args).await {
+                Ok(()) => status.set("Saved".to_string()),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let items = configs.read().clone();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary { "TLS client certificates & CAs" }
+
+                for (idx , config) in items.iter().enumerate() {
+                    div { class: "tls-config",
+                        div { class: "tls-config-row",
+                            input {
+                                placeholder: "Host (api.internal or *.internal)",
+                                value: "{config.host}",
+                                oninput: move |e| configs.with_mut(|c| c[idx].host = e.value()),
+                            }
+                            button {
+                                class: "remove-button",
+                                onclick: move |_| {
+                                    configs.with_mut(|c| {
+                                        c.remove(idx);
+                                    });
+                                },
+                                "✕"
+                            }
+                        }
+                        div { class: "tls-config-row",
+                            input {
+                                placeholder: "Client certificate (PEM)",
+                                value: "{config.client_cert_path}",
+                                oninput: move |e| configs.with_mut(|c| c[idx].client_cert_path = e.value()),
+                            }
+                            input {
+                                placeholder: "Client key (PEM)",
+                                value: "{config.client_key_path}",
+                                oninput: move |e| configs.with_mut(|c| c[idx].client_key_path = e.value()),
+                            }
+                        }
+                        div { class: "tls-config-row",
+                            input {
+                                placeholder: "...or PKCS#12 bundle",
+                                value: "{config.pkcs12_path}",
+                                oninput: move |e| configs.with_mut(|c| c[idx].pkcs12_path = e.value()),
+                            }
+                            input {
+                                r#type: "password",
+                                placeholder: "PKCS#12 password",
+                                value: "{config.pkcs12_password}",
+                                oninput: move |e| configs.with_mut(|c| c[idx].pkcs12_password = e.value()),
+                            }
+                        }
+                        textarea {
+                            class: "tls-ca-paths",
+                            placeholder: "Additional trusted CA certificate files, one per line",
+                            value: "{config.ca_cert_paths.join(\"\\n\")}",
+                            oninput: move |e| {
+                                configs
+                                    .with_mut(|c| {
+                                        c[idx].ca_cert_paths = e.value().lines().map(str::to_string).collect();
+                                    })
+                            },
+                        }
+                    }
+                }
+
+                div { class: "settings-actions",
+                    button { onclick: move |_| configs.with_mut(|c| c.push(TlsConfig::default())),
+                        "+ Add host"
+                    }
+                    button { onclick: save, "Save" }
+                    span { class: "settings-status", "{status}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::api::{call, listen};
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{
+    CapturedRequest, CapturedRequestArgs, KeyValue, RequestState, SaveWebhookSettingsArgs,
+    WebhookSettings,
+};
+use dioxus::prelude::*;
+
+/// Number of captured requests kept in the list.
+const LOG_SIZE: usize = 200;
+
+fn reply_headers(settings: &mut WebhookSettings) -> &mut Vec<KeyValue> {
+    &mut settings.reply.headers
+}
+
+fn captured_details(captured: &CapturedRequest) -> Element {
+    let headers = captured
+        .headers
+        .iter()
+        .map(|kv| format!("{}: {}", kv.key, kv.value))
+        .collect::<Vec<_>>()
+        .join("\n");
+    rsx! {
+        p { class: "settings-hint", "From {captured.source} at {captured.time}" }
+        pre { "{headers}" }
+        if !captured.body.is_empty() {
+            pre { "{captured.body}" }
+        }
+    }
+}
+
+/// A local endpoint capturing the requests third-party services send to it, so webhooks can
+/// be inspected and replayed from the editor.
+#[component]
+pub fn WebhookPanel(request: Signal<RequestState>) -> Element {
+    let mut settings = use_signal(WebhookSettings::default);
+    let mut port = use_signal(|| None::<u16>);
+    let mut captured = use_signal(Vec::<CapturedRequest>::new);
+    let mut origin = use_signal(String::new);
+    let mut status = use_signal(String::new);
+
+    use_hook(move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, WebhookSettings>("get_webhook_settings", &()).await {
+                Ok(saved) => settings.set(saved),
+                Err(err) => status.set(err),
+            }
+            if let Ok(running) = call::<_, Option<u16>>("webhook_receiver_port", &()).await {
+                port.set(running);
+            }
+            let subscribed = listen("webhook-capture", move |request: CapturedRequest| {
+                captured.with_mut(|c| {
+                    c.insert(0, request);
+                    c.truncate(LOG_SIZE);
+                });
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let save = move |_| {
+        let args = SaveWebhookSettingsArgs {
+            settings: settings.read().clone(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("save_webhook_settings", &args).await {
+                Ok(()) => status.set("Saved".to_string()),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let start = move |_| {
+        // The receiver reads the saved settings.
+        let args = SaveWebhookSettingsArgs {
+            settings: settings.read().clone(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let started = match call::<_, ()>("save_webhook_settings", &args).await {
+                Ok(()) => call::<_, u16>("start_webhook_receiver", &()).await,
+                Err(err) => Err(err),
+            };
+            match started {
+                Ok(listening) => {
+                    port.set(Some(listening));
+                    status.set(String::new());
+                }
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let stop = move |_| {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, ()>("stop_webhook_receiver", &()).await {
+                Ok(()) => port.set(None),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let open = move |captured: CapturedRequest| {
+        let receiver = format!("http://127.0.0.1:{}", settings.read().port);
+        let args = CapturedRequestArgs {
+            captured,
+            origin: match origin().trim() {
+                "" => receiver,
+                origin => origin.to_string(),
+            },
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, RequestState>("captured_request", &args).await {
+                Ok(opened) => request.set(opened),
+                Err(err) => status.set(err),
+            }
+        });
+    };
+
+    let current = settings.read().clone();
+
+    rsx! {
+        section { class: "settings",
+            details {
+                summary {
+                    "Webhooks"
+                    if let Some(port) = port() {
+                        " (receiving on :{port})"
+                    }
+                }
+                p { class: "settings-hint",
+                    "Captures every request sent under the path. Services outside this machine can reach it through a tunnel such as ngrok."
+                }
+                div { class: "environment-row",
+                    label {
+                        "Port "
+                        input {
+                            class: "run-number",
+                            disabled: port().is_some(),
+                            value: "{current.port}",
+                            oninput: move |e| {
+                                if let Ok(port) = e.value().trim().parse() {
+                                    settings.with_mut(|s| s.port = port);
+                                }
+                            },
+                        }
+                    }
+                    input {
+                        placeholder: "/hooks",
+                        value: "{current.path}",
+                        oninput: move |e| settings.with_mut(|s| s.path = e.value()),
+                    }
+                    if port().is_some() {
+                        button { onclick: stop, "Stop" }
+                    } else {
+                        button { onclick: start, "Start" }
+                    }
+                    if let Some(port) = port() {
+                        span { class: "settings-hint", "http://127.0.0.1:{port}{current.path}" }
+                    }
+                }
+
+                h4 { "Reply" }
+                label {
+                    "Status "
+                    input {
+                        class: "run-number",
+                        value: "{current.reply.status}",
+                        oninput: move |e| {
+                            if let Ok(status) = e.value().trim().parse() {
+                                settings.with_mut(|s| s.reply.status = status);
+                            }
+                        },
+                    }
+                }
+                {key_value_editor(settings, reply_headers)}
+                textarea {
+                    class: "webhook-reply",
+                    placeholder: "Reply body",
+                    value: "{current.reply.body}",
+                    oninput: move |e| settings.with_mut(|s| s.reply.body = e.value()),
+                }
+                div { class: "settings-actions",
+                    button { onclick: save, "Save" }
+                    span { class: "settings-status", "{status}" }
+                }
+
+                h4 { "Captured requests ({captured.read().len()})" }
+                div { class: "environment-row",
+                    input {
+                        placeholder: "Open requests against, e.g. http://localhost:3000",
+                        value: "{origin}",
+                        oninput: move |e| origin.set(e.value()),
+                    }
+                    button { onclick: move |_| captured.set(Vec::new()), "Clear" }
+                }
+                ul { class: "test-results webhook-captures",
+                    for (idx , item) in captured.read().iter().enumerate() {
+                        li { key: "{item.time}-{idx}",
+                            details {
+                                summary {
+                                    span { class: "response-time", "{item.time} " }
+                                    "{item.method} {item.path} "
+                                    button {
+                                        class: "history-mock",
+                                        title: "Open this request in the editor, to send it again or save it to a collection",
+                                        onclick: {
+                                            let item = item.clone();
+                                            move |e: Event<MouseData>| {
+                                                e.stop_propagation();
+                                                open(item.clone());
+                                            }
+                                        },
+                                        "Open"
+                                    }
+                                }
+                                {captured_details(item)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
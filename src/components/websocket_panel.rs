@@ -0,0 +1,276 @@
+use crate::api::{call, call_with_args, listen};
+use crate::components::key_value_editor::key_value_editor;
+use crate::models::{
+    WsCloseArgs, WsConnectArgs, WsDirection, WsEvent, WsFrameKind, WsMessage, WsPingArgs,
+    WsSendArgs, WsSession,
+};
+use dioxus::prelude::*;
+
+fn direction_marker(direction: &WsDirection) -> (&'static str, &'static str) {
+    match direction {
+        WsDirection::Sent => ("↑", "ws-sent"),
+        WsDirection::Received => ("↓", "ws-received"),
+        WsDirection::Info => ("•", "ws-info"),
+    }
+}
+
+/// The time of day of an RFC 3339 timestamp, down to milliseconds.
+pub fn time_of_day(timestamp: &str) -> &str {
+    timestamp.get(11..23).unwrap_or(timestamp)
+}
+
+fn message_log(messages: Vec<WsMessage>) -> Element {
+    rsx! {
+        ul { class: "ws-log",
+            for (idx , message) in messages.into_iter().enumerate() {
+                {
+                    let (marker, class) = direction_marker(&message.direction);
+                    rsx! {
+                        li { key: "{idx}", class: "{class}",
+                            span { class: "ws-time", "{time_of_day(&message.timestamp)}" }
+                            span { class: "ws-kind", "{marker} {message.kind}" }
+                            pre { "{message.data}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn WebSocketPanel() -> Element {
+    let mut connection = use_signal(|| WsConnectArgs {
+        url: "wss://".to_string(),
+        ..Default::default()
+    });
+    let mut session_id = use_signal(|| None::<u64>);
+    let mut connecting = use_signal(|| false);
+    let mut messages = use_signal(Vec::<WsMessage>::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut frame_kind = use_signal(WsFrameKind::default);
+    let mut frame_data = use_signal(String::new);
+    let mut close_code = use_signal(|| "1000".to_string());
+    let mut close_reason = use_signal(String::new);
+
+    let mut sessions = use_signal(Vec::<WsSession>::new);
+
+    let load_sessions = move || {
+        wasm_bindgen_futures::spawn_local(async move {
+            match call::<_, Vec<WsSession>>("get_ws_sessions", &()).await {
+                Ok(saved) => sessions.set(saved),
+                Err(err) => web_sys::console::error_1(&err.into()),
+            }
+        });
+    };
+
+    use_hook(move || {
+        load_sessions();
+        wasm_bindgen_futures::spawn_local(async move {
+            let subscribed = listen("ws-message", move |event: WsEvent| {
+                // The handshake message arrives before `ws_connect` returns the session id.
+                let pending = session_id().is_none() && connecting();
+                if !pending && session_id() != Some(event.session_id) {
+                    return;
+                }
+                messages.with_mut(|m| m.push(event.message));
+                if !event.connected {
+                    session_id.set(None);
+                    // The backend saves the transcript once the session ends.
+                    load_sessions();
+                }
+            })
+            .await;
+            if let Err(err) = subscribed {
+                web_sys::console::error_1(&err.into());
+            }
+        });
+    });
+
+    let connect = move |_| {
+        let args = connection.read().clone();
+        connecting.set(true);
+        error.set(None);
+        messages.set(Vec::new());
+        wasm_bindgen_futures::spawn_local(async move {
+            match call_with_args::<_, u64>("ws_connect", &args).await {
+                Ok(id) => session_id.set(Some(id)),
+                Err(err) => error.set(Some(err)),
+            }
+            connecting.set(false);
+        });
+    };
+
+    let send = move |_| {
+        let Some(id) = session_id() else { return };
+        let args = WsSendArgs {
+            session_id: id,
+            kind: frame_kind(),
+            data: frame_data(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            match call_with_args::<_, ()>("ws_send", &args).await {
+                Ok(()) => error.set(None),
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+
+    let ping = move |_| {
+        let Some(id) = session_id() else { return };
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err) = call::<_, ()>("ws_ping", &WsPingArgs { session: id }).await {
+                error.set(Some(err));
+            }
+        });
+    };
+
+    let close = move |_| {
+        let Some(id) = session_id() else { return };
+        let code = match close_code().trim().parse() {
+            Ok(code) => code,
+            Err(_) => {
+                error.set(Some("Close code must be a number".to_string()));
+                return;
+            }
+        };
+        let args = WsCloseArgs {
+            session_id: id,
+            code,
+            reason: close_reason(),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err) = call_with_args::<_, ()>("ws_close", &args).await {
+                error.set(Some(err));
+            }
+        });
+    };
+
+    let connected = session_id().is_some();
+    let protocols = connection.read().protocols.join(",");
+
+    rsx! {
+        section { class: "websocket-panel",
+            h3 { "WebSocket" }
+
+            div { class: "request-line",
+                input {
+                    class: "url-input",
+                    placeholder: "wss://example.com/socket",
+                    value: "{connection.read().url}",
+                    disabled: connected,
+                    oninput: move |e| connection.with_mut(|c| c.url = e.value()),
+                }
+                if connected {
+                    button { onclick: close, "Disconnect" }
+                } else {
+                    button { disabled: connecting(), onclick: connect,
+                        if connecting() {
+                            "Connecting..."
+                        } else {
+                            "Connect"
+                        }
+                    }
+                }
+            }
+
+            h4 { "Headers" }
+            {key_value_editor(connection, |c| &mut c.headers)}
+
+            input {
+                placeholder: "Subprotocols, comma separated (e.g. graphql-transport-ws)",
+                value: "{protocols}",
+                disabled: connected,
+                oninput: move |e| {
+                    connection
+                        .with_mut(|c| {
+                            c.protocols = e.value().split(',').map(str::to_string).collect();
+                        })
+                },
+            }
+
+            if let Some(err) = error() {
+                p { class: "status-error", "{err}" }
+            }
+
+            if connected {
+                div { class: "ws-composer",
+                    textarea {
+                        placeholder: if frame_kind() == WsFrameKind::Binary { "Base64 encoded payload" } else { "Message" },
+                        value: "{frame_data}",
+                        oninput: move |e| frame_data.set(e.value()),
+                    }
+                    div { class: "ws-actions",
+                        select {
+                            onchange: move |e| {
+                                frame_kind
+                                    .set(
+                                        if e.value() == "binary" {
+                                            WsFrameKind::Binary
+                                        } else {
+                                            WsFrameKind::Text
+                                        },
+                                    )
+                            },
+                            option { value: "text", selected: frame_kind() == WsFrameKind::Text, "Text" }
+                            option {
+                                value: "binary",
+                                selected: frame_kind() == WsFrameKind::Binary,
+                                "Binary"
+                            }
+                        }
+                        button { onclick: send, "Send" }
+                        button { onclick: ping, "Ping" }
+                        input {
+                            class: "ws-close-code",
+                            placeholder: "Code",
+                            value: "{close_code}",
+                            oninput: move |e| close_code.set(e.value()),
+                        }
+                        input {
+                            placeholder: "Close reason",
+                            value: "{close_reason}",
+                            oninput: move |e| close_reason.set(e.value()),
+                        }
+                    }
+                }
+            }
+
+            {message_log(messages.read().clone())}
+
+            if !sessions.read().is_empty() {
+                details { class: "ws-history",
+                    summary { "Session history" }
+                    for (idx , session) in sessions.read().clone().into_iter().rev().enumerate() {
+                        details { key: "{idx}",
+                            summary {
+                                "{session.url} "
+                                span { class: "response-time",
+                                    "({session.created_at}, {session.messages.len()} messages)"
+                                }
+                            }
+                            button {
+                                disabled: connected,
+                                onclick: {
+                                    let session = session.clone();
+                                    move |_| {
+                                        connection
+                                            .set(WsConnectArgs {
+                                                url: session.url.clone(),
+                                                headers: session.headers.clone(),
+                                                protocols: session.protocols.clone(),
+                                            });
+                                        messages.set(session.messages.clone());
+                                    }
+                                },
+                                "Load"
+                            }
+                            {message_log(session.messages.clone())}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod api;
 mod app;
 mod components;
 mod highlight;
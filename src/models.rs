@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct KeyValue {
     pub key: String,
     pub value: String,
@@ -12,12 +14,171 @@ pub struct RequestState {
     pub url: String,
     pub query_params: Vec<KeyValue>,
     pub headers: Vec<KeyValue>,
-    pub body: String,
+    pub body: RequestBody,
+    #[serde(default)]
+    pub proxy: RequestProxy,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub wire: bool,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    #[serde(default)]
+    pub pre_request_script: String,
+    #[serde(default)]
+    pub post_response_script: String,
+    #[serde(default)]
+    pub extractors: Vec<Extractor>,
 }
 
-#[derive(Serialize, Clone)]
-pub struct SendRequestArgs {
-    pub args: RequestState,
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionKind {
+    #[default]
+    Status,
+    HeaderPresent,
+    HeaderEquals,
+    HeaderMatches,
+    JsonPathEquals,
+    JsonPathContains,
+    JsonPathType,
+    BodyContains,
+    BodyMatches,
+    ResponseTimeBelow,
+    JsonSchema,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Assertion {
+    pub kind: AssertionKind,
+    pub target: String,
+    pub expected: String,
+    pub enabled: bool,
+}
+
+impl Default for Assertion {
+    fn default() -> Self {
+        Assertion {
+            kind: AssertionKind::Status,
+            target: String::new(),
+            expected: "2xx".to_string(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractorSource {
+    #[default]
+    JsonPath,
+    Regex,
+    Header,
+    Cookie,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Extractor {
+    pub source: ExtractorSource,
+    pub expression: String,
+    pub variable: String,
+    pub enabled: bool,
+}
+
+impl Default for Extractor {
+    fn default() -> Self {
+        Extractor {
+            source: ExtractorSource::JsonPath,
+            expression: String::new(),
+            variable: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Extraction {
+    pub variable: String,
+    pub value: Option<String>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum RequestBody {
+    #[default]
+    None,
+    Raw {
+        content_type: String,
+        text: String,
+    },
+    UrlEncoded {
+        fields: Vec<KeyValue>,
+    },
+    Multipart {
+        parts: Vec<MultipartPart>,
+    },
+    Binary {
+        path: String,
+        content_type: String,
+    },
+    GraphQl {
+        query: String,
+        variables: String,
+        operation_name: String,
+    },
+    JsonRpc {
+        calls: Vec<JsonRpcCall>,
+        batch: bool,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcCall {
+    pub method: String,
+    pub params: String,
+    #[serde(default)]
+    pub notification: bool,
+    #[serde(default)]
+    pub id: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcOutcome {
+    pub id: Option<u64>,
+    pub method: String,
+    pub result: Option<String>,
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartKind {
+    #[default]
+    Text,
+    File,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    pub kind: PartKind,
+    pub value: String,
+    pub content_type: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -34,8 +195,83 @@ pub struct CompletedRequestArgs {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ResponseState {
     pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
     pub body: String,
     pub response_time: f64,
+    #[serde(default)]
+    pub certificates: Vec<CertificateInfo>,
+    #[serde(default)]
+    pub events: Vec<StreamEvent>,
+    #[serde(default)]
+    pub json_rpc: Vec<JsonRpcOutcome>,
+    #[serde(default)]
+    pub wire: Option<WireExchange>,
+    #[serde(default)]
+    pub tests: Vec<AssertionResult>,
+    #[serde(default)]
+    pub console: Vec<String>,
+    #[serde(default)]
+    pub extracted: Vec<Extraction>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    Head,
+    ChunkSize,
+    Data,
+    Trailers,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WireSegment {
+    pub kind: SegmentKind,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WireExchange {
+    pub request: String,
+    pub response: Vec<WireSegment>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RawRequest {
+    pub target: String,
+    pub text: String,
+    pub fix_content_length: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendRawRequestArgs {
+    pub id: u64,
+    pub args: RawRequest,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    pub expired: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub host: String,
+    pub client_cert_path: String,
+    pub client_key_path: String,
+    pub pkcs12_path: String,
+    pub pkcs12_password: String,
+    pub ca_cert_paths: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveTlsConfigsArgs {
+    pub configs: Vec<TlsConfig>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -43,3 +279,796 @@ pub struct HighlightArgs<'a> {
     pub code: &'a str,
     pub lang: &'a str,
 }
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JwtSource {
+    pub label: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FindJwtsArgs {
+    pub sources: Vec<JwtSource>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecodedJwt {
+    pub source: String,
+    pub token: String,
+    pub header: serde_json::Value,
+    pub payload: serde_json::Value,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub expires_in: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VerifyJwtArgs {
+    pub token: String,
+    pub key: String,
+    pub key_path: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifyJwtResult {
+    pub valid: bool,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MintJwtArgs {
+    pub algorithm: String,
+    pub key: String,
+    pub key_path: String,
+    pub claims: String,
+    pub expires_in: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub no_proxy: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProxySettings {
+    pub proxy: ProxyConfig,
+    pub use_system: bool,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        ProxySettings {
+            proxy: ProxyConfig::default(),
+            use_system: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveProxySettingsArgs {
+    pub settings: ProxySettings,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub variables: Vec<KeyValue>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Environments {
+    pub environments: Vec<Environment>,
+    pub active: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveEnvironmentsArgs {
+    pub environments: Environments,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SetVariableArgs {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum RequestProxy {
+    #[default]
+    Inherit,
+    Direct,
+    Custom(ProxyConfig),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphQlSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: Vec<GraphQlType>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphQlType {
+    pub name: String,
+    pub kind: String,
+    pub description: Option<String>,
+    pub fields: Vec<GraphQlField>,
+    pub enum_values: Vec<String>,
+    pub possible_types: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphQlField {
+    pub name: String,
+    pub description: Option<String>,
+    pub type_name: String,
+    pub named_type: String,
+    pub required: bool,
+    pub args: Vec<GraphQlField>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IntrospectArgs {
+    pub url: String,
+    pub headers: Vec<KeyValue>,
+    pub proxy: RequestProxy,
+    pub refresh: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GraphQlOperationsArgs {
+    pub query: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidateGraphQlArgs {
+    pub url: String,
+    pub query: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WsConnectArgs {
+    pub url: String,
+    pub headers: Vec<KeyValue>,
+    pub protocols: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsDirection {
+    Sent,
+    Received,
+    Info,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WsMessage {
+    pub direction: WsDirection,
+    pub kind: String,
+    pub data: String,
+    pub timestamp: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsEvent {
+    pub session_id: u64,
+    pub connected: bool,
+    pub message: WsMessage,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsFrameKind {
+    #[default]
+    Text,
+    Binary,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WsSendArgs {
+    pub session_id: u64,
+    pub kind: WsFrameKind,
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WsPingArgs {
+    pub session: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WsCloseArgs {
+    pub session_id: u64,
+    pub code: u16,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct WsSession {
+    pub url: String,
+    pub headers: Vec<KeyValue>,
+    pub protocols: Vec<String>,
+    pub messages: Vec<WsMessage>,
+    pub created_at: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamEvent {
+    pub timestamp: String,
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamMessage {
+    pub stream_id: u64,
+    pub event: StreamEvent,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SendRequestArgs {
+    pub id: u64,
+    pub args: RequestState,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CancelRequestArgs {
+    pub id: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GrpcSource {
+    #[default]
+    Reflection,
+    ProtoFiles {
+        paths: Vec<String>,
+        include_paths: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GrpcServicesArgs {
+    pub url: String,
+    pub source: GrpcSource,
+    pub metadata: Vec<KeyValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct GrpcMethod {
+    pub name: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+    pub input_type: String,
+    pub output_type: String,
+    pub template: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct GrpcService {
+    pub name: String,
+    pub methods: Vec<GrpcMethod>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GrpcCallArgs {
+    pub url: String,
+    pub source: GrpcSource,
+    pub service: String,
+    pub method: String,
+    pub metadata: Vec<KeyValue>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GrpcCallRequest {
+    pub id: u64,
+    pub args: GrpcCallArgs,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct GrpcResponse {
+    pub messages: Vec<String>,
+    pub status_code: i32,
+    pub status: String,
+    pub status_message: String,
+    pub details: Vec<String>,
+    pub metadata: Vec<KeyValue>,
+    pub trailers: Vec<KeyValue>,
+    pub response_time: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GrpcMessageEvent {
+    pub call_id: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CollectionRequest {
+    pub name: String,
+    pub folder: String,
+    pub request: RequestState,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: Option<i64>,
+    pub name: String,
+    pub requests: Vec<CollectionRequest>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveCollectionArgs {
+    pub collection: Collection,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DeleteCollectionArgs {
+    pub id: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RunOptions {
+    pub collection_id: i64,
+    pub folder: String,
+    pub request: String,
+    pub delay_ms: u64,
+    pub stop_on_failure: bool,
+    pub iterations: u32,
+    pub data: Vec<DataRow>,
+    pub update_snapshots: bool,
+}
+
+/// One row of a data file, by column name.
+pub type DataRow = BTreeMap<String, String>;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LoadDataFileArgs {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RunCollectionArgs {
+    pub id: u64,
+    pub options: RunOptions,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunResult {
+    pub iteration: u32,
+    pub name: String,
+    pub folder: String,
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub response_time: f64,
+    pub tests: Vec<AssertionResult>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub snapshot: Option<SnapshotCheck>,
+}
+
+impl RunResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+            && self.tests.iter().all(|t| t.passed)
+            && self.snapshot.as_ref().is_none_or(SnapshotCheck::passed)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub requests: usize,
+    pub failed_requests: usize,
+    pub assertions: usize,
+    pub failed_assertions: usize,
+    pub total_time: f64,
+    pub average_time: f64,
+    pub stopped: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    pub id: i64,
+    pub collection: String,
+    pub folder: String,
+    pub iterations: u32,
+    pub started_at: String,
+    pub finished_at: String,
+    pub summary: RunSummary,
+    pub results: Vec<RunResult>,
+    #[serde(default)]
+    pub data: Vec<DataRow>,
+}
+
+impl Run {
+    /// Rows of the data file with a failing request, to run again.
+    pub fn failed_rows(&self) -> Vec<DataRow> {
+        let mut failed: Vec<u32> = self
+            .results
+            .iter()
+            .filter(|r| !r.passed())
+            .map(|r| r.iteration)
+            .collect();
+        failed.dedup();
+        failed
+            .into_iter()
+            .filter_map(|iteration| self.data.get(iteration as usize - 1).cloned())
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RunProgress {
+    pub run_id: u64,
+    pub total: usize,
+    pub result: RunResult,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchOptions {
+    pub requests: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub concurrency: u32,
+    pub rate: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RunBenchmarkArgs {
+    pub id: u64,
+    pub args: RequestState,
+    pub options: BenchOptions,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct LatencyStats {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct HistogramBucket {
+    pub start: f64,
+    pub end: f64,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct StatusCount {
+    pub status: u16,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ErrorCount {
+    pub error: String,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct BenchReport {
+    pub requests: u64,
+    pub failed: u64,
+    pub duration: f64,
+    pub throughput: f64,
+    pub statuses: Vec<StatusCount>,
+    pub errors: Vec<ErrorCount>,
+    pub latency: LatencyStats,
+    pub histogram: Vec<HistogramBucket>,
+    pub cancelled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct BenchProgress {
+    pub requests: u64,
+    pub failed: u64,
+    pub elapsed: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BenchMessage {
+    pub bench_id: u64,
+    pub progress: BenchProgress,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MockRoute {
+    pub name: String,
+    pub enabled: bool,
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<KeyValue>,
+    pub query: Vec<KeyValue>,
+    pub body: String,
+    pub status: u16,
+    pub response_headers: Vec<KeyValue>,
+    pub response_body: String,
+    pub delay_ms: u64,
+}
+
+impl Default for MockRoute {
+    fn default() -> Self {
+        MockRoute {
+            name: String::new(),
+            enabled: true,
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            headers: Vec::new(),
+            query: Vec::new(),
+            body: String::new(),
+            status: 200,
+            response_headers: Vec::new(),
+            response_body: String::new(),
+            delay_ms: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MockSettings {
+    pub port: u16,
+    pub routes: Vec<MockRoute>,
+}
+
+impl Default for MockSettings {
+    fn default() -> Self {
+        MockSettings {
+            port: 8787,
+            routes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct MockHit {
+    pub time: String,
+    pub method: String,
+    pub path: String,
+    pub route: Option<String>,
+    pub status: u16,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveMockSettingsArgs {
+    pub settings: MockSettings,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AddMockRouteArgs {
+    pub route: MockRoute,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecorderMode {
+    #[default]
+    Record,
+    Replay,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Recording {
+    pub id: i64,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<KeyValue>,
+    pub request_body: String,
+    pub status: u16,
+    pub response_headers: Vec<KeyValue>,
+    pub response_body: String,
+    pub response_time: f64,
+    pub created_at: String,
+    #[serde(default)]
+    pub replayed: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecorderExchange {
+    pub recording: Recording,
+    pub completed: CompletedRequest,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecorderStatus {
+    pub port: u16,
+    pub mode: RecorderMode,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StartRecorderArgs {
+    pub port: u16,
+    pub mode: RecorderMode,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportRecorderCaArgs {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookReply {
+    pub status: u16,
+    pub headers: Vec<KeyValue>,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookSettings {
+    pub port: u16,
+    pub path: String,
+    pub reply: WebhookReply,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            port: 8989,
+            path: "/".to_string(),
+            reply: WebhookReply {
+                status: 200,
+                headers: Vec::new(),
+                body: String::new(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub time: String,
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<KeyValue>,
+    pub body: String,
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SaveWebhookSettingsArgs {
+    pub settings: WebhookSettings,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CapturedRequestArgs {
+    pub captured: CapturedRequest,
+    pub origin: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffSide {
+    pub status: u16,
+    pub headers: Vec<KeyValue>,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DiffOptions {
+    pub ignore: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DiffResponsesArgs {
+    pub left: DiffSide,
+    pub right: DiffSide,
+    pub options: DiffOptions,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub left: Option<serde_json::Value>,
+    pub right: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineTag {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub tag: LineTag,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BodyDiff {
+    Json { changes: Vec<JsonChange> },
+    Text { lines: Vec<DiffLine> },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeaderChange {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResponseDiff {
+    pub left_status: u16,
+    pub right_status: u16,
+    pub headers: Vec<HeaderChange>,
+    pub body: BodyDiff,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: i64,
+    pub collection_id: i64,
+    pub folder: String,
+    pub name: String,
+    pub response: DiffSide,
+    pub ignore: Vec<String>,
+    pub approved_at: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotCheck {
+    pub diff: Option<ResponseDiff>,
+    pub received: Option<DiffSide>,
+    #[serde(default)]
+    pub updated: bool,
+}
+
+impl SnapshotCheck {
+    pub fn passed(&self) -> bool {
+        self.updated || self.diff.as_ref().is_none_or(ResponseDiff::is_empty)
+    }
+}
+
+impl ResponseDiff {
+    pub fn is_empty(&self) -> bool {
+        self.left_status == self.right_status
+            && self.headers.is_empty()
+            && match &self.body {
+                BodyDiff::Json { changes } => changes.is_empty(),
+                BodyDiff::Text { lines } => lines.iter().all(|line| line.tag == LineTag::Same),
+            }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GetSnapshotsArgs {
+    pub collection: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ApproveSnapshotArgs {
+    pub snapshot: Snapshot,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DeleteSnapshotArgs {
+    pub id: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FilterJsonArgs {
+    pub body: String,
+    pub filter: String,
+}